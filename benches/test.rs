@@ -1,88 +1,132 @@
 // #![feature(test)]
 #[path = "../src/board.rs"]
+#[allow(dead_code)]
 mod board;
 use board::*;
 #[path = "../src/error.rs"]
+#[allow(dead_code)]
 mod error;
 #[path = "../src/color.rs"]
 mod color;
 use color::{Color};
 #[path = "../src/players.rs"]
+#[allow(dead_code)]
 mod players;
 use players::*;
+#[path = "../src/capture_rules.rs"]
+#[allow(dead_code, unused_imports)]
+mod capture_rules;
+use capture_rules::{CaptureRules};
 #[path = "../src/algo.rs"]
+#[allow(dead_code)]
 mod algo;
 use algo::{get_bot_input};
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
 extern crate criterion;
 use criterion::{criterion_group, criterion_main, Criterion};
 #[path = "../src/heuristic.rs"]
+#[allow(unused_imports)]
 mod heuristic;
 #[path = "../src/matching_cases.rs"]
 mod matching_cases;
+#[path = "../src/rules.rs"]
+#[allow(dead_code)]
+mod rules;
+#[path = "../src/leakser.rs"]
+#[allow(dead_code)]
+mod leakser;
+#[path = "../src/clock.rs"]
+#[allow(dead_code, unused_imports)]
+mod clock;
+#[path = "../src/notation.rs"]
+#[allow(dead_code)]
+mod notation;
+#[path = "../src/ponder.rs"]
+#[allow(dead_code, unused_imports)]
+mod ponder;
+#[path = "../src/game_record.rs"]
+#[allow(dead_code, unused_imports)]
+mod game_record;
+#[path = "../src/opening_move.rs"]
+#[allow(dead_code)]
+mod opening_move;
+#[path = "../src/position.rs"]
+#[allow(dead_code, unused_imports)]
+mod position;
+#[path = "../src/psq.rs"]
+#[allow(dead_code, unused_imports)]
+mod psq;
+#[path = "../src/network.rs"]
+#[allow(dead_code)]
+mod network;
 
 const BENCHMARK_SIZE: usize = 19;
 const BENCHMARK_TOTAL_TILES: usize = BENCHMARK_SIZE * BENCHMARK_SIZE;
 const ALIGNEMENT_NB: usize = 5;
 const CAPTURE_RANGE: usize = 2;
 const CAPTURE_NB: usize = 10;
+const CAPTURE_RULES: CaptureRules = CaptureRules::new(CAPTURE_RANGE, true, true, true, true);
+const STONES_PER_TURN: usize = 1;
+const MINMAX_DEPTH: usize = 5;
 
 
 fn criterion_benchmark(c: &mut Criterion) {
     c.bench_function("Algo piece start", |b| {
-        let mut board: Board = Board::new(BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RANGE);
+        let mut board: Board = Board::new(BENCHMARK_SIZE, BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RULES);
         let player1 = Player::new(Color::Black, PlayerType::Bot(Algorithm::basic_algorithm()));
         let player2 = Player::new(Color::White, PlayerType::Bot(Algorithm::basic_algorithm()));
-        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RANGE);
+        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RULES, STONES_PER_TURN);
         let _ = board.add_value(board.get_input(0), &mut players);
         b.iter(|| {
             let new_players = players.clone();
-            get_bot_input(&new_players, &board, &None);
+            get_bot_input(new_players, &board, &None, MINMAX_DEPTH, &Arc::new(AtomicBool::new(false)));
         });
     });
 
     c.bench_function("Algo piece center", |b| {
-        let mut board: Board = Board::new(BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RANGE);
+        let mut board: Board = Board::new(BENCHMARK_SIZE, BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RULES);
         let player1 = Player::new(Color::Black, PlayerType::Bot(Algorithm::basic_algorithm()));
         let player2 = Player::new(Color::White, PlayerType::Bot(Algorithm::basic_algorithm()));
-        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RANGE);
+        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RULES, STONES_PER_TURN);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2), &mut players);
         b.iter(|| {
             let new_players = players.clone();
-            get_bot_input(&new_players, &board, &None);
+            get_bot_input(new_players, &board, &None, MINMAX_DEPTH, &Arc::new(AtomicBool::new(false)));
         });
     });
 
     c.bench_function("Algo piece end", |b| {
-        let mut board: Board = Board::new(BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RANGE);
+        let mut board: Board = Board::new(BENCHMARK_SIZE, BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RULES);
         let player1 = Player::new(Color::Black, PlayerType::Bot(Algorithm::basic_algorithm()));
         let player2 = Player::new(Color::White, PlayerType::Bot(Algorithm::basic_algorithm()));
-        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RANGE);
+        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RULES, STONES_PER_TURN);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES - 1), &mut players);
         b.iter(|| {
             let new_players = players.clone();
-            get_bot_input(&new_players, &board, &None);
+            get_bot_input(new_players, &board, &None, MINMAX_DEPTH, &Arc::new(AtomicBool::new(false)));
         });
     });
 
     c.bench_function("Algo 3 pieces stacked", |b| {
-        let mut board: Board = Board::new(BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RANGE);
+        let mut board: Board = Board::new(BENCHMARK_SIZE, BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RULES);
         let player1 = Player::new(Color::Black, PlayerType::Bot(Algorithm::basic_algorithm()));
         let player2 = Player::new(Color::White, PlayerType::Bot(Algorithm::basic_algorithm()));
-        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RANGE);
+        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RULES, STONES_PER_TURN);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2), &mut players);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 + 1), &mut players);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 - 1), &mut players);
         b.iter(|| {
             let new_players = players.clone();
-            get_bot_input(&new_players, &board, &None);
+            get_bot_input(new_players, &board, &None, MINMAX_DEPTH, &Arc::new(AtomicBool::new(false)));
         });
     });
 
     c.bench_function("Algo 6 pieces stacked", |b| {
-        let mut board: Board = Board::new(BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RANGE);
+        let mut board: Board = Board::new(BENCHMARK_SIZE, BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RULES);
         let player1 = Player::new(Color::Black, PlayerType::Bot(Algorithm::basic_algorithm()));
         let player2 = Player::new(Color::White, PlayerType::Bot(Algorithm::basic_algorithm()));
-        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RANGE);
+        let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RULES, STONES_PER_TURN);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2), &mut players);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 + 1), &mut players);
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 - 1), &mut players);
@@ -91,15 +135,15 @@ fn criterion_benchmark(c: &mut Criterion) {
         let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 - 1 + BENCHMARK_SIZE), &mut players);
         b.iter(|| {
             let new_players = players.clone();
-            get_bot_input(&new_players, &board, &None);
+            get_bot_input(new_players, &board, &None, MINMAX_DEPTH, &Arc::new(AtomicBool::new(false)));
         });
     });
 
     // c.bench_function("Algo 6 pieces non stacked", |b| {
-    //     let mut board: Board = Board::new(BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RANGE);
+    //     let mut board: Board = Board::new(BENCHMARK_SIZE, BENCHMARK_SIZE, ALIGNEMENT_NB, CAPTURE_RULES);
     //     let player1 = Player::new(Color::Black, PlayerType::Bot);
     //     let player2 = Player::new(Color::White, PlayerType::Bot);
-    //     let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RANGE);
+    //     let mut players = Players::new(player1, player2, CAPTURE_NB, CAPTURE_RULES, STONES_PER_TURN);
     //     let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2), &mut players);
     //     let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 + 8), &mut players);
     //     let _ = board.add_value(board.get_input(BENCHMARK_TOTAL_TILES / 2 - 8), &mut players);
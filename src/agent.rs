@@ -0,0 +1,492 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::board::{Board, Input};
+use crate::color::Color;
+use crate::players::{PlayerType, Players};
+use crate::algo::Tree;
+use crate::game_record::GameRecord;
+use crate::ponder::{Ponder, PonderMode};
+use crate::network::Connection;
+use crate::piskvork::PiskvorkEngine;
+use crate::command::{Command, read_command};
+use crate::error::{EngineError, FlagError, NetworkError};
+
+pub type Trees = (Option<Tree>, Option<Tree>);
+
+/* What an agent is told about the game, with the intersection clicked during this event in the window */
+pub struct GameState<'a> {
+    record: &'a GameRecord,
+    click: Option<Input>
+}
+
+impl<'a> GameState<'a> {
+    pub fn new(record: &'a GameRecord) -> GameState<'a> {
+        GameState {
+            record,
+            click: None
+        }
+    }
+
+    pub fn with_click(record: &'a GameRecord, click: Option<Input>) -> GameState<'a> {
+        GameState {
+            record,
+            click
+        }
+    }
+
+    pub fn get_record(&self) -> &GameRecord {
+        self.record
+    }
+
+    pub fn get_board(&self) -> &Board {
+        self.record.get_board()
+    }
+
+    pub fn get_players(&self) -> &Players {
+        self.record.get_players()
+    }
+
+    pub fn get_color(&self) -> Color {
+        self.get_players().get_current_player().get_player_color()
+    }
+
+    pub fn get_click(&self) -> Option<Input> {
+        self.click
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Limits {
+    depth: usize,
+    timeout: Duration
+}
+
+impl Limits {
+    pub fn new(depth: usize, timeout: Duration) -> Limits {
+        Limits {
+            depth,
+            timeout
+        }
+    }
+
+    pub fn get_depth(&self) -> usize {
+        self.depth
+    }
+
+    pub fn get_timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+pub enum Decision {
+    /* The move, the search trees kept for the next searches and its time when the agent knows it better than the game loop */
+    Play(Input, Box<Trees>, Option<u128>),
+    /* Nothing yet, the agent is asked again on the next event */
+    Pending,
+    Command(Command),
+    /* The agent cannot play anymore, the game stops there */
+    Failed(String)
+}
+
+/* Where the moves of a player come from */
+pub trait Agent {
+    fn choose_move(&mut self, game: &GameState, limits: &Limits) -> Decision;
+
+    /* Called once the other player's move is played, `game` being the position reached */
+    fn opponent_moved(&mut self, _game: &GameState, _input: Input, _time: u128) {}
+
+    /* Called while the other player has to move */
+    fn opponent_thinking(&mut self, _game: &GameState, _limits: &Limits) {}
+}
+
+pub struct TerminalHuman;
+
+impl Agent for TerminalHuman {
+    fn choose_move(&mut self, game: &GameState, _limits: &Limits) -> Decision {
+        match read_command(game.get_color(), game.get_board()) {
+            Ok(Command::Play(input)) => Decision::Play(input, Box::new((None, None)), None),
+            Ok(command) => Decision::Command(command),
+            Err(e) => {
+                println!("{}", e);
+                Decision::Pending
+            }
+        }
+    }
+}
+
+pub struct ClickHuman;
+
+impl Agent for ClickHuman {
+    fn choose_move(&mut self, game: &GameState, _limits: &Limits) -> Decision {
+        match game.get_click() {
+            Some(input) => Decision::Play(input, Box::new((None, None)), None),
+            None => Decision::Pending
+        }
+    }
+}
+
+/* The built-in searches, pondering on the human's time when asked to */
+pub struct BotAgent {
+    ponder: Ponder
+}
+
+impl BotAgent {
+    pub fn new(ponder_mode: Option<PonderMode>) -> BotAgent {
+        BotAgent {
+            ponder: Ponder::new(ponder_mode)
+        }
+    }
+}
+
+impl Agent for BotAgent {
+    fn choose_move(&mut self, game: &GameState, limits: &Limits) -> Decision {
+        let (input, trees) = self.ponder.get_bot_move(game.get_record(), limits.get_depth());
+        Decision::Play(input, Box::new(trees), None)
+    }
+
+    fn opponent_thinking(&mut self, game: &GameState, limits: &Limits) {
+        self.ponder.start(game.get_record(), limits.get_depth());
+    }
+}
+
+/* Plays any legal move, a xorshift seeded with the time is enough for that */
+pub struct RandomAgent {
+    state: u64
+}
+
+impl RandomAgent {
+    pub fn new() -> RandomAgent {
+        let seed = SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_nanos() as u64).unwrap_or(0);
+        RandomAgent {
+            state: seed | 1
+        }
+    }
+
+    fn next(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+}
+
+impl Agent for RandomAgent {
+    fn choose_move(&mut self, game: &GameState, _limits: &Limits) -> Decision {
+        let moves = game.get_board().legal_moves(game.get_players());
+        if moves.is_empty() {
+            return Decision::Failed(format!("{:?} \"{}\" has no legal move", game.get_color(), game.get_color()))
+        }
+        let index = (self.next() % moves.len() as u64) as usize;
+        Decision::Play(moves[index], Box::new((None, None)), None)
+    }
+}
+
+/* A piskvork program, it is given the whole position every turn and never more time than its clock has left */
+pub struct EngineAgent {
+    path: String,
+    engine: Option<PiskvorkEngine>
+}
+
+impl EngineAgent {
+    /* The program is only launched when it has to move, once the board is known */
+    pub fn new(path: &str) -> EngineAgent {
+        EngineAgent {
+            path: path.to_string(),
+            engine: None
+        }
+    }
+
+    pub fn start(path: &str, board: &Board, timeout: Duration) -> Result<EngineAgent, EngineError> {
+        PiskvorkEngine::start(path, board, timeout).map(|engine| EngineAgent {
+            path: path.to_string(),
+            engine: Some(engine)
+        })
+    }
+
+    fn get_move(&mut self, game: &GameState, timeout: Duration) -> Result<Input, EngineError> {
+        if self.engine.is_none() {
+            self.engine = Some(PiskvorkEngine::start(&self.path, game.get_board(), timeout)?);
+        }
+        let engine = self.engine.as_mut().ok_or(EngineError::CannotStart)?;
+        let input = engine.get_move(game.get_board(), game.get_color(), timeout)?;
+        match game.get_board().check_add_value(input, game.get_players()) {
+            Ok(_) => Ok(input),
+            Err(_) => Err(EngineError::IllegalMove)
+        }
+    }
+}
+
+impl Agent for EngineAgent {
+    fn choose_move(&mut self, game: &GameState, limits: &Limits) -> Decision {
+        let timeout = match game.get_record().get_clock().get_time_left(game.get_color()) {
+            Some(left) => limits.get_timeout().min(Duration::from_micros(left as u64)),
+            None => limits.get_timeout()
+        };
+        match self.get_move(game, timeout) {
+            Ok(input) => Decision::Play(input, Box::new((None, None)), None),
+            Err(e) => {
+                self.engine = None;
+                Decision::Failed(format!("{}: {}", self.path, e))
+            }
+        }
+    }
+}
+
+/* The opponent of a network game, the local moves are sent to it as they are played */
+pub struct RemoteAgent {
+    connection: Option<Connection>,
+    interface: Interface,
+    error: Option<NetworkError>
+}
+
+impl RemoteAgent {
+    pub fn new(connection: Option<Connection>, interface: Interface) -> RemoteAgent {
+        RemoteAgent {
+            connection,
+            interface,
+            error: None
+        }
+    }
+}
+
+impl Agent for RemoteAgent {
+    fn choose_move(&mut self, game: &GameState, _limits: &Limits) -> Decision {
+        if let Some(e) = self.error.take() {
+            self.connection = None;
+            return Decision::Failed(e.to_string())
+        }
+        let connection = match self.connection.as_mut() {
+            Some(connection) => connection,
+            None => return Decision::Failed(NetworkError::Disconnected.to_string())
+        };
+        let received = match self.interface {
+            Interface::Graphic => connection.try_receive_move(game.get_record()),
+            Interface::Terminal => {
+                println!("Waiting for your opponent...");
+                Some(connection.receive_move(game.get_record()))
+            },
+            Interface::FullScreen => Some(connection.receive_move(game.get_record()))
+        };
+        match received {
+            Some(Ok((input, time))) => Decision::Play(input, Box::new((None, None)), Some(time)),
+            Some(Err(e)) => {
+                self.connection = None;
+                Decision::Failed(e.to_string())
+            },
+            None => Decision::Pending
+        }
+    }
+
+    fn opponent_moved(&mut self, game: &GameState, input: Input, time: u128) {
+        if let Some(connection) = self.connection.as_mut() {
+            if let Err(e) = connection.send_move(game.get_record(), input, time) {
+                self.error = Some(e);
+            }
+        }
+    }
+}
+
+/* The window is never blocked waiting for a move, the full-screen terminal reads the human's keys itself */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum Interface {
+    Terminal,
+    FullScreen,
+    Graphic
+}
+
+/* The agent of each color, built from the player types and rebuilt when one changes */
+pub struct Agents {
+    interface: Interface,
+    ponder_mode: Option<PonderMode>,
+    connection: Option<Connection>,
+    engines: (Option<String>, Option<String>),
+    black: Option<(PlayerType, Box<dyn Agent>)>,
+    white: Option<(PlayerType, Box<dyn Agent>)>
+}
+
+impl Agents {
+    pub fn new(interface: Interface, ponder_mode: Option<PonderMode>, connection: Option<Connection>, engines: (Option<String>, Option<String>)) -> Agents {
+        Agents {
+            interface,
+            ponder_mode,
+            connection,
+            engines,
+            black: None,
+            white: None
+        }
+    }
+
+    /* An external player must have been given the path of its engine */
+    fn new_agent(&mut self, color: Color, player_type: PlayerType) -> Result<Box<dyn Agent>, FlagError> {
+        let engine = match color {
+            Color::Black => &self.engines.0,
+            Color::White => &self.engines.1
+        };
+        Ok(match (player_type, self.interface) {
+            (PlayerType::Human, Interface::Terminal) => Box::new(TerminalHuman),
+            (PlayerType::Human, _) => Box::new(ClickHuman),
+            (PlayerType::Bot(_), _) => Box::new(BotAgent::new(self.ponder_mode)),
+            (PlayerType::Random, _) => Box::new(RandomAgent::new()),
+            (PlayerType::External, _) => match engine {
+                Some(path) => Box::new(EngineAgent::new(path)),
+                None => return Err(FlagError::FlagNeedValue)
+            },
+            (PlayerType::Remote, interface) => Box::new(RemoteAgent::new(self.connection.take(), interface))
+        })
+    }
+
+    fn get(&mut self, color: Color, players: &Players) -> Result<&mut Box<dyn Agent>, FlagError> {
+        let player_type = players.get_player(color).get_player_type();
+        let current = match color {
+            Color::Black => &self.black,
+            Color::White => &self.white
+        };
+        if current.as_ref().map(|(agent_type, _)| *agent_type) != Some(player_type) {
+            let agent = self.new_agent(color, player_type)?;
+            match color {
+                Color::Black => self.black = Some((player_type, agent)),
+                Color::White => self.white = Some((player_type, agent))
+            }
+        }
+        let slot = match color {
+            Color::Black => &mut self.black,
+            Color::White => &mut self.white
+        };
+        Ok(&mut slot.as_mut().unwrap().1)
+    }
+
+    /* The player to move chooses, the other one being told it is thinking first */
+    pub fn choose_move(&mut self, game: &GameState, limits: &Limits) -> Decision {
+        self.opponent_thinking(game, limits);
+        match self.get(game.get_color(), game.get_players()) {
+            Ok(agent) => agent.choose_move(game, limits),
+            Err(e) => Decision::Failed(format!("{} \"exe:<path>\": {}", game.get_color(), e))
+        }
+    }
+
+    pub fn opponent_thinking(&mut self, game: &GameState, limits: &Limits) {
+        if let Ok(agent) = self.get(game.get_color().get_inverse_color(), game.get_players()) {
+            agent.opponent_thinking(game, limits);
+        }
+    }
+
+    /* Must be called once the move of `color` is played in the record of `game` */
+    pub fn notify_move(&mut self, game: &GameState, color: Color, input: Input, time: u128) {
+        if let Ok(agent) = self.get(color.get_inverse_color(), game.get_players()) {
+            agent.opponent_moved(game, input, time);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Algorithm, Player};
+    use crate::capture_rules::CaptureRules;
+    use crate::clock::TimeControl;
+
+    fn new_record(rows: &str, alignement_nb: usize, black: PlayerType, white: PlayerType) -> GameRecord {
+        let rules = CaptureRules::new(2, true, true, true, true);
+        let board = Board::parse_rows(rows, alignement_nb, rules).unwrap();
+        let players = Players::new(Player::new(Color::Black, black), Player::new(Color::White, white), 10, rules, 1);
+        GameRecord::new(board, players, TimeControl::Unlimited)
+    }
+
+    fn limits() -> Limits {
+        Limits::new(2, Duration::from_secs(1))
+    }
+
+    fn failure(decision: Decision) -> String {
+        match decision {
+            Decision::Failed(message) => message,
+            _ => panic!("the agent did not fail")
+        }
+    }
+
+    #[test]
+    fn graphic_human_waits_for_a_click() {
+        let record = new_record("9/9/9/9/9/9/9/9/9", 5, PlayerType::Human, PlayerType::Human);
+        let mut agents = Agents::new(Interface::Graphic, None, None, (None, None));
+        assert!(matches!(agents.choose_move(&GameState::new(&record), &limits()), Decision::Pending));
+        let decision = agents.choose_move(&GameState::with_click(&record, Some((2, 3))), &limits());
+        assert!(matches!(decision, Decision::Play((2, 3), _, None)));
+    }
+
+    #[test]
+    fn bot_opens_in_the_center() {
+        let record = new_record("9/9/9/9/9/9/9/9/9", 5, PlayerType::Bot(Algorithm::Minimax), PlayerType::Human);
+        let mut agents = Agents::new(Interface::Graphic, None, None, (None, None));
+        assert!(matches!(agents.choose_move(&GameState::new(&record), &limits()), Decision::Play((4, 4), _, None)));
+    }
+
+    #[test]
+    fn external_and_remote_players_need_their_engine_and_connection() {
+        let record = new_record("9/9/9/9/9/9/9/9/9", 5, PlayerType::External, PlayerType::Remote);
+        let mut agents = Agents::new(Interface::Graphic, None, None, (None, Some("./engine".to_string())));
+        assert!(failure(agents.choose_move(&GameState::new(&record), &limits())).contains("exe:<path>"));
+        let mut record = record;
+        record.set_player_type(Color::Black, PlayerType::Human);
+        record.play((4, 4), 0, (None, None)).unwrap();
+        assert_eq!(failure(agents.choose_move(&GameState::new(&record), &limits())), NetworkError::Disconnected.to_string());
+    }
+
+    #[test]
+    fn random_player_fails_on_a_full_board() {
+        let record = new_record("XXO/OOX/XXO", 3, PlayerType::Random, PlayerType::Random);
+        let mut agents = Agents::new(Interface::Terminal, None, None, (None, None));
+        assert!(failure(agents.choose_move(&GameState::new(&record), &limits())).contains("has no legal move"));
+    }
+
+    #[test]
+    fn agent_is_rebuilt_when_the_player_type_changes() {
+        let mut record = new_record("9/9/9/9/9/9/9/9/9", 5, PlayerType::Human, PlayerType::Human);
+        let mut agents = Agents::new(Interface::Graphic, None, None, (None, None));
+        assert!(matches!(agents.choose_move(&GameState::new(&record), &limits()), Decision::Pending));
+        record.set_player_type(Color::Black, PlayerType::Random);
+        match agents.choose_move(&GameState::new(&record), &limits()) {
+            Decision::Play(input, _, None) => assert!(record.get_board().check_add_value(input, record.get_players()).is_ok()),
+            _ => panic!("the random player did not play")
+        }
+        record.set_player_type(Color::Black, PlayerType::Human);
+        assert!(matches!(agents.choose_move(&GameState::new(&record), &limits()), Decision::Pending));
+    }
+
+    /* Answers the columns of the first row in turn, logging what it is told next to itself */
+    #[cfg(unix)]
+    fn stub_engine(name: &str) -> std::path::PathBuf {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = std::env::temp_dir().join(format!("gomoku-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("engine.sh");
+        std::fs::write(&path, "#!/bin/sh\nn=0\nwhile read -r line; do\n  echo \"$line\" >> \"$(dirname \"$0\")/log\"\n  case \"$line\" in\n    START*) echo OK ;;\n    DONE) echo \"MESSAGE thinking\"; echo \"$n,0\"; n=$((n + 1)) ;;\n    END) exit 0 ;;\n  esac\ndone\n").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        path
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn external_engine_moves_are_relayed() {
+        let path = stub_engine("relay");
+        let mut record = new_record("9/9/9/9/9/9/9/9/9", 5, PlayerType::External, PlayerType::Human);
+        let mut agents = Agents::new(Interface::Terminal, None, None, (Some(path.to_string_lossy().to_string()), None));
+        let limits = Limits::new(2, Duration::from_secs(5));
+        for (engine_move, human_move) in [((0, 0), (4, 4)), ((1, 0), (2, 0))].iter() {
+            match agents.choose_move(&GameState::new(&record), &limits) {
+                Decision::Play(input, _, None) => assert_eq!(input, *engine_move),
+                _ => panic!("the engine did not play")
+            }
+            record.play(*engine_move, 0, (None, None)).unwrap();
+            record.play(*human_move, 0, (None, None)).unwrap();
+        }
+        let message = failure(agents.choose_move(&GameState::new(&record), &limits));
+        assert!(message.contains(&EngineError::IllegalMove.to_string()));
+        let log = std::fs::read_to_string(path.with_file_name("log")).unwrap();
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines[0], "START 9");
+        assert!(lines[1].starts_with("INFO timeout_turn "));
+        let last_board = lines.iter().rposition(|x| *x == "BOARD").unwrap();
+        assert_eq!(lines[last_board..last_board + 6], ["BOARD", "0,0,1", "1,0,1", "2,0,2", "4,4,2", "DONE"]);
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
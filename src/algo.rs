@@ -2,21 +2,25 @@ use crate::board::*;
 use crate::players::*;
 use crate::color::*;
 use crate::heuristic::*;
+use crate::rules::update_game_status;
 use std::{
     cmp::{min, max},
     thread,
     fmt,
-    sync::{RwLock, Arc}
+    sync::{RwLock, Arc},
+    sync::atomic::{AtomicBool, Ordering}
 };
 
 const AVERAGE_PRUNNING: i32 = i32::MAX / 27;
 
+/* `score` is the heuristic of the position, `value` what the last search found for the player who chose this move */
 #[derive(Debug, Clone)]
 pub struct Tree {
     data: (Board, Players),
     input: usize,
     children: Vec<Tree>,
-    score: i32
+    score: i32,
+    value: Option<i32>
 }
 
 impl fmt::Display for Tree {
@@ -27,7 +31,7 @@ impl fmt::Display for Tree {
 
 impl Tree {
     fn new(data: (Board, Players), input: usize, default_color: Color) -> Tree {
-        Tree { children: vec![], input, score: heuristic(&data.0, &data.1, default_color), data}
+        Tree { children: vec![], input, score: heuristic(&data.0, &data.1, default_color), value: None, data}
     }
 
     fn push(&mut self, child: Tree) {
@@ -60,15 +64,22 @@ impl Tree {
     }
 
 }
-pub fn get_bot_input(players: Players, board: &Board, calculated_tree: &Option<Tree>, depth: usize) -> (Input, Option<Tree>) {
+/* Once `stop` is set every node returns its own score, the result is then meaningless and must be dropped */
+pub fn get_bot_input(players: Players, board: &Board, calculated_tree: &Option<Tree>, depth: usize, stop: &Arc<AtomicBool>) -> (Input, Option<Tree>) {
     let color = players.get_current_player().get_player_color();
     let lock: Arc<RwLock<bool>> = Arc::new(RwLock::new(false));
 
     if board.get_board().iter().all(|x| x == &Tile::Empty) {
-        return ((board.get_size() / 2, board.get_size() / 2), None)
+        return ((board.get_width() / 2, board.get_height() / 2), None)
     }
     if calculated_tree.is_some() {
-        if let Some(tree) = calculated_tree.as_ref().unwrap().find((&board, &players)) {
+        let calculated = calculated_tree.as_ref().unwrap();
+        let found = if calculated.board() == board && calculated.players() == &players {
+            Some(calculated)
+        } else {
+            calculated.find((board, &players))
+        };
+        if let Some(tree) = found {
             if tree.children.len() > 0 {
                 if let Some(finished_tree) = tree.children.iter().find(|x| x.score == i32::MAX) {
                     return (board.get_input(finished_tree.input), Some(finished_tree.clone()))
@@ -83,12 +94,15 @@ pub fn get_bot_input(players: Players, board: &Board, calculated_tree: &Option<T
                 for i in 0..end {
                     let mut new_tree = tree.children[i].clone();
                     let c_lock = Arc::clone(&lock);
+                    let c_stop = Arc::clone(stop);
                     handle.push(thread::spawn(move || {
+                        let maximizing_player = new_tree.players().get_current_player().get_player_color() == color;
                         let score = match players.get_current_player().get_player_type() {
-                            PlayerType::Bot(Algorithm::Minimax) => minimax((depth - 1, depth), false, i32::MIN, i32::MAX, color, &mut new_tree, &c_lock),
-                            PlayerType::Bot(Algorithm::Pvs) => pvs(&mut new_tree, depth - 1, i32::MIN + 1, i32::MAX, color),
+                            PlayerType::Bot(Algorithm::Minimax) => minimax((depth - 1, depth), maximizing_player, i32::MIN, i32::MAX, color, &mut new_tree, (&c_lock, &c_stop)),
+                            PlayerType::Bot(Algorithm::Pvs) => pvs(&mut new_tree, depth - 1, i32::MIN + 1, i32::MAX, color, &c_stop),
                             _ => unreachable!()
                         };
+                        new_tree.value = Some(score);
                         if score >= AVERAGE_PRUNNING && depth >= 5 {
                             let mut mut_lock = c_lock.write().unwrap();
                             *mut_lock = true;
@@ -121,15 +135,19 @@ pub fn get_bot_input(players: Players, board: &Board, calculated_tree: &Option<T
                 let mut new_board = board.clone();
                 let mut new_players = players.clone();
                 let c_lock = Arc::clone(&lock);
+                let c_stop = Arc::clone(stop);
                 handle.push(thread::spawn(move || {
                     new_board.add_value_checked(input, &mut new_players);
                     new_players.next_player();
+                    update_game_status(&new_board, &mut new_players, color);
+                    let maximizing_player = new_players.get_current_player().get_player_color() == color;
                     let mut tree = Tree::new((new_board, new_players), i, color);
                     let score = match players.get_current_player().get_player_type() {
-                        PlayerType::Bot(Algorithm::Minimax) => minimax((depth - 1, depth), false, i32::MIN, i32::MAX, color, &mut tree, &c_lock),
-                        PlayerType::Bot(Algorithm::Pvs) => pvs(&mut tree, depth - 1, i32::MIN + 1, i32::MAX, color),
-                        _ => minimax((depth - 1, depth), false, i32::MIN, i32::MAX, color, &mut tree, &c_lock)
+                        PlayerType::Bot(Algorithm::Minimax) => minimax((depth - 1, depth), maximizing_player, i32::MIN, i32::MAX, color, &mut tree, (&c_lock, &c_stop)),
+                        PlayerType::Bot(Algorithm::Pvs) => pvs(&mut tree, depth - 1, i32::MIN + 1, i32::MAX, color, &c_stop),
+                        _ => minimax((depth - 1, depth), maximizing_player, i32::MIN, i32::MAX, color, &mut tree, (&c_lock, &c_stop))
                     };
+                    tree.value = Some(score);
                     if score >= AVERAGE_PRUNNING && depth >= 5 {
                         let mut mut_lock = c_lock.write().unwrap();
                         *mut_lock = true;
@@ -153,7 +171,54 @@ pub fn get_bot_input(players: Players, board: &Board, calculated_tree: &Option<T
     (board.get_input(ret.1), ret.2)
 }
 
+/* Replies of the player to move, the ones already in the tree when it reaches this position, best first for that player */
+pub fn predicted_replies(calculated_tree: &Option<Tree>, board: &Board, players: &Players) -> Vec<Input> {
+    let color = players.get_current_player().get_player_color();
+    let found = calculated_tree.as_ref().and_then(|calculated| {
+        if calculated.board() == board && calculated.players() == players {
+            Some(calculated)
+        } else {
+            calculated.find((board, players))
+        }
+    });
+    let mut replies: Vec<(i32, Input)> = match found {
+        Some(tree) if !tree.children.is_empty() => tree.children.iter()
+            .map(|child| (heuristic(child.board(), child.players(), color), board.get_input(child.input)))
+            .collect(),
+        _ => (0..board.get_board().len())
+            .filter(|i| board.get_index(*i) == Tile::Empty)
+            .map(|i| board.get_input(i))
+            .filter(|input| pruning_heuristic(*input, board) && board.check_add_value_algo(*input, players).is_ok())
+            .map(|input| {
+                let mut new_board = board.clone();
+                let mut new_players = *players;
+                new_board.add_value_checked(input, &mut new_players);
+                (heuristic(&new_board, &new_players, color), input)
+            })
+            .collect()
+    };
+    replies.sort_by_key(|reply| std::cmp::Reverse(reply.0));
+    replies.into_iter().map(|(_, input)| input).collect()
+}
+
+/* Value the search gave to the move of the tree returned by `get_bot_input`, for the bot */
+pub fn root_value(calculated_tree: &Option<Tree>) -> Option<i32> {
+    calculated_tree.as_ref().and_then(|tree| tree.value)
+}
+
+/* Line expected after the tree returned by `get_bot_input`, its move first then the best searched reply of each node */
+pub fn principal_variation(calculated_tree: &Option<Tree>) -> Vec<Input> {
+    let mut line = vec![];
+    let mut node = calculated_tree.as_ref();
+    while let Some(tree) = node {
+        line.push(tree.board().get_input(tree.input));
+        node = tree.children.iter().rev().filter(|x| x.value.is_some()).max_by_key(|x| x.value);
+    }
+    line
+}
+
 fn play_everything(tree: &mut Tree, default_color: Color, is_minimax: bool) -> &mut Vec<Tree> {
+    tree.children.iter_mut().for_each(|x| x.value = None);
     for i in 0..tree.board().get_board().len() {
         let input = tree.board().get_input(i);
         if tree.board().get_index(i) == Tile::Empty
@@ -162,9 +227,11 @@ fn play_everything(tree: &mut Tree, default_color: Color, is_minimax: bool) -> &
             && tree.board().check_add_value_algo(input, tree.players()).is_ok() {
             let mut new_board = tree.board().clone();
             let mut new_players = tree.players().clone();
+            let color = new_players.get_current_player().get_player_color();
             new_board.add_value_checked(input, &mut new_players);
-                new_players.next_player();
-                tree.push(Tree::new((new_board, new_players), i, default_color));
+            new_players.next_player();
+            update_game_status(&new_board, &mut new_players, color);
+            tree.push(Tree::new((new_board, new_players), i, default_color));
         }
     }
     if tree.data.1.get_current_player().get_player_color() == default_color {
@@ -177,8 +244,8 @@ fn play_everything(tree: &mut Tree, default_color: Color, is_minimax: bool) -> &
     &mut tree.children
 }
 
-fn minimax(depth: (usize, usize), maximizing_player: bool, mut alpha: i32, mut beta: i32, default_color: Color, tree: &mut Tree, lock: &RwLock<bool>) -> i32 {
-    if depth.0 == 0 || tree.score == i32::MAX || tree.score == i32::MIN || *lock.read().unwrap() {
+fn minimax(depth: (usize, usize), maximizing_player: bool, mut alpha: i32, mut beta: i32, default_color: Color, tree: &mut Tree, cutoff: (&RwLock<bool>, &AtomicBool)) -> i32 {
+    if depth.0 == 0 || tree.score == i32::MAX || tree.score == i32::MIN || *cutoff.0.read().unwrap() || cutoff.1.load(Ordering::Relaxed) {
         return tree.score
     }
     let childs = play_everything(tree, default_color, true);
@@ -190,7 +257,10 @@ fn minimax(depth: (usize, usize), maximizing_player: bool, mut alpha: i32, mut b
                     childs.len()
                 };
         for i in 0..end {
-            value = max(value, minimax((depth.0 - 1, depth.1), false, alpha, beta, default_color, &mut childs[i], lock));
+            let maximizing_child = childs[i].players().get_current_player().get_player_color() == default_color;
+            let child_value = minimax((depth.0 - 1, depth.1), maximizing_child, alpha, beta, default_color, &mut childs[i], cutoff);
+            childs[i].value = Some(child_value);
+            value = max(value, child_value);
             if value >= beta || (value >= AVERAGE_PRUNNING && depth.1 >= 5) {
                 return value
             }
@@ -200,7 +270,10 @@ fn minimax(depth: (usize, usize), maximizing_player: bool, mut alpha: i32, mut b
     } else {
         let mut value: i32 = i32::MAX;
         for i in 0..childs.len() {
-            value = min(value, minimax((depth.0 - 1, depth.1), true, alpha, beta, default_color, &mut childs[i], lock));
+            let maximizing_child = childs[i].players().get_current_player().get_player_color() == default_color;
+            let child_value = minimax((depth.0 - 1, depth.1), maximizing_child, alpha, beta, default_color, &mut childs[i], cutoff);
+            childs[i].value = Some(child_value.saturating_neg());
+            value = min(value, child_value);
             if alpha >= value || (value <= -AVERAGE_PRUNNING && depth.1 >= 5) {
                 return value
             }
@@ -210,25 +283,27 @@ fn minimax(depth: (usize, usize), maximizing_player: bool, mut alpha: i32, mut b
     }
 }
 
-fn pvs(tree: &mut Tree, depth: usize, mut alpha: i32, beta: i32, color: Color) -> i32 {
-    if depth == 0 || tree.score == i32::MAX || tree.score == i32::MIN {
+fn pvs(tree: &mut Tree, depth: usize, mut alpha: i32, beta: i32, color: Color, stop: &AtomicBool) -> i32 {
+    if depth == 0 || tree.score == i32::MAX || tree.score == i32::MIN || stop.load(Ordering::Relaxed) {
         if tree.players().get_current_player().get_player_color() == color {
             return tree.score
         } else {
-            return -tree.score
+            return tree.score.saturating_neg()
         }
     }
-    let childs = play_everything(tree, tree.players().get_current_player().get_player_color().get_inverse_color(), false);
+    let current_color = tree.players().get_current_player().get_player_color();
+    let childs = play_everything(tree, current_color.get_inverse_color(), false);
     for i in 0..childs.len() {
         let mut score;
         if i == 0 {
-            score = -pvs(&mut childs[i], depth - 1, -beta, -alpha, color);
+            score = pvs_child(&mut childs[i], depth - 1, alpha, beta, current_color, color, stop);
         } else {
-            score = -pvs(&mut childs[i], depth - 1, -alpha - 1, -alpha, color);
+            score = pvs_child(&mut childs[i], depth - 1, alpha, alpha + 1, current_color, color, stop);
             if alpha < score && score < beta {
-                score = -pvs(&mut childs[i], depth - 1, -beta, -score, color);
+                score = pvs_child(&mut childs[i], depth - 1, score, beta, current_color, color, stop);
             }
         }
+        childs[i].value = Some(score);
         alpha = max(alpha, score);
         if alpha >= beta {
             break
@@ -236,3 +311,12 @@ fn pvs(tree: &mut Tree, depth: usize, mut alpha: i32, beta: i32, color: Color) -
     }
     return alpha
 }
+
+/* Score of a child seen from the parent's side: the window is only negated when the turn passes */
+fn pvs_child(child: &mut Tree, depth: usize, alpha: i32, beta: i32, parent_color: Color, color: Color, stop: &AtomicBool) -> i32 {
+    if child.players().get_current_player().get_player_color() == parent_color {
+        pvs(child, depth, alpha, beta, color, stop)
+    } else {
+        pvs(child, depth, -beta, -alpha, color, stop).saturating_neg()
+    }
+}
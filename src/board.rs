@@ -1,390 +1,594 @@
-use std::fmt;
-
-use crate::error::PlacementError;
-use crate::color::Color;
-use crate::players::{Players, Player};
-
-pub type Input = (usize, usize);
-
-#[derive(PartialEq, Clone, Debug, Copy, Eq, Ord, PartialOrd, Hash)]
-pub enum Tile {
-    Color(Color),
-    Empty,
-    OutOfBounds
-}
-
-impl fmt::Display for Tile {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            Tile::Empty =>  write!(f, "."),
-            Tile::Color(color) => write!(f, "{}", color),
-            Tile::OutOfBounds => write!(f, ""),
-        }
-    }
-}
-
-#[derive(PartialEq, Clone, Eq, Hash, Debug)]
-pub struct Board {
-    board: Vec<Tile>,
-    board_length: usize,
-    alignement_nb: usize,
-    capture_range: usize
-}
-
-impl Board {
-    pub fn new(size: usize, alignement_nb: usize, capture_range: usize) -> Board {
-        Board {
-            board: vec![Tile::Empty; size * size],
-            board_length: size,
-            alignement_nb,
-            capture_range
-        }
-    }
-
-    pub fn reset(&mut self) {
-        self.board = vec![Tile::Empty; self.get_total_tiles()];
-    }
-
-    pub fn get_board(&self) -> &Vec<Tile> {
-        &self.board
-    }
-
-    pub fn get_size(&self) -> usize {
-        self.board_length
-    }
-
-    pub fn get_alignement_nb(&self) -> usize {
-        self.alignement_nb
-    }
-
-    pub fn get_capture_range(&self) -> usize {
-        self.capture_range
-    }
-
-    pub fn from_input(&self, input: Input) -> usize {
-        input.0 + input.1 * self.get_size()
-    }
-
-    pub fn get_input(&self, i: usize) -> Input {
-        (i % self.get_size(), i / self.get_size())
-    }
-
-    pub fn get_total_tiles(&self) -> usize {
-        self.board_length * self.board_length
-    }
-
-    fn replace(&mut self, input: Input, tile: Tile) {
-        let size = self.get_size();
-        self.board[input.0 + input.1 * size] = tile;
-    }
-
-    pub fn get(&self, input: Input) -> Tile {
-        self.board[input.0 + input.1 * self.get_size()]
-    }
-
-    pub fn get_protected(&self, input: Input) -> Option<&Tile> {
-        self.board.get(input.0 + input.1 * self.get_size())
-    }
-
-    pub fn get_ref(&self, input: Input) -> &Tile {
-        self.board.get(input.0 + input.1 * self.get_size()).unwrap()
-    }
-
-    pub fn get_index(&self, i: usize) -> Tile {
-        self.board[i]
-    }
-
-    pub fn is_finished(&self, player: &Player) -> (bool, Option<Color>) {
-        let mut winner = (false, None);
-        if !self.board.iter().any(|x| *x == Tile::Empty) {
-            winner = (true, None)
-        }
-        for i in 0..self.board.len() {
-            if let Tile::Color(color) = self.board[i] {
-                if let (true, Some(actual_winner)) = self.check_victory(i, color, &self.board[i], color == player.get_player_color()) {
-                    winner = (true, Some(actual_winner));
-                    if actual_winner == player.get_player_color() {
-                        return winner
-                    }
-                }
-            }
-        }
-        winner
-    }
-
-    pub fn check_double_free_three(&self, input: Input, color: Color) -> bool {
-        let mut count: u8 = 0;
-        count += double_free_three_cases(self, input, color, |x, y| (x as i32 + y) as usize, |x, _| x);
-        count += double_free_three_cases(self, input, color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 - y) as usize);
-        if count >= 2 {
-            return true
-        }
-        count += double_free_three_cases(self, input, color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize);
-        if count >= 2 {
-            return true
-        }
-        count += double_free_three_cases(self, input, color, |x, _| x, |x, y| (x as i32 + y) as usize); 
-        if count >= 2 {
-            return true
-        }
-        false
-    }
-
-    pub fn check_add_value(&self, input: Input, players: &Players) -> Result<(), PlacementError> {
-        let color = players.get_current_player().get_player_color();
-        if input.0 > self.get_size() - 1 || input.1 > self.get_size() - 1 {
-            return Err(PlacementError::OutOfBounds)
-        } else if self.get_ref(input) != &Tile::Empty {
-            return Err(PlacementError::NotEmpty)
-        } else if self.check_double_free_three(input, color) {
-            return Err(PlacementError::DoubleFreeThree)
-        }
-        Ok(())
-    }
-
-    pub fn check_add_value_algo(&self, input: Input, players: &Players) -> Result<(), PlacementError> {
-        if self.check_double_free_three(input, players.get_current_player().get_player_color()) {
-            return Err(PlacementError::DoubleFreeThree)
-        }
-        Ok(())
-    }
-
-    pub fn add_value_checked(&mut self, input: Input, players: &mut Players) {
-        let color = players.get_current_player().get_player_color();
-        if self.get_capture_range() != 0 {
-            self.capture(input, players);
-        }
-        self.replace(input, Tile::Color(color));
-    }
-
-    pub fn add_value(&mut self, input: Input, players: &mut Players) -> Result<(), PlacementError> {
-        match self.check_add_value(input, players) {
-            Ok(()) => {
-                self.add_value_checked(input, players);
-                Ok(())
-            },
-            err => err
-        }
-    }
-
-    fn capture(&mut self, input: Input, players: &mut Players) {
-        execute_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, _| x);
-        execute_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, _| x);
-        execute_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 - y) as usize);
-        execute_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize);
-        execute_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 - y) as usize);
-        execute_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize);
-        execute_capture(self, input, players, |x, _|  x, |x, y| (x as i32 - y) as usize);
-        execute_capture(self, input, players, |x, _| x, |x, y| (x as i32 + y) as usize);
-    }
-
-    fn slice(&self, start: usize, length: usize, f_x: fn(usize, usize) -> usize, f_y: fn(usize, usize) -> usize) -> Vec<Tile> {
-        let mut ret =  Vec::with_capacity(length);
-        for i in 0..length {
-            ret.push(self.board[f_x(start, i) + f_y(start, i) * self.get_size()])
-        }
-        ret
-    }
-
-    fn check_victory(&self, i: usize, color: Color, tile: &Tile, captured: bool) -> (bool, Option<Color>) {
-        let is_capture_disabled: bool = self.get_capture_range() == 0;
-        if  i % self.get_size() <= self.get_size() - self.get_alignement_nb() &&
-            self.board[i..i + self.get_alignement_nb()].iter().all(|x| x == tile) &&
-            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, y| (x as i32 + y) as usize, |x, _| x))) {
-            return (true, Some(color))
-        }
-        if  i / self.get_size() <= self.get_size() - self.get_alignement_nb() &&
-            self.slice(i, self.get_alignement_nb(), |start, _| start, |_, x| x).iter().all(|x| x == tile) &&
-            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, _| x , |x, y| (x as i32 + y) as usize))) {
-            return (true, Some(color))
-        }
-        if  i / self.get_size() <= self.get_size() - self.get_alignement_nb() &&
-            i % self.get_size() <= self.get_size() - self.get_alignement_nb() &&
-            self.slice(i, self.get_alignement_nb(), |start, x| start + x, |_, x| x).iter().all(|x| x == tile) &&
-            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize))) {
-            return (true, Some(color))
-        }
-        if  i / self.get_size() <= self.get_size() - self.get_alignement_nb() &&
-            i % self.get_size() >= self.get_alignement_nb() - 1 &&
-            self.slice(i, self.get_alignement_nb(), |start, x| start - x, |_, x| x).iter().all(|x| x == tile) &&
-            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize))) {
-            return (true, Some(color))
-        }
-        (false, None)
-    }
-}
-
-impl fmt::Display for Board {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let legend: String = (0..self.get_size())
-            .collect::<Vec<usize>>()
-            .iter()
-            .map(|x| *x % 10)
-            .fold("".to_string(), |acc, x| format!("{}  {}", acc, x))
-            .to_string()
-            + "\n0 ";
-        let total_tiles_minus_1: usize = self.get_total_tiles() - 1;
-        let my_str: String = self.board.iter().enumerate().fold(legend, |acc, (i, x)| {
-            if i == total_tiles_minus_1 {
-                return format!("{}{}", acc, x);
-            } else if (i + 1) % self.get_size() == 0 {
-                return format!("{}{}\n{} ", acc, x, ((i / self.get_size()) + 1) % 10);
-            } else {
-                return format!("{}{}  ", acc, x);
-            }
-        });
-        write!(f, "{}", my_str)
-    }
-}
-
-/* PRIVATE */
-
-fn cannot_be_captured(
-    board: &Board,
-    input: Input,
-    color: Color,
-    f_x: fn(usize, i32) -> usize,
-    f_y: fn(usize, i32) -> usize
-) -> bool {
-    for i in 0..board.get_alignement_nb() {
-        if f_x(input.0, i as i32) < board.get_size() && f_y(input.1, i as i32) < board.get_size() {
-            if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, y| (x as i32 + y) as usize, |x, _| x) {
-                return false
-            } else if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize) {
-                return false
-            } else if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, _| x, |x, y| (x as i32 + y) as usize) {
-                return false
-            } else if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize) {
-                return false
-            }
-        }
-    }
-    true
-}
-
-fn cannot_be_captured_prime(
-    board: &Board,
-    input: Input,
-    color: Color,
-    f_x: fn(usize, i32) -> usize,
-    f_y: fn(usize, i32) -> usize
-) -> bool {
-    let mut vec: Vec<&Tile> = vec![board.get_ref(input)];
-    for i in 1..=board.get_capture_range() {
-        if f_x(input.0, i as i32) >= board.get_size() || f_y(input.1, i as i32) >= board.get_size() {
-            break;
-        }
-        let tile = board.get_ref((f_x(input.0, i as i32), f_y(input.1, i as i32)));
-        if tile == &Tile::Color(color.get_inverse_color()) || tile == &Tile::Empty {
-            vec.push(tile);
-            break;
-        }
-        vec.push(tile);
-    }
-    vec.reverse();
-    for i in 1..=board.get_capture_range() {
-        if f_x(input.0, -(i as i32)) > board.get_size() - 1 || f_y(input.1, -(i as i32)) > board.get_size() - 1 {
-            break;
-        }
-        let tile = board.get_ref((f_x(input.0, -(i as i32)), f_y(input.1, -(i as i32))));
-        if tile == &Tile::Color(color.get_inverse_color()) || tile == &Tile::Empty {
-            vec.push(tile);
-            break;
-        }
-        vec.push(tile);
-    }
-    if vec.len() < 2 {
-        return true
-    }
-    let mut sorted = vec![vec.pop().unwrap(), vec[0]];
-    sorted.sort();
-    vec = (&vec[1..]).to_vec();
-    if (
-        vec.len(),
-        sorted == vec![&Tile::Color(color.get_inverse_color()), &Tile::Empty]
-    ) == (board.get_capture_range(), true) {
-        return false;
-    } else {
-        return true
-    }
-}
-
-fn execute_capture(
-    board: &mut Board,
-    input: Input,
-    players: &mut Players,
-    f_x: fn(usize, i32) -> usize,
-    f_y: fn(usize, i32) -> usize
-) {
-    if f_x(input.0, (board.get_capture_range() + 1) as i32) > board.get_size() - 1 || f_y(input.1, (board.get_capture_range() + 1) as i32) > board.get_size() - 1 {
-        return
-    }
-    let mut selected_vec: Vec<(Tile, Input)> = Vec::with_capacity(board.get_capture_range() + 1);
-    let color = players.get_current_player().get_player_color();
-    for i in 1..=board.get_capture_range() + 1 {
-        let inp = (f_x(input.0, i as i32), f_y(input.1, i as i32));
-        selected_vec.push((board.get(inp), inp));
-    }
-    match (
-        Tile::Color(color) == selected_vec.pop().unwrap().0,
-        selected_vec.iter().all(|x| x.0 == Tile::Color(color.get_inverse_color()))
-    ) {
-        (true, true) => {
-            for (_, inp) in selected_vec.iter() {
-                board.replace(*inp, Tile::Empty);
-            }
-            players.add_capture(color)
-        },
-        _ => ()
-    }
-}
-
-fn double_free_three_cases(board: &Board, input: Input, color: Color, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize) -> u8 {
-    fn drift(board: &Board, input: Input, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, drift: i32) -> Option<&Tile> {
-        if f_x(input.0, drift) > board.get_size() - 1 || f_y(input.1, drift) > board.get_size() - 1 {
-            None
-        } else {
-            board.get_protected((f_x(input.0, drift), f_y(input.1, drift)))
-        }
-    }
-    match (
-        drift(board, input, f_x, f_y, -4),
-        drift(board, input, f_x, f_y, -3),
-        drift(board, input, f_x, f_y, -2),
-        drift(board, input, f_x, f_y, -1),
-        color,
-        drift(board, input, f_x, f_y, 1),
-        drift(board, input, f_x, f_y, 2),
-        drift(board, input, f_x, f_y, 3),
-        drift(board, input, f_x, f_y, 4),
-    ) {
-        /* .0.23. */
-        (_, _, _, Some(Tile::Empty), Color::Black, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Some(Tile::Empty)) => 1,
-        (_, _, _, Some(Tile::Empty), Color::White, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Some(Tile::Empty)) => 1,
-        /* .32.0. */
-        (Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Some(Tile::Empty), Color::Black, Some(Tile::Empty), _, _, _) => 1,
-        (Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Some(Tile::Empty), Color::White, Some(Tile::Empty), _, _, _) => 1,
-        /* .01.3. */
-        (_, _, _, Some(Tile::Empty), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty)) => 1,
-        (_, _, _, Some(Tile::Empty), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty)) => 1,
-        /* .3.10. */
-        (Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Empty), _, _, _) => 1,
-        (Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Empty), _, _, _) => 1,
-        /* .012. */
-        (_, _, _, Some(Tile::Empty), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Some(Tile::Empty), _) => 1,
-        (_, _, _, Some(Tile::Empty), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Some(Tile::Empty), _) => 1,
-        /* .210. */
-        (_, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Empty), _, _, _) => 1,
-        (_, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Empty), _, _, _) => 1,
-        /* .10.2. */
-        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty), _) => 1,
-        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty), _) => 1,
-        /* .2.01. */
-        (_, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Empty), _, _) => 1,
-        (_, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Empty), _, _) => 1,
-        /* .101. */
-        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Empty), _, _) => 1,
-        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Empty), _, _) => 1,
-        _ => 0
-    }
-}
\ No newline at end of file
+use std::fmt;
+
+use crate::error::PlacementError;
+use crate::color::Color;
+use crate::players::{Players};
+use crate::capture_rules::CaptureRules;
+use crate::notation::{column_name, row_name};
+use crate::leakser::BOARD_LENGTH_LIMIT;
+
+pub type Input = (usize, usize);
+
+#[derive(PartialEq, Clone, Debug, Copy, Eq, Ord, PartialOrd, Hash)]
+pub enum Tile {
+    Color(Color),
+    Empty,
+    OutOfBounds
+}
+
+impl fmt::Display for Tile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tile::Empty =>  write!(f, "."),
+            Tile::Color(color) => write!(f, "{}", color),
+            Tile::OutOfBounds => write!(f, ""),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Eq, Hash, Debug)]
+pub struct Board {
+    board: Vec<Tile>,
+    width: usize,
+    height: usize,
+    alignement_nb: usize,
+    capture_rules: CaptureRules
+}
+
+impl Board {
+    pub fn new(width: usize, height: usize, alignement_nb: usize, capture_rules: CaptureRules) -> Board {
+        Board {
+            board: vec![Tile::Empty; width * height],
+            width,
+            height,
+            alignement_nb,
+            capture_rules
+        }
+    }
+
+    /* Rows from the top separated by '/', X and O for the stones and a number for a run of empty tiles */
+    pub fn format_rows(&self) -> String {
+        self.board.chunks(self.width).map(|row| {
+            let mut text = String::new();
+            let mut empty = 0;
+            for tile in row.iter() {
+                if let Tile::Color(color) = tile {
+                    if empty > 0 {
+                        text += &empty.to_string();
+                        empty = 0;
+                    }
+                    text += &color.to_string();
+                } else {
+                    empty += 1;
+                }
+            }
+            if empty > 0 {
+                text += &empty.to_string();
+            }
+            text
+        }).collect::<Vec<String>>().join("/")
+    }
+
+    /* Rows and runs longer than the biggest board are refused before anything is allocated for them */
+    pub fn parse_rows(rows: &str, alignement_nb: usize, capture_rules: CaptureRules) -> Result<Board, PlacementError> {
+        let mut board: Vec<Tile> = vec![];
+        let mut width = None;
+        if rows.split('/').count() > BOARD_LENGTH_LIMIT {
+            return Err(PlacementError::IncorrectPosition)
+        }
+        for row in rows.split('/') {
+            let mut tiles = vec![];
+            let mut empty = String::new();
+            for c in row.chars() {
+                if c.is_ascii_digit() {
+                    empty.push(c);
+                    continue;
+                }
+                if !empty.is_empty() {
+                    Board::push_empty_run(&mut tiles, &empty)?;
+                    empty.clear();
+                }
+                if tiles.len() >= BOARD_LENGTH_LIMIT {
+                    return Err(PlacementError::IncorrectPosition)
+                }
+                match c.to_ascii_uppercase() {
+                    'X' => tiles.push(Tile::Color(Color::Black)),
+                    'O' => tiles.push(Tile::Color(Color::White)),
+                    _ => return Err(PlacementError::IncorrectPosition)
+                }
+            }
+            if !empty.is_empty() {
+                Board::push_empty_run(&mut tiles, &empty)?;
+            }
+            if tiles.is_empty() || width.unwrap_or(tiles.len()) != tiles.len() {
+                return Err(PlacementError::IncorrectPosition)
+            }
+            width = Some(tiles.len());
+            board.extend(tiles);
+        }
+        let width = width.unwrap_or(0);
+        Ok(Board {
+            height: board.len() / width,
+            board,
+            width,
+            alignement_nb,
+            capture_rules
+        })
+    }
+
+    fn push_empty_run(tiles: &mut Vec<Tile>, run: &str) -> Result<(), PlacementError> {
+        match run.parse::<usize>() {
+            Ok(run) if run <= BOARD_LENGTH_LIMIT - tiles.len() => {
+                tiles.extend(vec![Tile::Empty; run]);
+                Ok(())
+            },
+            _ => Err(PlacementError::IncorrectPosition)
+        }
+    }
+
+    pub fn get_board(&self) -> &Vec<Tile> {
+        &self.board
+    }
+
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn is_in_bounds(&self, input: Input) -> bool {
+        input.0 < self.get_width() && input.1 < self.get_height()
+    }
+
+    pub fn get_alignement_nb(&self) -> usize {
+        self.alignement_nb
+    }
+
+    pub fn get_capture_rules(&self) -> CaptureRules {
+        self.capture_rules
+    }
+
+    pub fn get_capture_range(&self) -> usize {
+        self.capture_rules.get_range()
+    }
+
+    pub fn from_input(&self, input: Input) -> usize {
+        input.0 + input.1 * self.get_width()
+    }
+
+    pub fn get_input(&self, i: usize) -> Input {
+        (i % self.get_width(), i / self.get_width())
+    }
+
+    pub fn get_total_tiles(&self) -> usize {
+        self.width * self.height
+    }
+
+    fn replace(&mut self, input: Input, tile: Tile) {
+        let index = self.from_input(input);
+        self.board[index] = tile;
+    }
+
+    pub fn get(&self, input: Input) -> Tile {
+        self.board[self.from_input(input)]
+    }
+
+    pub fn get_protected(&self, input: Input) -> Option<&Tile> {
+        if !self.is_in_bounds(input) {
+            return None
+        }
+        self.board.get(self.from_input(input))
+    }
+
+    pub fn get_ref(&self, input: Input) -> &Tile {
+        self.board.get(self.from_input(input)).unwrap()
+    }
+
+    pub fn get_index(&self, i: usize) -> Tile {
+        self.board[i]
+    }
+
+    pub fn is_full(&self) -> bool {
+        !self.board.contains(&Tile::Empty)
+    }
+
+    pub fn has_alignement(&self, color: Color, breakable: bool) -> bool {
+        for i in 0..self.board.len() {
+            if self.board[i] == Tile::Color(color) {
+                if let (true, Some(_)) = self.check_victory(i, color, &self.board[i], breakable) {
+                    return true
+                }
+            }
+        }
+        false
+    }
+
+    /* Stones of the first run of `color` long enough to win, breakable or not */
+    pub fn get_alignement(&self, color: Color) -> Option<Vec<Input>> {
+        let directions: [(i32, i32); 4] = [(1, 0), (0, 1), (1, 1), (-1, 1)];
+        for i in 0..self.board.len() {
+            let (x, y) = self.get_input(i);
+            for (dx, dy) in directions.iter() {
+                let line: Vec<Input> = (0..self.get_alignement_nb() as i32)
+                    .map(|k| ((x as i32 + dx * k) as usize, (y as i32 + dy * k) as usize))
+                    .collect();
+                if line.iter().all(|input| self.is_in_bounds(*input) && self.get(*input) == Tile::Color(color)) {
+                    return Some(line)
+                }
+            }
+        }
+        None
+    }
+
+    pub fn check_double_free_three(&self, input: Input, color: Color) -> bool {
+        let mut count: u8 = 0;
+        count += double_free_three_cases(self, input, color, |x, y| (x as i32 + y) as usize, |x, _| x);
+        count += double_free_three_cases(self, input, color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 - y) as usize);
+        if count >= 2 {
+            return true
+        }
+        count += double_free_three_cases(self, input, color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize);
+        if count >= 2 {
+            return true
+        }
+        count += double_free_three_cases(self, input, color, |x, _| x, |x, y| (x as i32 + y) as usize); 
+        if count >= 2 {
+            return true
+        }
+        false
+    }
+
+    pub fn check_add_value(&self, input: Input, players: &Players) -> Result<(), PlacementError> {
+        let color = players.get_current_player().get_player_color();
+        if !self.is_in_bounds(input) {
+            return Err(PlacementError::OutOfBounds)
+        } else if self.get_ref(input) != &Tile::Empty {
+            return Err(PlacementError::NotEmpty)
+        } else if players.get_stones_per_turn() == 1 && players.is_double_three_forbidden() && self.check_double_free_three(input, color) {
+            return Err(PlacementError::DoubleFreeThree)
+        }
+        Ok(())
+    }
+
+    pub fn legal_moves(&self, players: &Players) -> Vec<Input> {
+        (0..self.get_total_tiles())
+            .map(|i| self.get_input(i))
+            .filter(|input| self.check_add_value(*input, players).is_ok())
+            .collect()
+    }
+
+    pub fn has_legal_move(&self, players: &Players) -> bool {
+        (0..self.get_total_tiles()).any(|i| self.check_add_value(self.get_input(i), players).is_ok())
+    }
+
+    pub fn check_add_value_algo(&self, input: Input, players: &Players) -> Result<(), PlacementError> {
+        if players.get_stones_per_turn() == 1 && players.is_double_three_forbidden() && self.check_double_free_three(input, players.get_current_player().get_player_color()) {
+            return Err(PlacementError::DoubleFreeThree)
+        }
+        Ok(())
+    }
+
+    pub fn add_value_checked(&mut self, input: Input, players: &mut Players) {
+        let color = players.get_current_player().get_player_color();
+        if self.get_capture_rules().is_enabled() {
+            self.capture(input, players);
+        }
+        self.replace(input, Tile::Color(color));
+        if self.get_capture_rules().is_enabled() && !self.get_capture_rules().is_safe_flank() {
+            self.capture_flanked(input, players);
+        }
+    }
+
+    pub fn add_value(&mut self, input: Input, players: &mut Players) -> Result<(), PlacementError> {
+        match self.check_add_value(input, players) {
+            Ok(()) => {
+                self.add_value_checked(input, players);
+                Ok(())
+            },
+            err => err
+        }
+    }
+
+    fn capture(&mut self, input: Input, players: &mut Players) {
+        execute_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, _| x);
+        execute_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, _| x);
+        execute_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 - y) as usize);
+        execute_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize);
+        execute_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 - y) as usize);
+        execute_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize);
+        execute_capture(self, input, players, |x, _|  x, |x, y| (x as i32 - y) as usize);
+        execute_capture(self, input, players, |x, _| x, |x, y| (x as i32 + y) as usize);
+    }
+
+    fn capture_flanked(&mut self, input: Input, players: &mut Players) {
+        execute_flanked_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, _| x);
+        execute_flanked_capture(self, input, players, |x, _| x, |x, y| (x as i32 + y) as usize);
+        execute_flanked_capture(self, input, players, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize);
+        execute_flanked_capture(self, input, players, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize);
+    }
+
+    fn slice(&self, start: usize, length: usize, f_x: fn(usize, usize) -> usize, f_y: fn(usize, usize) -> usize) -> Vec<Tile> {
+        let mut ret =  Vec::with_capacity(length);
+        for i in 0..length {
+            ret.push(self.board[f_x(start, i) + f_y(start, i) * self.get_width()])
+        }
+        ret
+    }
+
+    fn check_victory(&self, i: usize, color: Color, tile: &Tile, captured: bool) -> (bool, Option<Color>) {
+        let is_capture_disabled: bool = !self.get_capture_rules().is_enabled() || !self.get_capture_rules().is_break_alignement();
+        if  i % self.get_width() + self.get_alignement_nb() <= self.get_width() &&
+            self.board[i..i + self.get_alignement_nb()].iter().all(|x| x == tile) &&
+            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, y| (x as i32 + y) as usize, |x, _| x))) {
+            return (true, Some(color))
+        }
+        if  i / self.get_width() + self.get_alignement_nb() <= self.get_height() &&
+            self.slice(i, self.get_alignement_nb(), |start, _| start, |_, x| x).iter().all(|x| x == tile) &&
+            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, _| x , |x, y| (x as i32 + y) as usize))) {
+            return (true, Some(color))
+        }
+        if  i / self.get_width() + self.get_alignement_nb() <= self.get_height() &&
+            i % self.get_width() + self.get_alignement_nb() <= self.get_width() &&
+            self.slice(i, self.get_alignement_nb(), |start, x| start + x, |_, x| x).iter().all(|x| x == tile) &&
+            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize))) {
+            return (true, Some(color))
+        }
+        if  i / self.get_width() + self.get_alignement_nb() <= self.get_height() &&
+            i % self.get_width() >= self.get_alignement_nb() - 1 &&
+            self.slice(i, self.get_alignement_nb(), |start, x| start - x, |_, x| x).iter().all(|x| x == tile) &&
+            (is_capture_disabled || (captured || cannot_be_captured(self, self.get_input(i), color, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize))) {
+            return (true, Some(color))
+        }
+        (false, None)
+    }
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let legend: String = (0..self.get_width())
+            .fold("   ".to_string(), |acc, x| format!("{}{:<3}", acc, column_name(x)));
+        let my_str: String = (0..self.get_height()).fold(legend.trim_end().to_string(), |acc, y| {
+            let line: String = (0..self.get_width())
+                .map(|x| self.get((x, y)).to_string())
+                .collect::<Vec<String>>()
+                .join("  ");
+            format!("{}\n{:>2} {}", acc, row_name(y, self.get_height()), line)
+        });
+        write!(f, "{}", my_str)
+    }
+}
+
+/* PRIVATE */
+
+fn cannot_be_captured(
+    board: &Board,
+    input: Input,
+    color: Color,
+    f_x: fn(usize, i32) -> usize,
+    f_y: fn(usize, i32) -> usize
+) -> bool {
+    for i in 0..board.get_alignement_nb() {
+        if board.is_in_bounds((f_x(input.0, i as i32), f_y(input.1, i as i32))) {
+            if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, y| (x as i32 + y) as usize, |x, _| x) {
+                return false
+            } else if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, y| (x as i32 + y) as usize, |x, y| (x as i32 + y) as usize) {
+                return false
+            } else if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, _| x, |x, y| (x as i32 + y) as usize) {
+                return false
+            } else if !cannot_be_captured_prime(board, (f_x(input.0, i as i32), f_y(input.1, i as i32)), color, |x, y| (x as i32 - y) as usize, |x, y| (x as i32 + y) as usize) {
+                return false
+            }
+        }
+    }
+    true
+}
+
+fn cannot_be_captured_prime(
+    board: &Board,
+    input: Input,
+    color: Color,
+    f_x: fn(usize, i32) -> usize,
+    f_y: fn(usize, i32) -> usize
+) -> bool {
+    let (run, ends) = get_run(board, input, color, f_x, f_y);
+    !(board.get_capture_rules().is_capturable(run.len()) && ends == vec![Tile::Color(color.get_inverse_color()), Tile::Empty])
+}
+
+/* Stones of `color` aligned with `input` (included) and the sorted tiles closing the run */
+fn get_run(
+    board: &Board,
+    input: Input,
+    color: Color,
+    f_x: fn(usize, i32) -> usize,
+    f_y: fn(usize, i32) -> usize
+) -> (Vec<Input>, Vec<Tile>) {
+    let mut run: Vec<Input> = vec![input];
+    let mut ends: Vec<Tile> = Vec::with_capacity(2);
+    for sign in [1, -1].iter() {
+        let mut end = Tile::OutOfBounds;
+        for i in 1..=board.get_capture_range() {
+            let inp = (f_x(input.0, sign * i as i32), f_y(input.1, sign * i as i32));
+            if !board.is_in_bounds(inp) {
+                break;
+            }
+            let tile = board.get(inp);
+            if tile != Tile::Color(color) {
+                end = tile;
+                break;
+            }
+            run.push(inp);
+        }
+        ends.push(end);
+    }
+    ends.sort();
+    (run, ends)
+}
+
+fn execute_capture(
+    board: &mut Board,
+    input: Input,
+    players: &mut Players,
+    f_x: fn(usize, i32) -> usize,
+    f_y: fn(usize, i32) -> usize
+) {
+    let color = players.get_current_player().get_player_color();
+    let mut selected_vec: Vec<Input> = Vec::with_capacity(board.get_capture_range());
+    for i in 1..=board.get_capture_range() + 1 {
+        let inp = (f_x(input.0, i as i32), f_y(input.1, i as i32));
+        if !board.is_in_bounds(inp) {
+            return
+        }
+        match board.get(inp) {
+            Tile::Color(tile_color) if tile_color == color => break,
+            Tile::Color(_) => selected_vec.push(inp),
+            _ => return
+        }
+    }
+    if board.get_capture_rules().is_capturable(selected_vec.len()) {
+        for inp in selected_vec.iter() {
+            board.replace(*inp, Tile::Empty);
+        }
+        players.add_capture(color, selected_vec.len())
+    }
+}
+
+fn execute_flanked_capture(
+    board: &mut Board,
+    input: Input,
+    players: &mut Players,
+    f_x: fn(usize, i32) -> usize,
+    f_y: fn(usize, i32) -> usize
+) {
+    let color = players.get_current_player().get_player_color();
+    if board.get(input) != Tile::Color(color) {
+        return
+    }
+    let (run, ends) = get_run(board, input, color, f_x, f_y);
+    let opponent = Tile::Color(color.get_inverse_color());
+    if board.get_capture_rules().is_capturable(run.len()) && ends == vec![opponent, opponent] {
+        for inp in run.iter() {
+            board.replace(*inp, Tile::Empty);
+        }
+        players.add_capture(color.get_inverse_color(), run.len())
+    }
+}
+
+fn double_free_three_cases(board: &Board, input: Input, color: Color, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize) -> u8 {
+    fn drift(board: &Board, input: Input, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, drift: i32) -> Option<&Tile> {
+        if !board.is_in_bounds((f_x(input.0, drift), f_y(input.1, drift))) {
+            None
+        } else {
+            board.get_protected((f_x(input.0, drift), f_y(input.1, drift)))
+        }
+    }
+    match (
+        drift(board, input, f_x, f_y, -4),
+        drift(board, input, f_x, f_y, -3),
+        drift(board, input, f_x, f_y, -2),
+        drift(board, input, f_x, f_y, -1),
+        color,
+        drift(board, input, f_x, f_y, 1),
+        drift(board, input, f_x, f_y, 2),
+        drift(board, input, f_x, f_y, 3),
+        drift(board, input, f_x, f_y, 4),
+    ) {
+        /* .0.23. */
+        (_, _, _, Some(Tile::Empty), Color::Black, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Some(Tile::Empty)) => 1,
+        (_, _, _, Some(Tile::Empty), Color::White, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Some(Tile::Empty)) => 1,
+        /* .32.0. */
+        (Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Some(Tile::Empty), Color::Black, Some(Tile::Empty), _, _, _) => 1,
+        (Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Some(Tile::Empty), Color::White, Some(Tile::Empty), _, _, _) => 1,
+        /* .01.3. */
+        (_, _, _, Some(Tile::Empty), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty)) => 1,
+        (_, _, _, Some(Tile::Empty), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty)) => 1,
+        /* .3.10. */
+        (Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Empty), _, _, _) => 1,
+        (Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Empty), _, _, _) => 1,
+        /* .012. */
+        (_, _, _, Some(Tile::Empty), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Some(Tile::Empty), _) => 1,
+        (_, _, _, Some(Tile::Empty), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Some(Tile::Empty), _) => 1,
+        /* .210. */
+        (_, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Empty), _, _, _) => 1,
+        (_, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Empty), _, _, _) => 1,
+        /* .10.2. */
+        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty), _) => 1,
+        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty), _) => 1,
+        /* .2.01. */
+        (_, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Some(Tile::Empty), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Empty), _, _) => 1,
+        (_, Some(Tile::Empty), Some(Tile::Color(Color::White)), Some(Tile::Empty), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Empty), _, _) => 1,
+        /* .101. */
+        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::Black)), Color::Black, Some(Tile::Color(Color::Black)), Some(Tile::Empty), _, _) => 1,
+        (_, _, Some(Tile::Empty), Some(Tile::Color(Color::White)), Color::White, Some(Tile::Color(Color::White)), Some(Tile::Empty), _, _) => 1,
+        _ => 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Player, PlayerType};
+
+    const RULES: CaptureRules = CaptureRules::new(2, true, true, true, true);
+
+    #[test]
+    fn rows_round_trip() {
+        for rows in ["3/3/3", "X2/1O1/2X", "5XO/O6/7", "XOXOXOXOXO/10"] {
+            let board = Board::parse_rows(rows, 5, RULES).unwrap();
+            assert_eq!(board.format_rows(), rows);
+        }
+        let board = Board::parse_rows("2X4/7/O6", 5, RULES).unwrap();
+        assert_eq!((board.get_width(), board.get_height()), (7, 3));
+        assert_eq!(board.get((2, 0)), Tile::Color(Color::Black));
+        assert_eq!(board.get((0, 2)), Tile::Color(Color::White));
+    }
+
+    #[test]
+    fn rows_longer_than_the_limit_are_refused() {
+        assert_eq!(Board::parse_rows("99999999999999999/3", 5, RULES), Err(PlacementError::IncorrectPosition));
+        assert_eq!(Board::parse_rows("99999999999999999999999/3", 5, RULES), Err(PlacementError::IncorrectPosition));
+        assert_eq!(Board::parse_rows(&format!("{}X", BOARD_LENGTH_LIMIT), 5, RULES), Err(PlacementError::IncorrectPosition));
+        assert_eq!(Board::parse_rows(&vec!["3"; BOARD_LENGTH_LIMIT + 1].join("/"), 5, RULES), Err(PlacementError::IncorrectPosition));
+        assert!(Board::parse_rows(&BOARD_LENGTH_LIMIT.to_string(), 5, RULES).is_ok());
+    }
+
+    #[test]
+    fn rows_of_different_lengths_are_refused() {
+        assert_eq!(Board::parse_rows("3/4", 5, RULES), Err(PlacementError::IncorrectPosition));
+        assert_eq!(Board::parse_rows("3//3", 5, RULES), Err(PlacementError::IncorrectPosition));
+        assert_eq!(Board::parse_rows("2Z/3", 5, RULES), Err(PlacementError::IncorrectPosition));
+    }
+
+    fn play(rows: &str, capture_rules: CaptureRules, color: Color, input: Input) -> (String, Players) {
+        let mut board = Board::parse_rows(rows, 5, capture_rules).unwrap();
+        let mut players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, capture_rules, 1);
+        if color == Color::White {
+            players.next_player();
+        }
+        board.add_value(input, &mut players).unwrap();
+        (board.format_rows(), players)
+    }
+
+    #[test]
+    fn captures_follow_the_range() {
+        let exact = CaptureRules::new(3, true, true, true, true);
+        let (rows, players) = play("XOOO1/5", exact, Color::Black, (4, 0));
+        assert_eq!(rows, "X3X/5");
+        assert_eq!(players.get_player(Color::Black).get_player_captured(), 3);
+        assert_eq!(play("XOO2/5", exact, Color::Black, (3, 0)).0, "XOOX1/5");
+        let up_to = CaptureRules::new(3, false, true, true, true);
+        assert_eq!(play("XOO2/5", up_to, Color::Black, (3, 0)).0, "X2X1/5");
+        assert_eq!(play("XOO2/5", CaptureRules::new(0, false, true, true, true), Color::Black, (3, 0)).0, "XOOX1/5");
+    }
+
+    #[test]
+    fn playing_into_a_flank_is_safe_unless_told_otherwise() {
+        let safe = CaptureRules::new(2, true, true, true, true);
+        assert_eq!(play("XO1X1/5", safe, Color::White, (2, 0)).0, "XOOX1/5");
+        let unsafe_flank = CaptureRules::new(2, true, true, true, false);
+        let (rows, players) = play("XO1X1/5", unsafe_flank, Color::White, (2, 0));
+        assert_eq!(rows, "X2X1/5");
+        assert_eq!(players.get_player(Color::Black).get_player_captured(), 2);
+    }
+}
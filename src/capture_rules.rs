@@ -0,0 +1,66 @@
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub struct CaptureRules {
+    range: usize,
+    exact_range: bool,
+    capture_win: bool,
+    break_alignement: bool,
+    safe_flank: bool
+}
+
+impl CaptureRules {
+    pub const fn new(range: usize, exact_range: bool, capture_win: bool, break_alignement: bool, safe_flank: bool) -> CaptureRules {
+        CaptureRules {
+            range,
+            exact_range,
+            capture_win,
+            break_alignement,
+            safe_flank
+        }
+    }
+
+    pub fn get_range(&self) -> usize {
+        self.range
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.range != 0
+    }
+
+    pub fn is_exact_range(&self) -> bool {
+        self.exact_range
+    }
+
+    pub fn is_capture_win(&self) -> bool {
+        self.capture_win
+    }
+
+    pub fn is_break_alignement(&self) -> bool {
+        self.break_alignement
+    }
+
+    pub fn is_safe_flank(&self) -> bool {
+        self.safe_flank
+    }
+
+    pub fn is_capturable(&self, stones: usize) -> bool {
+        if stones == 0 || stones > self.get_range() {
+            return false
+        }
+        !self.is_exact_range() || stones == self.get_range()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exact_range_only_captures_full_runs() {
+        let exact = CaptureRules::new(3, true, true, true, true);
+        assert_eq!((1..=4).map(|x| exact.is_capturable(x)).collect::<Vec<bool>>(), vec![false, false, true, false]);
+        let up_to = CaptureRules::new(3, false, true, true, true);
+        assert_eq!((0..=4).map(|x| up_to.is_capturable(x)).collect::<Vec<bool>>(), vec![false, true, true, true, false]);
+        let disabled = CaptureRules::new(0, false, true, true, true);
+        assert!(!disabled.is_enabled() && !disabled.is_capturable(1));
+    }
+}
@@ -0,0 +1,238 @@
+use std::fmt;
+
+use crate::color::Color;
+
+const MICROS: u128 = 1_000_000;
+/* Part of the remaining time the bot allows itself for one move */
+const MOVES_TO_GO: u128 = 30;
+
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub enum TimeControl {
+    Unlimited,
+    Fischer { base: u128, increment: u128 },
+    Byoyomi { base: u128, period: u128, periods: usize },
+    PerMove(u128)
+}
+
+impl fmt::Display for TimeControl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TimeControl::Unlimited => write!(f, "unlimited"),
+            TimeControl::Fischer { base, increment } => write!(f, "{}+{}", seconds(*base), seconds(*increment)),
+            TimeControl::Byoyomi { base, period, periods } => write!(f, "{}/{}x{}", seconds(*base), seconds(*period), periods),
+            TimeControl::PerMove(limit) => write!(f, "move:{}", seconds(*limit))
+        }
+    }
+}
+
+fn seconds(us: u128) -> f64 {
+    us as f64 / MICROS as f64
+}
+
+/* Average of the move times in µs, 0 when there is none */
+pub fn average_time(durations: &[u128]) -> u128 {
+    match durations.len() {
+        0 => 0,
+        len => durations.iter().sum::<u128>() / len as u128
+    }
+}
+
+fn parse_seconds(value: &str) -> Option<u128> {
+    value.parse::<f64>().ok()
+        .filter(|x| *x >= 0.0)
+        .map(|x| (x * MICROS as f64) as u128)
+}
+
+impl TimeControl {
+    /* "300+5" base and increment, "600/30x5" byoyomi periods, "move:10" fixed time per move, all in seconds */
+    pub fn parse(value: &str) -> Option<TimeControl> {
+        if value == "unlimited" {
+            return Some(TimeControl::Unlimited)
+        }
+        if let Some(limit) = value.strip_prefix("move:") {
+            return parse_seconds(limit).filter(|x| *x > 0).map(TimeControl::PerMove)
+        }
+        if let Some((base, increment)) = value.split_once('+') {
+            return match (parse_seconds(base), parse_seconds(increment)) {
+                (Some(base), Some(increment)) if base > 0 => Some(TimeControl::Fischer { base, increment }),
+                _ => None
+            }
+        }
+        if let Some((base, byoyomi)) = value.split_once('/') {
+            let (period, periods) = byoyomi.split_once('x').unwrap_or((byoyomi, "1"));
+            return match (parse_seconds(base), parse_seconds(period), periods.parse::<usize>()) {
+                (Some(base), Some(period), Ok(periods)) if period > 0 && periods > 0 => Some(TimeControl::Byoyomi { base, period, periods }),
+                _ => None
+            }
+        }
+        parse_seconds(value).filter(|x| *x > 0).map(|base| TimeControl::Fischer { base, increment: 0 })
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub struct Clock {
+    time_control: TimeControl,
+    remaining: [u128; 2],
+    periods: [usize; 2]
+}
+
+impl Clock {
+    pub fn new(time_control: TimeControl) -> Clock {
+        let (base, periods) = match time_control {
+            TimeControl::Fischer { base, .. } => (base, 0),
+            TimeControl::Byoyomi { base, periods, .. } => (base, periods),
+            _ => (0, 0)
+        };
+        Clock {
+            time_control,
+            remaining: [base; 2],
+            periods: [periods; 2]
+        }
+    }
+
+    pub fn get_time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.time_control == TimeControl::Unlimited
+    }
+
+    /* Clock of `color` once `elapsed` has been spent on the current move, None when its flag fell */
+    fn after(&self, color: Color, elapsed: u128) -> Option<(u128, usize)> {
+        let remaining = self.remaining[color as usize];
+        let periods = self.periods[color as usize];
+        match self.time_control {
+            TimeControl::Unlimited => Some((remaining, periods)),
+            TimeControl::Fischer { .. } => remaining.checked_sub(elapsed).map(|x| (x, periods)),
+            TimeControl::Byoyomi { period, .. } => match remaining.checked_sub(elapsed) {
+                Some(left) => Some((left, periods)),
+                None => {
+                    let used = ((elapsed - remaining) / period) as usize;
+                    if used >= periods {
+                        None
+                    } else {
+                        Some((0, periods - used))
+                    }
+                }
+            },
+            TimeControl::PerMove(limit) => if elapsed > limit { None } else { Some((remaining, periods)) }
+        }
+    }
+
+    pub fn is_flagged(&self, color: Color, elapsed: u128) -> bool {
+        self.after(color, elapsed).is_none()
+    }
+
+    /* Charges a finished move to `color`, returns false when the time was exceeded */
+    pub fn spend(&mut self, color: Color, elapsed: u128) -> bool {
+        match self.after(color, elapsed) {
+            Some((remaining, periods)) => {
+                let increment = match self.time_control {
+                    TimeControl::Fischer { increment, .. } => increment,
+                    _ => 0
+                };
+                self.remaining[color as usize] = remaining + increment;
+                self.periods[color as usize] = periods;
+                true
+            },
+            None => {
+                self.remaining[color as usize] = 0;
+                self.periods[color as usize] = 0;
+                false
+            }
+        }
+    }
+
+    /* Time the bot may think on this move, None without time control */
+    pub fn get_move_budget(&self, color: Color) -> Option<u128> {
+        let remaining = self.remaining[color as usize];
+        match self.time_control {
+            TimeControl::Unlimited => None,
+            TimeControl::Fischer { increment, .. } => Some((remaining / MOVES_TO_GO + increment * 3 / 4).min(remaining / 2)),
+            TimeControl::Byoyomi { period, .. } => Some(remaining / MOVES_TO_GO + period * 3 / 4),
+            TimeControl::PerMove(limit) => Some(limit * 3 / 4)
+        }
+    }
+
+    /* Time `color` may spend on this move before its flag falls, None without time control */
+    pub fn get_time_left(&self, color: Color) -> Option<u128> {
+        let remaining = self.remaining[color as usize];
+        match self.time_control {
+            TimeControl::Unlimited => None,
+            TimeControl::Fischer { .. } => Some(remaining),
+            TimeControl::Byoyomi { period, .. } => Some(remaining + period * self.periods[color as usize] as u128),
+            TimeControl::PerMove(limit) => Some(limit)
+        }
+    }
+
+    pub fn display(&self, color: Color, elapsed: u128) -> String {
+        let (remaining, periods) = self.after(color, elapsed).unwrap_or((0, 0));
+        let seconds = remaining.div_ceil(MICROS);
+        let time = format!("{}:{:02}", seconds / 60, seconds % 60);
+        match self.time_control {
+            TimeControl::Unlimited => String::new(),
+            TimeControl::Fischer { .. } => time,
+            TimeControl::Byoyomi { period, .. } => format!("{} ({}x{}s)", time, periods, period / MICROS),
+            TimeControl::PerMove(limit) => format!("{}s/move", limit.saturating_sub(elapsed).div_ceil(MICROS))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn time_control_is_written_as_parsed() {
+        for value in ["unlimited", "300+5", "600/30x5", "move:10"].iter() {
+            assert_eq!(TimeControl::parse(value).unwrap().to_string(), *value);
+        }
+        assert_eq!(TimeControl::parse("60"), Some(TimeControl::Fischer { base: 60 * MICROS, increment: 0 }));
+        assert_eq!(TimeControl::parse("600/30"), Some(TimeControl::Byoyomi { base: 600 * MICROS, period: 30 * MICROS, periods: 1 }));
+        for value in ["0+5", "10/0x3", "10/5x0", "move:0", "-1", "soon"].iter() {
+            assert_eq!(TimeControl::parse(value), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn move_budget_follows_the_time_control() {
+        assert_eq!(Clock::new(TimeControl::Unlimited).get_move_budget(Color::Black), None);
+        assert_eq!(Clock::new(TimeControl::parse("300+5").unwrap()).get_move_budget(Color::Black), Some(13_750_000));
+        assert_eq!(Clock::new(TimeControl::parse("6+5").unwrap()).get_move_budget(Color::Black), Some(3_000_000));
+        assert_eq!(Clock::new(TimeControl::parse("move:10").unwrap()).get_move_budget(Color::White), Some(7_500_000));
+        assert_eq!(Clock::new(TimeControl::parse("0/4x2").unwrap()).get_move_budget(Color::White), Some(3_000_000));
+    }
+
+    #[test]
+    fn increment_is_added_after_the_move() {
+        let mut clock = Clock::new(TimeControl::parse("10+2").unwrap());
+        assert!(clock.spend(Color::Black, 3 * MICROS));
+        assert_eq!(clock.get_time_left(Color::Black), Some(9 * MICROS));
+        assert_eq!(clock.get_time_left(Color::White), Some(10 * MICROS));
+        assert!(!clock.spend(Color::White, 11 * MICROS));
+        assert_eq!(clock.get_time_left(Color::White), Some(0));
+    }
+
+    #[test]
+    fn byoyomi_periods_are_used_up() {
+        let mut clock = Clock::new(TimeControl::parse("10/5x3").unwrap());
+        assert!(clock.spend(Color::Black, 12 * MICROS));
+        assert_eq!(clock.get_time_left(Color::Black), Some(15 * MICROS));
+        assert!(clock.spend(Color::Black, 4 * MICROS));
+        assert_eq!(clock.get_time_left(Color::Black), Some(15 * MICROS));
+        assert!(clock.spend(Color::Black, 6 * MICROS));
+        assert_eq!(clock.get_time_left(Color::Black), Some(10 * MICROS));
+        assert!(!clock.is_flagged(Color::Black, 9 * MICROS));
+        assert!(clock.is_flagged(Color::Black, 11 * MICROS));
+        assert!(!clock.spend(Color::Black, 11 * MICROS));
+    }
+
+    #[test]
+    fn per_move_limit_does_not_carry_over() {
+        let mut clock = Clock::new(TimeControl::parse("move:2").unwrap());
+        assert!(clock.spend(Color::White, 2 * MICROS));
+        assert_eq!(clock.get_time_left(Color::White), Some(2 * MICROS));
+        assert!(clock.is_flagged(Color::White, 2 * MICROS + 1));
+    }
+}
@@ -0,0 +1,81 @@
+use std::io::{self, Write};
+
+use crate::board::{Board, Input};
+use crate::color::Color;
+use crate::error::PlacementError;
+use crate::notation::parse_input;
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum Command {
+    Play(Input),
+    Undo,
+    Hint,
+    Eval,
+    Show,
+    Position,
+    Save(String),
+    Load(String),
+    Export(String),
+    Rules,
+    Help,
+    Quit
+}
+
+fn get_argument(args: &[&str]) -> Result<String, PlacementError> {
+    match args {
+        [path] => Ok(path.to_string()),
+        _ => Err(PlacementError::MissingArgument)
+    }
+}
+
+pub fn parse_command(line: &str, board: &Board) -> Result<Command, PlacementError> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    if words.is_empty() {
+        return Err(PlacementError::UnknownCommand);
+    }
+    match words[0].to_lowercase().as_str() {
+        "play" | "p" => Ok(Command::Play(parse_input(&words[1..], board)?)),
+        "undo" | "u" => Ok(Command::Undo),
+        "hint" => Ok(Command::Hint),
+        "eval" => Ok(Command::Eval),
+        "show" => Ok(Command::Show),
+        "position" => Ok(Command::Position),
+        "save" => Ok(Command::Save(get_argument(&words[1..])?)),
+        "load" => Ok(Command::Load(get_argument(&words[1..])?)),
+        "export" => Ok(Command::Export(get_argument(&words[1..])?)),
+        "rules" => Ok(Command::Rules),
+        "help" | "h" => Ok(Command::Help),
+        "quit" | "exit" | "q" => Ok(Command::Quit),
+        _ => match parse_input(&words, board) {
+            Ok(input) => Ok(Command::Play(input)),
+            Err(_) => Err(PlacementError::UnknownCommand)
+        }
+    }
+}
+
+pub fn read_command(player_color: Color, board: &Board) -> Result<Command, PlacementError> {
+    let mut line = String::new();
+    print!("{} > ", player_color);
+    io::stdout().flush().expect("Failed to flush stdout");
+    match io::stdin().read_line(&mut line) {
+        Ok(0) => Ok(Command::Quit),
+        Ok(_) => parse_command(line.trim(), board),
+        Err(_) => Err(PlacementError::IncorrectPlacement)
+    }
+}
+
+pub fn print_commands() {
+    println!("COMMANDS:");
+    println!("\tplay <h8>\tplace a stone, \"<x> <y>\" also works and \"play\" can be omitted");
+    println!("\tundo\t\ttake back your last move");
+    println!("\thint\t\task the bot for a move");
+    println!("\teval\t\tprint the heuristic value of the position");
+    println!("\tshow\t\tprint the players and the board");
+    println!("\tposition\tprint the position string, usable with --position");
+    println!("\tsave <file>\tsave the moves of the game, as a piskvork record if the file ends with .psq");
+    println!("\tload <file>\treplay the moves saved in a file or a piskvork .psq record");
+    println!("\texport <file>\tdraw the position in a .svg or .png image");
+    println!("\trules\t\tdisplay gomoku\'s rules");
+    println!("\thelp\t\tdisplay this list");
+    println!("\tquit\t\tleave the game");
+}
@@ -1,62 +1,151 @@
-use std::fmt;
-use crate::leakser::{
-    BOARD_LENGTH_LIMIT,
-    CAPTURED_NB_LIMIT,
-    MINMAX_DEPTH_LIMIT
-};
-
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub enum PlacementError {
-    OutOfBounds,
-    NotEmpty,
-    IncorrectPlacement,
-    DoubleFreeThree
-}
-
-#[derive(PartialEq, Clone, Copy, Debug)]
-pub enum FlagError {
-    WrongFlag,
-    MapTooBig,
-    CapturedTooBig,
-    RangeTooBig(usize),
-    AlignementTooBig(usize),
-    AlignementTooSmall,
-    MapTooSmall,
-    FlagNeedValue,
-    NoNumberValue,
-    IncorrectValue,
-    PrintRules,
-    PrintHelper,
-    IncorectDepth
-}
-
-impl fmt::Display for PlacementError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            PlacementError::OutOfBounds =>  write!(f, "Out Of Bounds"),
-            PlacementError::NotEmpty => write!(f, "Not Empty"),
-            PlacementError::IncorrectPlacement => write!(f, "Incorrect placement"),
-            PlacementError::DoubleFreeThree => write!(f, "Double Free Three")
-        }
-    }
-}
-
-impl fmt::Display for FlagError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        match self {
-            FlagError::WrongFlag => write!(f, "This flag doesn't exist"),
-            FlagError::MapTooBig => write!(f, "Size must be countained between 3 and {}", BOARD_LENGTH_LIMIT),
-            FlagError::CapturedTooBig => write!(f, "Capture number must be countained between 1 and {}", CAPTURED_NB_LIMIT),
-            FlagError::RangeTooBig(max) => write!(f, "Range must be countained between 0 and {}", max),
-            FlagError::AlignementTooBig(max) => write!(f, "Alignement must be countained between 2 and {}", max),
-            FlagError::AlignementTooSmall => write!(f, "Your alignement number is too small"),
-            FlagError::MapTooSmall => write!(f, "Your map is too small"),
-            FlagError::FlagNeedValue => write!(f, "This flag need a value"),
-            FlagError::NoNumberValue => write!(f, "This is not a number"),
-            FlagError::IncorrectValue => write!(f, "That's an incorrect value"),
-            FlagError::PrintRules => write!(f, ""),
-            FlagError::PrintHelper => write!(f, ""),
-            FlagError::IncorectDepth => write!(f, "Depth must be countained between 1 and {}", MINMAX_DEPTH_LIMIT)
-        }
-    }
-}
+use std::fmt;
+use crate::leakser::{
+    BOARD_LENGTH_LIMIT,
+    CAPTURED_NB_LIMIT,
+    MINMAX_DEPTH_LIMIT
+};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PlacementError {
+    OutOfBounds,
+    NotEmpty,
+    IncorrectPlacement,
+    DoubleFreeThree,
+    UnknownCommand,
+    MissingArgument,
+    NothingToUndo,
+    IncorrectFile,
+    IncorrectPosition,
+    NetworkGame,
+    IncorrectImage,
+    IncorrectMoveNumber,
+    RenLibFile
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum FlagError {
+    WrongFlag,
+    MapTooBig,
+    CapturedTooBig,
+    RangeTooBig(usize),
+    AlignementTooBig(usize),
+    AlignementTooSmall,
+    MapTooSmall,
+    FlagNeedValue,
+    NoNumberValue,
+    IncorrectValue,
+    PrintRules,
+    PrintHelper,
+    IncorectDepth,
+    NotEnoughEngines,
+    MissingImage
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum EngineError {
+    CannotStart,
+    Crashed,
+    Timeout,
+    IncorrectAnswer(String),
+    IllegalMove
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum NetworkError {
+    CannotConnect,
+    CannotListen(u16),
+    Disconnected,
+    OpponentLeft,
+    IncorrectMessage(String),
+    IllegalMove,
+    Desync
+}
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum ProtocolError {
+    IncorrectJson,
+    UnknownCommand(String),
+    MissingField(String),
+    IncorrectField(String),
+    GameFinished
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlacementError::OutOfBounds =>  write!(f, "Out Of Bounds"),
+            PlacementError::NotEmpty => write!(f, "Not Empty"),
+            PlacementError::IncorrectPlacement => write!(f, "Incorrect placement"),
+            PlacementError::DoubleFreeThree => write!(f, "Double Free Three"),
+            PlacementError::UnknownCommand => write!(f, "Unknown command, type \"help\" to list them"),
+            PlacementError::MissingArgument => write!(f, "This command need a file name"),
+            PlacementError::NothingToUndo => write!(f, "Nothing to undo"),
+            PlacementError::IncorrectFile => write!(f, "Cannot read or write this file"),
+            PlacementError::IncorrectPosition => write!(f, "Incorrect position, see --help for its format"),
+            PlacementError::NetworkGame => write!(f, "Moves cannot be taken back in a network game"),
+            PlacementError::IncorrectImage => write!(f, "Images must be .svg or .png files, replays can also be .gif"),
+            PlacementError::IncorrectMoveNumber => write!(f, "The game has no move with this number"),
+            PlacementError::RenLibFile => write!(f, "RenLib .lib files are not supported, export the game as a piskvork .psq record")
+        }
+    }
+}
+
+impl fmt::Display for FlagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagError::WrongFlag => write!(f, "This flag doesn't exist"),
+            FlagError::MapTooBig => write!(f, "Size must be countained between 3 and {}", BOARD_LENGTH_LIMIT),
+            FlagError::CapturedTooBig => write!(f, "Capture number must be countained between 1 and {}", CAPTURED_NB_LIMIT),
+            FlagError::RangeTooBig(max) => write!(f, "Range must be countained between 0 and {}", max),
+            FlagError::AlignementTooBig(max) => write!(f, "Alignement must be countained between 2 and {}", max),
+            FlagError::AlignementTooSmall => write!(f, "Your alignement number is too small"),
+            FlagError::MapTooSmall => write!(f, "Your map is too small"),
+            FlagError::FlagNeedValue => write!(f, "This flag need a value"),
+            FlagError::NoNumberValue => write!(f, "This is not a number"),
+            FlagError::IncorrectValue => write!(f, "That's an incorrect value"),
+            FlagError::PrintRules => write!(f, ""),
+            FlagError::PrintHelper => write!(f, ""),
+            FlagError::IncorectDepth => write!(f, "Depth must be countained between 1 and {}", MINMAX_DEPTH_LIMIT),
+            FlagError::NotEnoughEngines => write!(f, "A tournament needs at least two engines"),
+            FlagError::MissingImage => write!(f, "An export needs --export-image <File> or --replay <File>")
+        }
+    }
+}
+
+impl fmt::Display for EngineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EngineError::CannotStart => write!(f, "The engine cannot be started"),
+            EngineError::Crashed => write!(f, "The engine crashed"),
+            EngineError::Timeout => write!(f, "The engine ran out of time"),
+            EngineError::IncorrectAnswer(answer) => write!(f, "The engine answered \"{}\"", answer),
+            EngineError::IllegalMove => write!(f, "The engine played an illegal move")
+        }
+    }
+}
+
+impl fmt::Display for NetworkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NetworkError::CannotConnect => write!(f, "Cannot reach the other player"),
+            NetworkError::CannotListen(port) => write!(f, "Cannot listen on port {}", port),
+            NetworkError::Disconnected => write!(f, "The connection was lost"),
+            NetworkError::OpponentLeft => write!(f, "Your opponent left the game"),
+            NetworkError::IncorrectMessage(message) => write!(f, "Unexpected message \"{}\"", message),
+            NetworkError::IllegalMove => write!(f, "Your opponent sent an illegal move"),
+            NetworkError::Desync => write!(f, "The two games do not have the same position anymore")
+        }
+    }
+}
+
+impl fmt::Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::IncorrectJson => write!(f, "Each line must be a JSON object with a \"cmd\""),
+            ProtocolError::UnknownCommand(command) => write!(f, "Unknown command \"{}\"", command),
+            ProtocolError::MissingField(field) => write!(f, "\"{}\" is missing", field),
+            ProtocolError::IncorrectField(field) => write!(f, "\"{}\" has an incorrect value", field),
+            ProtocolError::GameFinished => write!(f, "The game is finished")
+        }
+    }
+}
@@ -0,0 +1,566 @@
+use std::fs;
+use std::time::{Duration, Instant};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use crate::board::{Board, Input, Tile};
+use crate::players::{Player, PlayerType, Players};
+use crate::color::Color;
+use crate::algo::{get_bot_input, Tree};
+use crate::opening_move::opening_move;
+use crate::error::PlacementError;
+use crate::rules::{GameStatus, update_game_status};
+use crate::clock::{Clock, TimeControl};
+use crate::notation::{format_input, parse_input};
+use crate::psq::{format_psq, is_psq, is_renlib, parse_psq};
+use crate::capture_rules::CaptureRules;
+
+#[derive(Clone)]
+struct RecordNode {
+    board: Board,
+    players: Players,
+    input: Option<Input>,
+    time: u128,
+    trees: (Option<Tree>, Option<Tree>),
+    clock: Clock,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    selected_child: usize
+}
+
+/* Rough cost of one more depth compared to the previous one */
+const DEPTH_GROWTH: u32 = 5;
+
+pub type BotMove = (Input, (Option<Tree>, Option<Tree>));
+
+/* Color, input, stones it captured and number of moves played from the same position */
+pub type LineMove = (Color, Input, usize, usize);
+
+fn search(board: &Board, players: &Players, trees: &(Option<Tree>, Option<Tree>), depth: usize, stop: &Arc<AtomicBool>) -> BotMove {
+    match players.get_current_player().get_player_color() {
+        Color::Black => {
+            let (bot_input, bot_tree) = get_bot_input(*players, board, &trees.0, depth, stop);
+            (bot_input, (bot_tree, None))
+        },
+        Color::White => {
+            let (bot_input, bot_tree) = get_bot_input(*players, board, &trees.1, depth, stop);
+            (bot_input, (None, bot_tree))
+        }
+    }
+}
+
+/* Bot move in any position, `move_number` being the number of moves already played to reach it, meaningless once `stop` is set */
+pub fn search_bot_move(board: &Board, players: &Players, trees: &(Option<Tree>, Option<Tree>), clock: &Clock, move_number: usize, depth: usize, stop: &Arc<AtomicBool>) -> BotMove {
+    if let Some(o_move) = opening_move(board, players, move_number + 1) {
+        return (o_move, (None, None))
+    }
+    let color = players.get_current_player().get_player_color();
+    let budget = match clock.get_move_budget(color) {
+        Some(budget) => Duration::from_micros(budget as u64),
+        None => return search(board, players, trees, depth, stop)
+    };
+    let start = Instant::now();
+    let mut best = search(board, players, trees, 1, stop);
+    for current_depth in 2..=depth {
+        let last = start.elapsed();
+        if last * DEPTH_GROWTH > budget || stop.load(Ordering::Relaxed) {
+            break;
+        }
+        best = search(board, players, trees, current_depth, stop);
+    }
+    best
+}
+
+/* Every position reached in the game, the first child of a node being its main line */
+pub struct GameRecord {
+    nodes: Vec<RecordNode>,
+    current: usize
+}
+
+impl GameRecord {
+    pub fn new(board: Board, players: Players, time_control: TimeControl) -> GameRecord {
+        GameRecord {
+            nodes: vec![RecordNode {
+                board,
+                players,
+                input: None,
+                time: 0,
+                trees: (None, None),
+                clock: Clock::new(time_control),
+                parent: None,
+                children: vec![],
+                selected_child: 0
+            }],
+            current: 0
+        }
+    }
+
+    pub fn reset(&mut self) {
+        let mut root = self.nodes[0].clone();
+        root.children = vec![];
+        root.selected_child = 0;
+        self.nodes = vec![root];
+        self.current = 0;
+    }
+
+    fn get_current(&self) -> &RecordNode {
+        &self.nodes[self.current]
+    }
+
+    pub fn get_board(&self) -> &Board {
+        &self.get_current().board
+    }
+
+    pub fn get_players(&self) -> &Players {
+        &self.get_current().players
+    }
+
+    pub fn get_trees(&self) -> (&Option<Tree>, &Option<Tree>) {
+        (&self.get_current().trees.0, &self.get_current().trees.1)
+    }
+
+    pub fn get_clock(&self) -> &Clock {
+        &self.get_current().clock
+    }
+
+    pub fn get_last_input(&self) -> Option<&Input> {
+        self.get_current().input.as_ref()
+    }
+
+    pub fn get_move_number(&self) -> usize {
+        self.get_path().len() - 1
+    }
+
+    /* Full turns of the current line, counting every stone one player places in a row as a single move */
+    pub fn get_turn_number(&self) -> usize {
+        let moves = self.get_moves();
+        let player_turns = moves.iter().enumerate().filter(|(i, x)| *i == 0 || moves[i - 1].0 != x.0).count();
+        player_turns.div_ceil(2)
+    }
+
+    /* Moves played since the empty board, the stones of the starting position included */
+    pub fn get_played_moves(&self) -> usize {
+        let root = &self.nodes[0];
+        let stones = root.board.get_board().iter().filter(|x| matches!(x, Tile::Color(_))).count();
+        let captured = root.players.get_player(Color::Black).get_player_captured() + root.players.get_player(Color::White).get_player_captured();
+        self.get_move_number() + stones + captured
+    }
+
+    fn get_path(&self) -> Vec<usize> {
+        let mut path = vec![self.current];
+        while let Some(parent) = self.nodes[*path.last().unwrap()].parent {
+            path.push(parent);
+        }
+        path.reverse();
+        path
+    }
+
+    pub fn get_line(&self) -> Vec<Input> {
+        self.get_path()[1..].iter().filter_map(|x| self.nodes[*x].input).collect()
+    }
+
+    /* The move number of each stone still on the board, indexed like the board */
+    pub fn get_move_numbers(&self) -> Vec<Option<usize>> {
+        let board = self.get_board();
+        let mut numbers = vec![None; board.get_total_tiles()];
+        for (number, (color, input, _)) in self.get_moves().iter().enumerate() {
+            if board.get_ref(*input) == &Tile::Color(*color) {
+                numbers[board.from_input(*input)] = Some(number + 1);
+            }
+        }
+        numbers
+    }
+
+    /* Every move of the current line with the color that played it */
+    pub fn get_moves(&self) -> Vec<(Color, Input, u128)> {
+        self.get_path().windows(2).filter_map(|nodes| {
+            let color = self.nodes[nodes[0]].players.get_current_player().get_player_color();
+            self.nodes[nodes[1]].input.map(|input| (color, input, self.nodes[nodes[1]].time))
+        }).collect()
+    }
+
+    /* The current line followed by the moves `redo` would replay */
+    pub fn get_selected_line(&self) -> Vec<LineMove> {
+        let mut path = self.get_path();
+        let mut node = &self.nodes[self.current];
+        while let Some(child) = node.children.get(node.selected_child) {
+            path.push(*child);
+            node = &self.nodes[*child];
+        }
+        path.windows(2).filter_map(|nodes| {
+            let (parent, child) = (&self.nodes[nodes[0]], &self.nodes[nodes[1]]);
+            let color = parent.players.get_current_player().get_player_color();
+            let captured = child.players.get_player(color).get_player_captured() - parent.players.get_player(color).get_player_captured();
+            child.input.map(|input| (color, input, captured, parent.children.len()))
+        }).collect()
+    }
+
+    pub fn has_next_move(&self) -> bool {
+        !self.get_current().children.is_empty()
+    }
+
+    pub fn get_times(&self) -> Vec<u128> {
+        self.get_path()[1..].iter().map(|x| self.nodes[*x].time).collect()
+    }
+
+    pub fn push(&mut self, board: Board, players: Players, input: Input, time: u128, trees: (Option<Tree>, Option<Tree>)) {
+        let mut players = players;
+        let mut clock = self.get_current().clock;
+        let color = self.get_players().get_current_player().get_player_color();
+        if !clock.spend(color, time) {
+            players.set_game_status(GameStatus::Finished(Some(color.get_inverse_color())));
+        }
+        let trees = (
+            trees.0.or_else(|| self.get_current().trees.0.clone()),
+            trees.1.or_else(|| self.get_current().trees.1.clone())
+        );
+        let existing = self.get_current().children.iter().position(|x| self.nodes[*x].input == Some(input));
+        let child_index = match existing {
+            Some(index) => {
+                let child = self.get_current().children[index];
+                self.nodes[child].players = players;
+                self.nodes[child].time = time;
+                self.nodes[child].trees = trees;
+                self.nodes[child].clock = clock;
+                index
+            },
+            None => {
+                self.nodes.push(RecordNode {
+                    board,
+                    players,
+                    input: Some(input),
+                    time,
+                    trees,
+                    clock,
+                    parent: Some(self.current),
+                    children: vec![],
+                    selected_child: 0
+                });
+                let new_node = self.nodes.len() - 1;
+                let current = self.current;
+                self.nodes[current].children.push(new_node);
+                self.nodes[current].children.len() - 1
+            }
+        };
+        let current = self.current;
+        self.nodes[current].selected_child = child_index;
+        self.current = self.nodes[current].children[child_index];
+    }
+
+    /* With a clock the search deepens until the next depth would not fit in the move budget */
+    pub fn get_bot_move(&self, depth: usize) -> BotMove {
+        self.get_bot_move_on_clock(depth, self.get_clock())
+    }
+
+    /* Same search with the move budget taken from another clock than the one of the game */
+    pub fn get_bot_move_on_clock(&self, depth: usize, clock: &Clock) -> BotMove {
+        let current = self.get_current();
+        search_bot_move(&current.board, &current.players, &current.trees, clock, self.get_played_moves(), depth, &Arc::new(AtomicBool::new(false)))
+    }
+
+    /* Flags the player to move when `elapsed` exceeds its clock, returns true if the game ended on time */
+    pub fn check_time(&mut self, elapsed: u128) -> bool {
+        let color = self.get_players().get_current_player().get_player_color();
+        if !self.get_clock().is_flagged(color, elapsed) {
+            return false
+        }
+        self.lose_on_time();
+        true
+    }
+
+    /* Ends the game in favour of the opponent of the player to move */
+    pub fn lose_on_time(&mut self) {
+        let color = self.get_players().get_current_player().get_player_color();
+        let current = self.current;
+        self.nodes[current].players.set_game_status(GameStatus::Finished(Some(color.get_inverse_color())));
+    }
+
+    pub fn play(&mut self, input: Input, time: u128, trees: (Option<Tree>, Option<Tree>)) -> Result<(), PlacementError> {
+        let mut new_board = self.get_board().clone();
+        let mut new_players = *self.get_players();
+        let color = new_players.get_current_player().get_player_color();
+        new_board.add_value(input, &mut new_players)?;
+        new_players.next_player();
+        update_game_status(&new_board, &mut new_players, color);
+        self.push(new_board, new_players, input, time, trees);
+        Ok(())
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), PlacementError> {
+        if is_renlib(path) {
+            return Err(PlacementError::RenLibFile)
+        }
+        let content: String = if is_psq(path) {
+            let moves: Vec<(Input, u128)> = self.get_moves().iter().map(|(_, input, time)| (*input, *time)).collect();
+            format_psq((self.get_board().get_width(), self.get_board().get_height()), &moves)
+        } else {
+            self.get_line().iter()
+                .map(|input| format!("{}\n", format_input(*input, self.get_board())))
+                .collect()
+        };
+        fs::write(path, content).map_err(|_| PlacementError::IncorrectFile)
+    }
+
+    /*
+    ** Replays a piskvork game from an empty board of its size, keeping the alignement and player types of `board` and `players`,
+    ** piskvork games are freestyle ones so captures are turned off and double free threes allowed
+    */
+    pub fn from_psq(content: &str, board: &Board, players: &Players, time_control: TimeControl) -> Result<GameRecord, PlacementError> {
+        let ((width, height), moves) = parse_psq(content)?;
+        let capture_rules = CaptureRules::new(0, true, false, false, true);
+        let new_board = Board::new(width, height, board.get_alignement_nb(), capture_rules);
+        let mut new_players = Players::new(
+            Player::new(Color::Black, players.get_player(Color::Black).get_player_type()),
+            Player::new(Color::White, players.get_player(Color::White).get_player_type()),
+            players.get_captured_nb(),
+            capture_rules,
+            players.get_stones_per_turn()
+        );
+        new_players.set_double_three_forbidden(false);
+        let mut record = GameRecord::new(new_board, new_players, time_control);
+        for (input, time) in moves {
+            record.play(input, time, (None, None))?;
+        }
+        Ok(record)
+    }
+
+    /* Replays the saved moves from the start, the record is left untouched if a move is illegal */
+    pub fn load(&mut self, path: &str) -> Result<(), PlacementError> {
+        if is_renlib(path) {
+            return Err(PlacementError::RenLibFile)
+        }
+        let content = fs::read_to_string(path).map_err(|_| PlacementError::IncorrectFile)?;
+        let root = &self.nodes[0];
+        if is_psq(path) {
+            *self = GameRecord::from_psq(&content, &root.board, &root.players, root.clock.get_time_control())?;
+            return Ok(())
+        }
+        let mut new_record = GameRecord::new(root.board.clone(), root.players, root.clock.get_time_control());
+        for line in content.lines().map(|x| x.split('#').next().unwrap_or("").trim()).filter(|x| !x.is_empty()) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let input = parse_input(&words, new_record.get_board())?;
+            new_record.play(input, 0, (None, None))?;
+        }
+        *self = new_record;
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.get_current().parent {
+            Some(parent) => {
+                self.current = parent;
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let node = self.get_current();
+        match node.children.get(node.selected_child) {
+            Some(child) => {
+                self.current = *child;
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn jump_to(&mut self, move_number: usize) -> bool {
+        while self.get_move_number() > move_number {
+            self.undo();
+        }
+        while self.get_move_number() < move_number {
+            if !self.redo() {
+                return false
+            }
+        }
+        true
+    }
+
+    pub fn select_variation(&mut self, index: usize) -> bool {
+        if index >= self.get_current().children.len() {
+            return false
+        }
+        let current = self.current;
+        self.nodes[current].selected_child = index;
+        self.redo()
+    }
+
+    pub fn switch_variation(&mut self, forward: bool) -> bool {
+        let parent = match self.get_current().parent {
+            Some(parent) => parent,
+            None => return false
+        };
+        let siblings = self.nodes[parent].children.len();
+        let index = self.nodes[parent].children.iter().position(|x| *x == self.current).unwrap();
+        let next = if forward {
+            (index + 1) % siblings
+        } else {
+            (index + siblings - 1) % siblings
+        };
+        self.current = parent;
+        self.select_variation(next)
+    }
+
+    pub fn set_player_type(&mut self, color: Color, player_type: PlayerType) {
+        for node in self.nodes.iter_mut() {
+            node.players.set_player_type(color, player_type);
+        }
+    }
+
+    pub fn change_player_type(&mut self, color: Color) {
+        for node in self.nodes.iter_mut() {
+            node.players.change_player_type(color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::Algorithm;
+
+    fn new_record() -> GameRecord {
+        let capture_rules = CaptureRules::new(2, true, true, true, true);
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, capture_rules, 1);
+        GameRecord::new(Board::new(9, 9, 5, capture_rules), players, TimeControl::Unlimited)
+    }
+
+    #[test]
+    fn undo_and_redo_walk_the_line() {
+        let mut record = new_record();
+        for input in [(4, 4), (5, 5), (3, 3)].iter() {
+            record.play(*input, 0, (None, None)).unwrap();
+        }
+        assert!(record.undo() && record.undo());
+        assert_eq!(record.get_line(), vec![(4, 4)]);
+        assert_eq!(record.get_board().get((5, 5)), Tile::Empty);
+        assert_eq!(record.get_players().get_current_player().get_player_color(), Color::White);
+        assert!(record.redo());
+        assert_eq!(record.get_line(), vec![(4, 4), (5, 5)]);
+        assert!(record.jump_to(0) && !record.undo());
+        assert!(record.jump_to(3) && !record.redo());
+        assert!(!record.jump_to(4));
+        assert_eq!(record.get_move_number(), 3);
+    }
+
+    #[test]
+    fn new_move_after_undo_keeps_the_old_line_as_variation() {
+        let mut record = new_record();
+        record.play((4, 4), 0, (None, None)).unwrap();
+        record.play((5, 5), 0, (None, None)).unwrap();
+        record.undo();
+        record.play((3, 3), 0, (None, None)).unwrap();
+        assert_eq!(record.get_line(), vec![(4, 4), (3, 3)]);
+        assert!(record.switch_variation(true));
+        assert_eq!(record.get_line(), vec![(4, 4), (5, 5)]);
+        assert!(record.switch_variation(false));
+        assert_eq!(record.get_line(), vec![(4, 4), (3, 3)]);
+        record.undo();
+        assert_eq!(record.get_selected_line().iter().map(|x| (x.1, x.3)).collect::<Vec<_>>(), vec![((4, 4), 1), ((3, 3), 2)]);
+        assert!(record.select_variation(0) && !record.has_next_move());
+        assert_eq!(record.get_line(), vec![(4, 4), (5, 5)]);
+        record.undo();
+        assert!(!record.select_variation(2));
+    }
+
+    #[test]
+    fn replaying_a_variation_does_not_duplicate_it() {
+        let mut record = new_record();
+        record.play((4, 4), 0, (None, None)).unwrap();
+        record.undo();
+        record.play((4, 4), 0, (None, None)).unwrap();
+        record.undo();
+        assert!(record.select_variation(0) && !record.select_variation(1));
+        assert_eq!(record.get_line(), vec![(4, 4)]);
+    }
+
+    #[test]
+    fn illegal_move_is_not_recorded() {
+        let mut record = new_record();
+        record.play((4, 4), 0, (None, None)).unwrap();
+        assert!(record.play((4, 4), 0, (None, None)).is_err());
+        assert_eq!(record.get_line(), vec![(4, 4)]);
+        assert_eq!(record.get_move_numbers()[record.get_board().from_input((4, 4))], Some(1));
+    }
+
+    #[test]
+    fn move_numbers_follow_the_stones_on_the_board() {
+        let mut record = new_record();
+        for input in [(1, 0), (2, 0), (5, 5), (3, 0), (4, 0), (2, 0)].iter() {
+            record.play(*input, 0, (None, None)).unwrap();
+        }
+        let number = |record: &GameRecord, input: Input| record.get_move_numbers()[record.get_board().from_input(input)];
+        let numbers: Vec<Option<usize>> = [(1, 0), (2, 0), (3, 0), (4, 0), (5, 5)].iter().map(|x| number(&record, *x)).collect();
+        assert_eq!(numbers, vec![Some(1), Some(6), None, Some(5), Some(3)]);
+        assert_eq!(record.get_move_numbers().iter().filter(|x| x.is_some()).count(), 4);
+        record.undo();
+        assert_eq!(number(&record, (2, 0)), None);
+        record.jump_to(4);
+        assert_eq!((number(&record, (2, 0)), number(&record, (3, 0)), number(&record, (4, 0))), (Some(2), Some(4), None));
+    }
+
+    #[test]
+    fn flagged_player_loses_before_the_move() {
+        let capture_rules = CaptureRules::new(2, true, true, true, true);
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, capture_rules, 1);
+        let mut record = GameRecord::new(Board::new(9, 9, 5, capture_rules), players, TimeControl::parse("move:1").unwrap());
+        assert!(!record.check_time(1_000_000));
+        assert!(record.check_time(1_000_001));
+        assert_eq!(record.get_players().get_game_status(), GameStatus::Finished(Some(Color::White)));
+        assert_eq!(record.get_move_number(), 0);
+    }
+
+    #[test]
+    fn psq_games_are_replayed_as_freestyle() {
+        let record = new_record();
+        let moves: Vec<(Input, u128)> = [(2, 4), (1, 0), (3, 4), (2, 0), (4, 3), (10, 10), (4, 5), (12, 12), (0, 0), (10, 12), (3, 0), (12, 10), (4, 4)]
+            .iter().map(|x| (*x, 0)).collect();
+        let replayed = GameRecord::from_psq(&format_psq((15, 15), &moves), record.get_board(), record.get_players(), TimeControl::Unlimited).unwrap();
+        assert_eq!(replayed.get_moves().len(), moves.len());
+        assert_eq!(replayed.get_board().get((1, 0)), Tile::Color(Color::White));
+        assert_eq!(replayed.get_board().get((4, 4)), Tile::Color(Color::Black));
+        assert!(!replayed.get_players().is_double_three_forbidden());
+        assert!(!replayed.get_board().get_capture_rules().is_enabled());
+    }
+
+    #[test]
+    fn renlib_files_are_refused() {
+        let mut record = new_record();
+        assert_eq!(record.load("games/opening.lib"), Err(PlacementError::RenLibFile));
+        assert_eq!(record.save("games/opening.LIB"), Err(PlacementError::RenLibFile));
+    }
+
+    fn connect6_record(player_type: PlayerType) -> GameRecord {
+        let capture_rules = CaptureRules::new(0, false, false, false, false);
+        let players = Players::new(Player::new(Color::Black, player_type), Player::new(Color::White, player_type), 10, capture_rules, 2);
+        GameRecord::new(Board::new(9, 9, 6, capture_rules), players, TimeControl::Unlimited)
+    }
+
+    #[test]
+    fn connect6_turns_are_one_stone_then_two() {
+        let mut record = connect6_record(PlayerType::Human);
+        let mut turns = vec![];
+        for input in [(4, 4), (5, 5), (3, 3), (4, 5), (4, 3), (6, 6)].iter() {
+            record.play(*input, 0, (None, None)).unwrap();
+            turns.push(record.get_turn_number());
+        }
+        let colors: Vec<Color> = record.get_moves().iter().map(|x| x.0).collect();
+        assert_eq!(colors, vec![Color::Black, Color::White, Color::White, Color::Black, Color::Black, Color::White]);
+        assert_eq!(turns, vec![1, 1, 1, 2, 2, 2]);
+        assert_eq!(record.get_players().get_current_player().get_player_color(), Color::White);
+    }
+
+    #[test]
+    fn connect6_search_plays_both_stones_of_a_turn() {
+        let mut record = connect6_record(PlayerType::Bot(Algorithm::Minimax));
+        for _ in 0..3 {
+            let (input, trees) = record.get_bot_move(2);
+            record.play(input, 0, trees).unwrap();
+        }
+        let moves = record.get_moves();
+        assert_eq!(moves.iter().map(|x| x.0).collect::<Vec<_>>(), vec![Color::Black, Color::White, Color::White]);
+        assert_ne!(moves[1].1, moves[2].1);
+        assert_eq!(record.get_players().get_current_player().get_player_color(), Color::Black);
+    }
+}
@@ -2,12 +2,14 @@ use crate::board::*;
 use crate::players::*;
 use crate::color::*;
 use crate::matching_cases::*;
+use crate::rules::GameStatus;
 
 #[derive(Debug)]
 pub struct Coordinates {
     pub x: usize,
     pub y: usize,
-    size: usize,
+    width: usize,
+    height: usize,
     start: Input,
     mode: ((usize, i32), (usize, i32))
 }
@@ -20,16 +22,17 @@ pub enum Mode {
 }
 
 impl Coordinates {
-    fn new(size: usize, start: Input, mode: Mode) -> Coordinates {
+    /* `mode` is how the start of the next line moves, along the first edge then along the second one */
+    fn new(width: usize, height: usize, start: Input, mode: Mode) -> Coordinates {
         match mode {
-            Mode::Horizontaly | Mode::Vertically => {
-                Coordinates {x: start.0, y: start.1, size, start, mode: ((1, 1), (0, 1))}
+            Mode::Horizontaly => {
+                Coordinates {x: start.0, y: start.1, width, height, start, mode: ((1, 1), (0, 1))}
             },
-            Mode::Diagoneso => {
-                Coordinates {x: start.0, y: start.1, size, start, mode: ((0, 1), (1, 1))}
+            Mode::Vertically | Mode::Diagoneso => {
+                Coordinates {x: start.0, y: start.1, width, height, start, mode: ((0, 1), (1, 1))}
             }
             _ => {
-                Coordinates {x: start.0, y: start.1, size, start, mode: ((0, 1), (1, -1))}
+                Coordinates {x: start.0, y: start.1, width, height, start, mode: ((1, -1), (0, 1))}
             }
         }
     }
@@ -38,65 +41,37 @@ impl Coordinates {
         (self.x, self.y)
     }
 
-    fn drift(&mut self, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, nb: i32) {
-        if f_x(self.x, nb) >= self.size && f_y(self.y, nb) >= self.size {
-            if self.mode.0.0 == 0 {
-                if (self.start.0 as i32 + self.mode.0.1) < self.size as i32 {
-                    self.start.0 = (self.start.0 as i32 + self.mode.0.1) as usize;
-                }
-            } else {
-                if (self.start.1 as i32 + self.mode.0.1) < self.size as i32 {
-                    self.start.1 = (self.start.1 as i32 + self.mode.0.1) as usize;
-                }
-            }
-            if self.mode.1.0 == 0 {
-                if (self.start.0 as i32 + self.mode.1.1) < 0 {
-                    self.start.0 = 0;
-                } else {
-                    self.start.0 = (self.start.0 as i32 + self.mode.1.1) as usize;
-                }
-            } else {
-                if (self.start.1 as i32 + self.mode.1.1) < 0 {
-                    self.start.1 = 0;
-                } else {
-                    self.start.1 = (self.start.1 as i32 + self.mode.1.1) as usize;
-                }
-            }
-            self.x = self.start.0;
-            self.y = self.start.1;
-            return ;
+    fn shift_start(&mut self, (axis, step): (usize, i32)) -> bool {
+        let (value, limit) = match axis {
+            0 => (self.start.0, self.width),
+            _ => (self.start.1, self.height)
+        };
+        let next = value as i32 + step;
+        if next < 0 || next >= limit as i32 {
+            return false
         }
-        if f_x(self.x, nb) >= self.size {
-            if self.mode.0.0 == 0 {
-                self.start.0 = (self.start.0 as i32 + self.mode.0.1) as usize;
-            } else {
-                self.start.1 = (self.start.1 as i32 + self.mode.0.1) as usize;
-            }
-            self.x = self.start.0;
-            self.y = self.start.1;
-            return ;
-        } else {
-            self.x = f_x(self.x, nb);
+        match axis {
+            0 => self.start.0 = next as usize,
+            _ => self.start.1 = next as usize
+        };
+        true
+    }
+
+    fn next_line(&mut self) {
+        if !self.shift_start(self.mode.0) {
+            self.shift_start(self.mode.1);
         }
-        if f_y(self.y, nb) >= self.size {
-            if self.mode.1.0 == 0 {
-                if (self.start.0 as i32 + self.mode.1.1) < 0 {
-                    self.start.0 = 0;
-                } else {
-                    self.start.0 = (self.start.0 as i32 + self.mode.1.1) as usize;
-                }
-            } else {
-                if (self.start.1 as i32 + self.mode.1.1) < 0 {
-                    self.start.1 = 0;
-                } else {
-                    self.start.1 = (self.start.1 as i32 + self.mode.1.1) as usize;
-                }
-            }
-            self.x = self.start.0;
-            self.y = self.start.1;
-            return ;
+        self.x = self.start.0;
+        self.y = self.start.1;
+    }
+
+    fn drift(&mut self, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, nb: i32) {
+        let (x, y) = (f_x(self.x, nb), f_y(self.y, nb));
+        if x >= self.width || y >= self.height {
+            self.next_line();
         } else {
-            self.y = f_y(self.y, nb);
+            self.x = x;
+            self.y = y;
         }
     }
 }
@@ -141,11 +116,11 @@ pub fn iter_on_board(board: &Board, mode: Mode, color: Color) -> i32 {
         Mode::Vertically => (skip, add, (0, 0)),
         Mode::Horizontaly => (add, skip, (0, 0)),
         Mode::Diagoneso => (sub, add, (0, 0)),
-        Mode::Diagonose => (add, add, (0, board.get_size() - 1))
+        Mode::Diagonose => (add, add, (0, board.get_height() - 1))
     };
     let mut note: i32 = 0;
     let mut i: usize = 0;
-    let mut coordinates = Coordinates::new(board.get_size(), start, mode);
+    let mut coordinates = Coordinates::new(board.get_width(), board.get_height(), start, mode);
     while i < board.get_total_tiles() {
         match board.get_protected(coordinates.to_input()) {
             Some(Tile::Empty) | None => {
@@ -166,18 +141,19 @@ pub fn iter_on_board(board: &Board, mode: Mode, color: Color) -> i32 {
 }
 
 fn get_distance(board: &Board, distance: i32, input: Input) -> bool {
-    let size = board.get_size() as i32;
+    let width = board.get_width() as i32;
+    let height = board.get_height() as i32;
     for y in -distance..=distance {
         let inp_y = (input.1 as i32) + y;
         if  inp_y < 0 {
             continue;
-        } else if inp_y >= size {
+        } else if inp_y >= height {
             break;
         }
         for x in -distance..=distance {
             if (input.0 as i32) + x < 0 || (y != -distance && y != distance && x != -distance && x != distance){
                 continue;
-            } else if (input.0 as i32) + x >= size {
+            } else if (input.0 as i32) + x >= width {
                 break;
             }
             if let &Tile::Color(_) = board.get_ref((((input.0 as i32) + x) as usize, inp_y as usize)) {
@@ -195,23 +171,14 @@ pub fn pruning_heuristic(input: Input, board: &Board) -> bool {
 }
 
 pub fn heuristic(board: &Board, players: &Players, default_color: Color) -> i32 {
-    match players.is_finished() {
-        (true, Some(color)) => {
+    match players.get_game_status() {
+        GameStatus::Finished(Some(color)) => {
             if color == default_color {
                 return i32::MAX
             }
             return i32::MIN
         },
-        _ => ()
-    }
-    match board.is_finished(players.get_current_player()) {
-        (true, Some(color)) => {
-            if color == default_color {
-                return i32::MAX
-            }
-            return i32::MIN
-        },
-        (true, _) => {
+        GameStatus::Finished(None) => {
             return 0
         }
         _ => ()
@@ -222,4 +189,36 @@ pub fn heuristic(board: &Board, players: &Players, default_color: Color) -> i32
     eval += iter_on_board(board, Mode::Vertically, default_color);
     eval += iter_on_board(board, Mode::Diagoneso, default_color);
     eval + iter_on_board(board, Mode::Diagonose, default_color)
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drift_visits_every_tile_once_on_rectangles() {
+        type Step = fn(usize, i32) -> usize;
+        fn visited(width: usize, height: usize, mode: Mode) -> Vec<usize> {
+            let (f_x, f_y, start): (Step, Step, Input) = match mode {
+                Mode::Vertically => (skip, add, (0, 0)),
+                Mode::Horizontaly => (add, skip, (0, 0)),
+                Mode::Diagoneso => (sub, add, (0, 0)),
+                Mode::Diagonose => (add, add, (0, height - 1))
+            };
+            let mut counts = vec![0; width * height];
+            let mut coordinates = Coordinates::new(width, height, start, mode);
+            for _ in 0..width * height {
+                if coordinates.x < width && coordinates.y < height {
+                    counts[coordinates.y * width + coordinates.x] += 1;
+                }
+                coordinates.drift(f_x, f_y, 1);
+            }
+            counts
+        }
+        for (width, height) in [(19, 19), (19, 9), (9, 19), (1, 7)].iter() {
+            for mode in [Mode::Vertically, Mode::Horizontaly, Mode::Diagoneso, Mode::Diagonose] {
+                assert_eq!(visited(*width, *height, mode), vec![1; width * height], "{}x{}", width, height);
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+use std::fmt;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/* Just enough JSON for the engine services, objects keep the order of their keys */
+#[derive(PartialEq, Clone, Debug)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>)
+}
+
+impl Json {
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::String(value) => Some(value),
+            _ => None
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Json::Number(value) if *value >= 0.0 && value.fract() == 0.0 => Some(*value as usize),
+            _ => None
+        }
+    }
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(value) => write!(f, "{}", value),
+            Json::String(value) => write_string(f, value),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            },
+            Json::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (name, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{}", value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut fmt::Formatter<'_>, value: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in value.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?
+        }
+    }
+    write!(f, "\"")
+}
+
+/* Builds an object from borrowed keys, to keep the responses short to write */
+pub fn object(fields: Vec<(&str, Json)>) -> Json {
+    Json::Object(fields.into_iter().map(|(name, value)| (name.to_string(), value)).collect())
+}
+
+pub fn parse_json(value: &str) -> Option<Json> {
+    let mut chars = value.chars().peekable();
+    let json = parse_value(&mut chars)?;
+    skip_whitespace(&mut chars);
+    match chars.next() {
+        None => Some(json),
+        Some(_) => None
+    }
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+fn parse_keyword(chars: &mut Peekable<Chars>, keyword: &str, value: Json) -> Option<Json> {
+    for expected in keyword.chars() {
+        if chars.next()? != expected {
+            return None
+        }
+    }
+    Some(value)
+}
+
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<Json> {
+    skip_whitespace(chars);
+    match *chars.peek()? {
+        'n' => parse_keyword(chars, "null", Json::Null),
+        't' => parse_keyword(chars, "true", Json::Bool(true)),
+        'f' => parse_keyword(chars, "false", Json::Bool(false)),
+        '"' => parse_string(chars).map(Json::String),
+        '[' => {
+            chars.next();
+            let mut values = vec![];
+            skip_whitespace(chars);
+            if chars.peek() == Some(&']') {
+                chars.next();
+                return Some(Json::Array(values))
+            }
+            loop {
+                values.push(parse_value(chars)?);
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => (),
+                    ']' => return Some(Json::Array(values)),
+                    _ => return None
+                }
+            }
+        },
+        '{' => {
+            chars.next();
+            let mut fields = vec![];
+            skip_whitespace(chars);
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                return Some(Json::Object(fields))
+            }
+            loop {
+                skip_whitespace(chars);
+                let name = parse_string(chars)?;
+                skip_whitespace(chars);
+                if chars.next()? != ':' {
+                    return None
+                }
+                fields.push((name, parse_value(chars)?));
+                skip_whitespace(chars);
+                match chars.next()? {
+                    ',' => (),
+                    '}' => return Some(Json::Object(fields)),
+                    _ => return None
+                }
+            }
+        },
+        _ => {
+            let mut number = String::new();
+            while chars.peek().is_some_and(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) {
+                number.push(chars.next()?);
+            }
+            number.parse::<f64>().ok().map(Json::Number)
+        }
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Option<String> {
+    if chars.next()? != '"' {
+        return None
+    }
+    let mut value = String::new();
+    loop {
+        match chars.next()? {
+            '"' => return Some(value),
+            '\\' => match chars.next()? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'b' => value.push('\u{8}'),
+                'f' => value.push('\u{c}'),
+                'u' => {
+                    let code: String = (0..4).filter_map(|_| chars.next()).collect();
+                    value.push(u32::from_str_radix(&code, 16).ok().and_then(char::from_u32)?);
+                },
+                c => value.push(c)
+            },
+            c => value.push(c)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_is_written_as_parsed() {
+        let value = "{\"move\":\"h8\",\"depth\":3,\"score\":-1.5,\"ok\":true,\"rules\":null,\"line\":[\"h8\",[],{}],\"name\":\"a \\\"b\\\"\\n\\u0001\"}";
+        let json = parse_json(value).unwrap();
+        assert_eq!(json.to_string(), value);
+        assert_eq!(json.get("move").and_then(Json::as_str), Some("h8"));
+        assert_eq!(json.get("depth").and_then(Json::as_usize), Some(3));
+        assert_eq!(json.get("score").and_then(Json::as_usize), None);
+        assert_eq!(json.get("name").and_then(Json::as_str), Some("a \"b\"\n\u{1}"));
+        assert_eq!(json.get("missing"), None);
+    }
+
+    #[test]
+    fn whitespace_and_escapes_are_accepted() {
+        let json = parse_json(" { \"a\" : [ 1 , 2e1 ] ,\n\t\"b\" : \"\\u00e9\\/\" } ").unwrap();
+        assert_eq!(json, object(vec![
+            ("a", Json::Array(vec![Json::Number(1.0), Json::Number(20.0)])),
+            ("b", Json::String("é/".to_string()))
+        ]));
+    }
+
+    #[test]
+    fn broken_json_is_refused() {
+        for value in ["", "{", "{\"a\" 1}", "{\"a\":1,}", "[1 2]", "nul", "\"open", "{} {}", "-", "{a:1}", "\"\\u12\""].iter() {
+            assert_eq!(parse_json(value), None, "{}", value);
+        }
+    }
+}
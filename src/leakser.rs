@@ -20,13 +20,27 @@ const TENTEN_C: usize = 10;
 const TENTEN_R: usize = 2;
 const TENTEN_A: usize = 5;
 
+const CONNECT6_S: usize = 19;
+const CONNECT6_C: usize = 10;
+const CONNECT6_R: usize = 0;
+const CONNECT6_A: usize = 6;
+const CONNECT6_T: usize = 2;
+
+const STONES_PER_TURN: usize = 1;
+const HOST_ADDRESS: &str = "127.0.0.1";
+
 use crate::error::{FlagError};
 use crate::players::*;
 use crate::color::{Color};
+use crate::capture_rules::{CaptureRules};
+use crate::clock::{TimeControl};
+use crate::ponder::{PonderMode};
+use crate::network::{NetworkMode};
 
 struct MapFlag {
     lst_flag: Vec<String>,
-    size: usize,
+    width: usize,
+    height: usize,
     captured_nb: usize,
     range: usize,
     alignement_nb: usize,
@@ -36,16 +50,67 @@ struct MapFlag {
 struct OnOffFlag {
     lst_flag: Vec<String>,
     visual: bool,
+    tui: bool,
     special_rule: bool,
     morpion_rule: bool,
     tenten_rule: bool,
-    suggestion: bool
+    connect6_rule: bool,
+    suggestion: bool,
+    up_to_range: bool,
+    no_capture_win: bool,
+    no_break: bool,
+    unsafe_flank: bool
+}
+
+struct ClockFlag {
+    lst_flag: Vec<String>,
+    time_control: TimeControl
+}
+
+struct PonderFlag {
+    lst_flag: Vec<String>,
+    ponder_mode: Option<PonderMode>
+}
+
+struct PositionFlag {
+    lst_flag: Vec<String>,
+    position: Option<String>
+}
+
+struct NetworkFlag {
+    lst_flag: Vec<String>,
+    port: Option<u16>,
+    address: Option<String>,
+    bind: String,
+    color: Color
 }
 
 struct PlayerFlag {
     lst_flag: Vec<String>,
     player1: Player,
     player2: Player,
+    engines: (Option<String>, Option<String>)
+}
+
+/* Everything the flags set up, the paths of the exe: players in color order */
+pub struct Config {
+    pub width: usize,
+    pub height: usize,
+    pub captured_nb: usize,
+    pub capture_rules: CaptureRules,
+    pub alignement_nb: usize,
+    pub visual: bool,
+    pub tui: bool,
+    pub player1: Player,
+    pub player2: Player,
+    pub depth: usize,
+    pub suggestion: bool,
+    pub stones_per_turn: usize,
+    pub time_control: TimeControl,
+    pub ponder_mode: Option<PonderMode>,
+    pub position: Option<String>,
+    pub network_mode: Option<NetworkMode>,
+    pub engines: (Option<String>, Option<String>)
 }
 
 impl MapFlag {
@@ -53,12 +118,14 @@ impl MapFlag {
         MapFlag {
             lst_flag: vec![
                 "-s".to_string(), "--size".to_string(),
+                "--width".to_string(), "--height".to_string(),
                 "-c".to_string(), "--captured".to_string(),
                 "-r".to_string(), "--range".to_string(),
                 "-a".to_string(), "--alignement".to_string(),
                 "-d".to_string(), "--depth".to_string(),
             ],
-            size: BOARD_LENGTH,
+            width: BOARD_LENGTH,
+            height: BOARD_LENGTH,
             captured_nb: CAPTURED_NB,
             range: CAPTURE_RANGE,
             alignement_nb: ALIGNEMENT_NB,
@@ -70,8 +137,12 @@ impl MapFlag {
         &self.lst_flag
     }
 
-    fn get_size(&self) -> usize {
-        self.size
+    fn get_width(&self) -> usize {
+        self.width
+    }
+
+    fn get_height(&self) -> usize {
+        self.height
     }
 
     fn get_captured_nb(&self) -> usize {
@@ -88,7 +159,12 @@ impl MapFlag {
 
     fn get_flag(&mut self, flag: &str, value: usize) {
         match flag {
-            "-s" | "--size" => self.size = value,
+            "-s" | "--size" => {
+                self.width = value;
+                self.height = value;
+            },
+            "--width" => self.width = value,
+            "--height" => self.height = value,
             "-c" | "--captured" => self.captured_nb = value,
             "-r" | "--range" => self.range = value,
             "-a" | "--alignement" => self.alignement_nb = value,
@@ -105,7 +181,9 @@ impl MapFlag {
     }
 
     fn parse_values(&self) -> Result<(), FlagError> {
-        let m = self.get_size();
+        let w = self.get_width();
+        let h = self.get_height();
+        let m = w.max(h);
         let c = self.get_captured_nb();
         let r = self.get_range();
         let a = self.get_alignement_nb();
@@ -118,7 +196,7 @@ impl MapFlag {
             Err(FlagError::RangeTooBig(CAPTURE_RANGE_LIMIT))
         } else if m < a {
             Err(FlagError::AlignementTooBig(m))
-        } else if m < 3 || m < r + 2 {
+        } else if w < 1 || h < 1 || m < 3 || m < r + 2 {
             Err(FlagError::MapTooSmall)
         }  else if a < 2 {
             Err(FlagError::AlignementTooSmall)
@@ -137,15 +215,27 @@ impl OnOffFlag {
         OnOffFlag {
             lst_flag: vec![
                 "-v".to_string(), "--visual".to_string(),
+                "--tui".to_string(),
                 "--morpion".to_string(), "--MORPION".to_string(),
                 "--tenten".to_string(), "--TENTEN".to_string(),
-                "--suggestion".to_string()
+                "--connect6".to_string(), "--CONNECT6".to_string(),
+                "--suggestion".to_string(),
+                "--up-to-range".to_string(),
+                "--no-capture-win".to_string(),
+                "--no-break".to_string(),
+                "--unsafe-flank".to_string()
             ],
             visual: false,
+            tui: false,
             special_rule: false,
             morpion_rule: false,
             tenten_rule: false,
-            suggestion: false
+            connect6_rule: false,
+            suggestion: false,
+            up_to_range: false,
+            no_capture_win: false,
+            no_break: false,
+            unsafe_flank: false
         }
     }
 
@@ -157,6 +247,10 @@ impl OnOffFlag {
         self.visual
     }
 
+    fn get_tui_flag(&self) -> bool {
+        self.tui
+    }
+
     fn get_special_rule(&self) -> bool {
         self.special_rule
     }
@@ -169,10 +263,18 @@ impl OnOffFlag {
         self.tenten_rule
     }
 
+    fn get_connect6_rule(&self) -> bool {
+        self.connect6_rule
+    }
+
     fn get_suggestion_flag(&self) -> bool {
         self.suggestion
     }
 
+    fn get_capture_rules(&self, range: usize) -> CaptureRules {
+        CaptureRules::new(range, !self.up_to_range, !self.no_capture_win, !self.no_break, !self.unsafe_flank)
+    }
+
     fn assign_special_rule(&mut self) -> bool {
         match self.get_special_rule() {
             true => false,
@@ -186,9 +288,15 @@ impl OnOffFlag {
     fn get_flag(&mut self, flag: &str) {
         match flag {
             "-v" | "--visual" => self.visual = !self.get_visual_flag(),
+            "--tui" => self.tui = !self.get_tui_flag(),
             "--morpion" | "--MORPION" => self.morpion_rule = self.assign_special_rule(),
             "--tenten" | "--TENTEN" => self.tenten_rule = self.assign_special_rule(),
+            "--connect6" | "--CONNECT6" => self.connect6_rule = self.assign_special_rule(),
             "--suggestion" => self.suggestion = !self.get_suggestion_flag(),
+            "--up-to-range" => self.up_to_range = !self.up_to_range,
+            "--no-capture-win" => self.no_capture_win = !self.no_capture_win,
+            "--no-break" => self.no_break = !self.no_break,
+            "--unsafe-flank" => self.unsafe_flank = !self.unsafe_flank,
             _ => ()
         }
     }
@@ -201,6 +309,138 @@ impl OnOffFlag {
     }
 }
 
+impl ClockFlag {
+    fn new() -> ClockFlag {
+        ClockFlag {
+            lst_flag: vec![
+                "-t".to_string(), "--clock".to_string()
+            ],
+            time_control: TimeControl::Unlimited
+        }
+    }
+
+    fn get_time_control(&self) -> TimeControl {
+        self.time_control
+    }
+
+    fn get_flag(&mut self, value: &str) -> bool {
+        match TimeControl::parse(value) {
+            Some(time_control) => {
+                self.time_control = time_control;
+                true
+            },
+            None => false
+        }
+    }
+
+    fn parse(&self, flag: &str) -> bool {
+        self.lst_flag.iter().any(|x| *x == flag)
+    }
+}
+
+impl PonderFlag {
+    fn new() -> PonderFlag {
+        PonderFlag {
+            lst_flag: vec![
+                "--ponder".to_string()
+            ],
+            ponder_mode: None
+        }
+    }
+
+    fn get_ponder_mode(&self) -> Option<PonderMode> {
+        self.ponder_mode
+    }
+
+    fn get_flag(&mut self, value: &str) -> bool {
+        match PonderMode::parse(value) {
+            Some(ponder_mode) => {
+                self.ponder_mode = Some(ponder_mode);
+                true
+            },
+            None => false
+        }
+    }
+
+    fn parse(&self, flag: &str) -> bool {
+        self.lst_flag.iter().any(|x| *x == flag)
+    }
+}
+
+impl PositionFlag {
+    fn new() -> PositionFlag {
+        PositionFlag {
+            lst_flag: vec![
+                "--position".to_string()
+            ],
+            position: None
+        }
+    }
+
+    fn get_position(&self) -> Option<String> {
+        self.position.clone()
+    }
+
+    fn get_flag(&mut self, value: &str) {
+        self.position = Some(value.to_string());
+    }
+
+    fn parse(&self, flag: &str) -> bool {
+        self.lst_flag.iter().any(|x| *x == flag)
+    }
+}
+
+impl NetworkFlag {
+    fn new() -> NetworkFlag {
+        NetworkFlag {
+            lst_flag: vec![
+                "--host".to_string(), "--connect".to_string(),
+                "--bind".to_string(), "--color".to_string()
+            ],
+            port: None,
+            address: None,
+            bind: HOST_ADDRESS.to_string(),
+            color: Color::Black
+        }
+    }
+
+    /* --bind and --color only matter to the host, the last of --host and --connect is kept */
+    fn get_network_mode(&self) -> Option<NetworkMode> {
+        match (&self.address, self.port) {
+            (Some(address), _) => Some(NetworkMode::Connect(address.clone())),
+            (None, Some(port)) => Some(NetworkMode::Host(self.bind.clone(), port, self.color)),
+            _ => None
+        }
+    }
+
+    fn get_flag(&mut self, flag: &str, value: &str) -> Result<(), FlagError> {
+        match flag {
+            "--host" => match value.parse::<u16>() {
+                Ok(port) => {
+                    self.port = Some(port);
+                    self.address = None;
+                },
+                _ => return Err(FlagError::NoNumberValue)
+            },
+            "--bind" => self.bind = value.to_string(),
+            "--color" => self.color = match value.to_lowercase().as_str() {
+                "x" => Color::Black,
+                "o" => Color::White,
+                _ => return Err(FlagError::IncorrectValue)
+            },
+            _ => {
+                self.address = Some(value.to_string());
+                self.port = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse(&self, flag: &str) -> bool {
+        self.lst_flag.iter().any(|x| *x == flag)
+    }
+}
+
 impl PlayerFlag {
     fn new() -> PlayerFlag {
         PlayerFlag {
@@ -209,7 +449,8 @@ impl PlayerFlag {
                 "-p2".to_string(), "--player2".to_string()
             ],
             player1: Player::new(Color::Black, PlayerType::Bot(Algorithm::basic_algorithm())),
-            player2: Player::new(Color::White, PlayerType::Human)
+            player2: Player::new(Color::White, PlayerType::Human),
+            engines: (None, None)
         }
     }
 
@@ -225,22 +466,37 @@ impl PlayerFlag {
         self.player2
     }
 
-    fn assign_player_type(&self, color: Color, value: &str) -> Player {
-        match value.to_lowercase().as_str() {
-            "human" => Player::new(color, PlayerType::Human),
-            "bot" => Player::new(color, PlayerType::Bot(Algorithm::basic_algorithm())),
-            "pvs" => Player::new(color, PlayerType::Bot(Algorithm::Pvs)),
-            "minimax" => Player::new(color, PlayerType::Bot(Algorithm::Minimax)),
-            _ => unreachable!()
-        }
+    fn get_engines(&self) -> (Option<String>, Option<String>) {
+        self.engines.clone()
+    }
+
+    /* The path of an "exe:<path>" engine keeps its case */
+    fn assign_engine(&self, value: &str) -> Option<String> {
+        value.get(4..).filter(|_| value.to_lowercase().starts_with("exe:")).map(|path| path.to_string())
     }
 
-    fn get_flag(&mut self, flag: &str, value: &str) {
+    /* An external engine is "exe:<path>", the path keeping its case */
+    fn assign_player_type(&self, color: Color, value: &str) -> Result<(Player, Option<String>), FlagError> {
+        let player_type = match value.to_lowercase().as_str() {
+            "human" => PlayerType::Human,
+            "bot" => PlayerType::Bot(Algorithm::basic_algorithm()),
+            "pvs" => PlayerType::Bot(Algorithm::Pvs),
+            "minimax" => PlayerType::Bot(Algorithm::Minimax),
+            "random" => PlayerType::Random,
+            "exe:" => return Err(FlagError::FlagNeedValue),
+            value if value.starts_with("exe:") => PlayerType::External,
+            _ => return Err(FlagError::IncorrectValue)
+        };
+        Ok((Player::new(color, player_type), self.assign_engine(value)))
+    }
+
+    fn get_flag(&mut self, flag: &str, value: &str) -> Result<(), FlagError> {
         match flag {
-            "-p1" | "--player1" => self.player1 = self.assign_player_type(Color::Black, value),
-            "-p2" | "--player2" => self.player2 = self.assign_player_type(Color::White, value),
+            "-p1" | "--player1" => (self.player1, self.engines.0) = self.assign_player_type(Color::Black, value)?,
+            "-p2" | "--player2" => (self.player2, self.engines.1) = self.assign_player_type(Color::White, value)?,
             _ => ()
         }
+        Ok(())
     }
 
     fn parse(&self, flag: &str) -> bool {
@@ -250,15 +506,6 @@ impl PlayerFlag {
         false
     }
 
-    fn parse_value(&self, value: &str) -> bool {
-        match value.to_lowercase().as_str() {
-            "human" |
-            "pvs" |
-            "bot" |
-            "minimax" => true,
-            _ => false
-        }
-    }
 }
 
 fn check_helper(flags: &mut [String]) -> Result<(), FlagError> {
@@ -285,50 +532,45 @@ fn check_rules(flags: &mut [String]) -> Result<(), FlagError> {
 fn assign_values(
     map_flag: MapFlag,
     on_off_flag: OnOffFlag,
-    player_flag:PlayerFlag
-) -> Result<(usize, usize, usize, usize, bool, Player, Player, usize, bool), (FlagError, usize)> {
-    if on_off_flag.get_morpion_rule() == true {
-        Ok((
-            MORPION_S,
-            MORPION_C,
-            MORPION_R,
-            MORPION_A,
-            on_off_flag.get_visual_flag(),
-            player_flag.get_player1(),
-            player_flag.get_player2(),
-            map_flag.depth,
-            on_off_flag.get_suggestion_flag()
-        ))
-    } else if on_off_flag.get_tenten_rule() == true {
-        Ok((
-            TENTEN_S,
-            TENTEN_C,
-            TENTEN_R,
-            TENTEN_A,
-            on_off_flag.get_visual_flag(),
-            player_flag.get_player1(),
-            player_flag.get_player2(),
-            map_flag.depth,
-            on_off_flag.get_suggestion_flag()
-        ))
+    player_flag:PlayerFlag,
+    clock_flag: ClockFlag,
+    ponder_flag: PonderFlag,
+    position_flag: PositionFlag,
+    network_flag: NetworkFlag
+) -> Result<Config, (FlagError, usize)> {
+    let (width, height, captured_nb, range, alignement_nb, stones_per_turn) = if on_off_flag.get_morpion_rule() {
+        (MORPION_S, MORPION_S, MORPION_C, MORPION_R, MORPION_A, STONES_PER_TURN)
+    } else if on_off_flag.get_tenten_rule() {
+        (TENTEN_S, TENTEN_S, TENTEN_C, TENTEN_R, TENTEN_A, STONES_PER_TURN)
+    } else if on_off_flag.get_connect6_rule() {
+        (CONNECT6_S, CONNECT6_S, CONNECT6_C, CONNECT6_R, CONNECT6_A, CONNECT6_T)
     } else {
-        Ok((
-            map_flag.get_size(),
-            map_flag.get_captured_nb(),
-            map_flag.get_range(),
-            map_flag.get_alignement_nb(),
-            on_off_flag.get_visual_flag(),
-            player_flag.get_player1(),
-            player_flag.get_player2(),
-            map_flag.depth,
-            on_off_flag.get_suggestion_flag()
-        ))
-    }
+        (map_flag.get_width(), map_flag.get_height(), map_flag.get_captured_nb(), map_flag.get_range(), map_flag.get_alignement_nb(), STONES_PER_TURN)
+    };
+    Ok(Config {
+        width,
+        height,
+        captured_nb,
+        capture_rules: on_off_flag.get_capture_rules(range),
+        alignement_nb,
+        visual: on_off_flag.get_visual_flag(),
+        tui: on_off_flag.get_tui_flag(),
+        player1: player_flag.get_player1(),
+        player2: player_flag.get_player2(),
+        depth: map_flag.depth,
+        suggestion: on_off_flag.get_suggestion_flag(),
+        stones_per_turn,
+        time_control: clock_flag.get_time_control(),
+        ponder_mode: ponder_flag.get_ponder_mode(),
+        position: position_flag.get_position(),
+        network_mode: network_flag.get_network_mode(),
+        engines: player_flag.get_engines()
+    })
 }
 
 pub fn leakser(
     flags: &mut [String]
-) -> Result<(usize, usize, usize, usize, bool, Player, Player, usize, bool), (FlagError, usize)> {
+) -> Result<Config, (FlagError, usize)> {
     match check_helper(flags) {
         Err(e) => return Err((e, usize::MAX)),
         _ => ()
@@ -342,6 +584,10 @@ pub fn leakser(
     let mut map_flag: MapFlag = MapFlag::new();
     let mut on_off_flag: OnOffFlag = OnOffFlag::new();
     let mut player_flag: PlayerFlag = PlayerFlag::new();
+    let mut clock_flag: ClockFlag = ClockFlag::new();
+    let mut ponder_flag: PonderFlag = PonderFlag::new();
+    let mut position_flag: PositionFlag = PositionFlag::new();
+    let mut network_flag: NetworkFlag = NetworkFlag::new();
     while i < flags.len() {
         if i == 0 && flags[i] == "main.rs" {
             i += 1;
@@ -364,9 +610,38 @@ pub fn leakser(
             if i >= flags.len() - 1 {
                 return Err((FlagError::FlagNeedValue, i));
             }
-            match player_flag.parse_value(flags[i + 1].as_str()) {
-                true => player_flag.get_flag(flags[i].as_str(), flags[i + 1].as_str()),
-                _ => return Err((FlagError::IncorrectValue, i + 1))
+            if let Err(e) = player_flag.get_flag(flags[i].as_str(), flags[i + 1].as_str()) {
+                return Err((e, i + 1));
+            }
+            i += 1;
+        } else if clock_flag.parse(flags[i].as_str()) {
+            if i >= flags.len() - 1 {
+                return Err((FlagError::FlagNeedValue, i));
+            }
+            if !clock_flag.get_flag(flags[i + 1].as_str()) {
+                return Err((FlagError::IncorrectValue, i + 1));
+            }
+            i += 1;
+        } else if ponder_flag.parse(flags[i].as_str()) {
+            if i >= flags.len() - 1 {
+                return Err((FlagError::FlagNeedValue, i));
+            }
+            if !ponder_flag.get_flag(flags[i + 1].as_str()) {
+                return Err((FlagError::IncorrectValue, i + 1));
+            }
+            i += 1;
+        } else if position_flag.parse(flags[i].as_str()) {
+            if i >= flags.len() - 1 {
+                return Err((FlagError::FlagNeedValue, i));
+            }
+            position_flag.get_flag(flags[i + 1].as_str());
+            i += 1;
+        } else if network_flag.parse(flags[i].as_str()) {
+            if i >= flags.len() - 1 {
+                return Err((FlagError::FlagNeedValue, i));
+            }
+            if let Err(e) = network_flag.get_flag(flags[i].as_str(), flags[i + 1].as_str()) {
+                return Err((e, i + 1));
             }
             i += 1;
         } else {
@@ -378,29 +653,84 @@ pub fn leakser(
         Err(e) => return Err((e, usize::MAX)),
         _ => ()
     }
-    assign_values(map_flag, on_off_flag, player_flag)
+    assign_values(map_flag, on_off_flag, player_flag, clock_flag, ponder_flag, position_flag, network_flag)
 }
 
 fn print_helper() {
-    println!("USAGE: cargo run --release [--] [OPTIONS]\n");
+    println!("USAGE: cargo run --release [--] [OPTIONS]");
+    println!("       cargo run --release [--] match [MATCH OPTIONS] [OPTIONS]");
+    println!("       cargo run --release [--] tournament [TOURNAMENT OPTIONS] [OPTIONS]");
+    println!("       cargo run --release [--] serve [SERVE OPTIONS] [OPTIONS]");
+    println!("       cargo run --release [--] export [EXPORT OPTIONS] [OPTIONS]");
+    println!("       cargo run --release [--] json [OPTIONS]\n");
     println!("OPTIONS:");
     println!("\t-s, --size <Value>\t\tsize of gomoku's board");
+    println!("\t    --width <Value>\t\twidth of gomoku's board");
+    println!("\t    --height <Value>\t\theight of gomoku's board");
     println!("\t-c, --captured <Value>\t\tnumber of stones to capture to win");
     println!("\t-r, --range <Value>\t\trange used for capture opponent's stones");
     println!("\t-a, --alignement <Value>\tnumber of stones to align for win");
+    println!("\t    --up-to-range\t\tcapture any run from 1 to range stones");
+    println!("\t    --no-capture-win\t\tcapturing stones doesn't win the game");
+    println!("\t    --no-break\t\t\talignement wins even if it can be broken by capture");
+    println!("\t    --unsafe-flank\t\tplaying into a flanked position gets captured");
     println!("\t-d, --depth\t\t\tset minimax depth value");
-    println!("\t-p1 --player1 <Player>\t\tchange Player type (human/bot/pvs/minimax)");
-    println!("\t-p2 --player2 <Player>\t\tchange Player type (human/bot/pvs/minimax)");
+    println!("\t-p1 --player1 <Player>\t\tchange Player type (human/bot/pvs/minimax/random/exe:<path>)");
+    println!("\t-p2 --player2 <Player>\t\tchange Player type (human/bot/pvs/minimax/random/exe:<path>)");
+    println!("\t-t, --clock <Time>\t\tgame clock in seconds: 300+5 (base+increment), 600/30x5 (byoyomi), move:10 (per move)");
+    println!("\t    --ponder <Mode>\t\tbot thinks during the human's turn on its predicted reply or on all replies (predicted/all)");
+    println!("\t    --position <Position>\tstart from a position, it replaces the board and rule options:");
+    println!("\t\t\t\t\t\"<rows from the top> <side to move> <black captures>,<white captures> <rules>\"");
+    println!("\t\t\t\t\tex: \"7/7/2XO3/3X3/7/7/7 o 0,0 a5,c10,r2,t1\", rules are a alignement, c captures to win,");
+    println!("\t\t\t\t\tr range, t stones per turn, then u, n, b, f for --up-to-range, --no-capture-win, --no-break, --unsafe-flank");
+    println!("\t\t\t\t\tand d when double free threes are allowed, like in the replayed .psq games");
+    println!("\t    --host <Port>\t\twait for an opponent on this port, your rules are used");
+    println!("\t    --bind <Address>\t\taddress the host listens on, {} by default, 0.0.0.0 for any network", HOST_ADDRESS);
+    println!("\t    --color <Color>\t\tcolor played by the host (x/o), x by default");
+    println!("\t    --connect <Address>\t\tjoin a game hosted at <ip>:<port>, the host chooses your color");
     println!("\t    --suggestion\t\tprint move suggestion for human player");
-    println!("\t-v, --visual\t\t\toutput is a graphical window");
+    println!("\t-v, --visual\t\t\toutput is a graphical window, n shows the move numbers, a click in the move list goes to that move");
+    println!("\t    --tui\t\t\tfull-screen terminal interface, arrows move and enter plays");
     println!("\t    --morpion\t\t\tset value for a morpion game");
     println!("\t    --tenten\t\t\tset value with a ten's map");
+    println!("\t    --connect6\t\t\tplay connect6: two stones per turn, six to win");
     println!("\t    --rules\t\t\tdisplay gomoku\'s rules");
     println!("\t-h, --help\t\t\tdisplay help information");
+    println!("\nMATCH OPTIONS:");
+    println!("\t    --games <Value>\t\tnumber of games, colors alternate every game");
+    println!("\t    --engine1 <Engine>\t\tfirst engine as <algorithm>[:<depth>][@<time>] (pvs/minimax), -t format for the time");
+    println!("\t    --engine2 <Engine>\t\tsecond engine, depth and time default to --depth and -t");
+    println!("\t\t\t\t\tthe evaluation weights are fixed, engines differ by algorithm, depth and time only");
+    println!("\t    --openings <File>\t\tone opening per line in h8 notation, each played with both colors");
+    println!("\nTOURNAMENT OPTIONS:");
+    println!("\t    --engine <Engine>\t\tadd an engine, <algorithm>[:<depth>][@<time>] or exe:<path> for a piskvork program");
+    println!("\t    --games <Value>\t\tnumber of games per pairing, colors alternate every game");
+    println!("\t    --gauntlet\t\t\tthe first engine plays every other one instead of a round-robin");
+    println!("\t    --threads <Value>\t\tnumber of games played at the same time");
+    println!("\t    --output <Directory>\tdirectory where every game is written");
+    println!("\t    --openings <File>\t\tsame as for a match");
+    println!("\nSERVE OPTIONS:");
+    println!("\t    --port <Value>\t\tport of the local HTTP service, 7878 by default");
+    println!("\t\t\t\t\tPOST /bestmove, /check, /status and GET /rules take a JSON object with an optional");
+    println!("\t\t\t\t\t\"position\" and \"moves\" [\"h8\", ...], the board options being the default position;");
+    println!("\t\t\t\t\t/bestmove also takes \"depth\", \"time\" (a --clock value) and \"engine\" (pvs/minimax),");
+    println!("\t\t\t\t\t/check takes the \"move\" to check");
+    println!("\nEXPORT OPTIONS:");
+    println!("\t    --export-image <File>\tdraw the position in a .svg or .png image, --suggestion adds the bot's move");
+    println!("\t    --game <File>\t\tgame saved with \"save\" or a piskvork .psq record, replayed on the board options");
+    println!("\t    --replay <File>\t\tanimated .gif of the game, or one numbered .png or .svg per move");
+    println!("\t    --delay <Value>\t\tmilliseconds between two frames of the replay, 800 by default");
+    println!("\t    --move <Value>\t\tdraw the position after this many moves instead of the last one");
+    println!("\nJSON PROTOCOL:");
+    println!("\tOne JSON object per line on stdin, each answered by one line {{\"ok\": true, ...}} or {{\"ok\": false, \"error\": {{...}}}}");
+    println!("\t{{\"cmd\": \"new_game\", \"size\": 15, \"no-break\": true}}\tnew game, every other field is an option without its --");
+    println!("\t{{\"cmd\": \"play\", \"move\": \"h8\"}}\t\tplay a move, undo takes it back");
+    println!("\t{{\"cmd\": \"go\", \"depth\": 5, \"time\": \"move:5\"}}\tbest move, score and expected line without playing it");
+    println!("\t{{\"cmd\": \"eval\"}}\t\t\theuristic value for the side to move, legal_moves and status also exist");
 }
 
 
-fn print_rules() {
+pub fn print_rules() {
     println!("\nThe rules are simple, two players take turns placing stones of their color on an intersection of the board.");
     println!("The game ends when one player manages to align five stones or capture 10 opponent's stones.");
     println!("The game is played on a 19x19 Goban, without limit to the number of stones.\n");
@@ -1,4 +1,4 @@
-use std::{io, time, env, process};
+use std::{time, env, process};
 use std::time::{Duration};
 mod board;
 use board::{Board, Input};
@@ -8,22 +8,58 @@ mod color;
 use color::{Color};
 mod players;
 use players::*;
+mod capture_rules;
+mod clock;
+use clock::{average_time};
+mod rules;
+use rules::{GameStatus, update_game_status};
 mod algo;
-use algo::{get_bot_input, Tree};
 mod leakser;
-use leakser::{leakser, DEPTH_SUGGESTION};
+use leakser::{leakser, print_rules, DEPTH_SUGGESTION};
+mod command;
+mod notation;
+mod terminal_view;
+mod tui;
+mod self_play;
+use self_play::{match_leakser, run_match};
+mod piskvork;
+use piskvork::{ENGINE_TIMEOUT};
+mod tournament;
+use tournament::{tournament_leakser, run_tournament};
+mod ponder;
+use ponder::{PonderMode};
+mod position;
+use position::{format_position, parse_position};
+mod psq;
+mod network;
+use network::{Connection, NetworkMode};
+mod agent;
+use agent::{Agents, Decision, GameState, Interface, Limits, Trees};
+mod json;
+mod serve;
+use serve::{serve_leakser, run_serve};
+mod protocol;
+use protocol::{run_protocol};
+mod render;
+use render::{export_leakser, export_image, run_export};
+use notation::{format_input};
+use command::{Command, print_commands};
 mod heuristic;
+use heuristic::{heuristic};
 mod matching_cases;
 mod view;
 use view::{View};
 mod opening_move;
-use opening_move::{opening_move};
+mod game_record;
+use game_record::{GameRecord};
 
 extern crate piston;
 extern crate glutin_window;
 extern crate graphics;
 extern crate opengl_graphics;
 extern crate colored;
+extern crate image;
+extern crate rusttype;
 
 use piston::*;
 use glutin_window::GlutinWindow;
@@ -38,80 +74,96 @@ use graphics::*;
 
 const SUGGESTION_TIME: u64 = 4;
 
-fn get_human_input(_player_color: Color) -> Result<Input, PlacementError> {
-    let mut guess = String::new();
-    io::stdin()
-        .read_line(&mut guess)
-        .expect("Failed to read line");
-    let vec: Vec<&str> = guess.trim().split(' ').collect();
-    if vec.len() != 2 {
-        return Err(PlacementError::IncorrectPlacement);
-    }
-    for coord in vec.iter() {
-        match coord.parse::<usize>() {
-            Ok(_) => (),
-            _ => return Err(PlacementError::IncorrectPlacement)
-        }
-    }
-    Ok((vec[0].parse::<usize>().unwrap(), vec[1].parse::<usize>().unwrap()))
+fn execute_command(record: &mut GameRecord, command: Command) -> Result<bool, PlacementError> {
+    match command {
+        Command::Play(_) => (),
+        Command::Undo | Command::Load(_) if record.get_players().has_remote_player() => return Err(PlacementError::NetworkGame),
+        Command::Undo => {
+            if !record.undo() {
+                return Err(PlacementError::NothingToUndo);
+            }
+            if record.get_players().get_current_player().get_player_type() != PlayerType::Human {
+                record.undo();
+            }
+        },
+        Command::Hint => {
+            if let Some(input) = determinate_input_suggestion(record, DEPTH_SUGGESTION) {
+                println!("Hint: {}", format_input(input, record.get_board()));
+            }
+        },
+        Command::Eval => {
+            let color = record.get_players().get_current_player().get_player_color();
+            println!("Eval for {:?} \"{}\": {}", color, color, heuristic(record.get_board(), record.get_players(), color));
+        },
+        Command::Show => {
+            println!("{}", record.get_players());
+            println!("Turn: {}", record.get_turn_number());
+        },
+        Command::Position => println!("{}", format_position(record.get_board(), record.get_players())),
+        Command::Save(path) => {
+            record.save(&path)?;
+            println!("Game saved in {}", path);
+        },
+        Command::Load(path) => {
+            record.load(&path)?;
+            println!("Game loaded from {}", path);
+        },
+        Command::Export(path) => {
+            export_image(record, None, &path)?;
+            println!("Position written in {}", path);
+        },
+        Command::Rules => print_rules(),
+        Command::Help => print_commands(),
+        Command::Quit => return Ok(true)
+    };
+    Ok(false)
 }
 
-fn game(board: &mut Board, players: &mut Players, trees: (&mut Option<Tree>, &mut Option<Tree>), turn_count: &mut usize, depth: usize) -> bool {
-    
-    match (board.is_finished(players.get_current_player()), players.is_finished()) {
-        (_, (true, Some(color))) => {
+fn game(record: &mut GameRecord, limits: &Limits, agents: &mut Agents) -> bool {
+    match record.get_players().get_game_status() {
+        GameStatus::Finished(Some(color)) => {
             println!("BRAVO {:?} \"{}\"", color, color);
             return true;
         },
-        ((true, None), _) => {
+        GameStatus::Finished(None) => {
             println!("DRAW !");
             return true;
         },
-        ((true, Some(color)), _) => {
-            println!("BRAVO {:?} \"{}\"", color, color);
-            return true;
-        },
         _ => ()
     };
     
     let now = time::Instant::now();
-    let input = match players.get_current_player().get_player_type() {
-        PlayerType::Human => {
-            match get_human_input(players.get_current_player().get_player_color()) {
-                Ok(input) => input,
+    let (input, new_trees, agent_time) = match agents.choose_move(&GameState::new(record), limits) {
+        Decision::Play(input, trees, time) => (input, *trees, time),
+        Decision::Pending => return false,
+        Decision::Command(command) => {
+            return match execute_command(record, command) {
+                Ok(quit) => quit,
                 Err(e) => {
                     println!("{}", e);
-                    return false;
+                    false
                 }
-            }
-        }
-        PlayerType::Bot(_) => {
-            match opening_move(board, *turn_count) {
-                Some(o_move) => o_move,
-                _ => {
-                    match players.get_current_player().get_player_color() {
-                        Color::Black => {
-                            let (bot_input, bot_tree) = get_bot_input(*players, &board, trees.0, depth);
-                            *trees.0 = bot_tree;
-                            bot_input
-                        },
-                        Color::White => {
-                            let (bot_input, bot_tree) = get_bot_input(*players, &board, trees.1, depth);
-                            *trees.1 = bot_tree;
-                            bot_input
-                        },
-                    }
-                }
-            }
+            };
         },
+        Decision::Failed(reason) => {
+            println!("{}", reason);
+            return true;
+        }
     };
-    let elapsed_time = now.elapsed();
-    println!("Input took {:?}.", elapsed_time);
-    match board.add_value(input, players) {
+    let elapsed_time = agent_time.unwrap_or(now.elapsed().as_micros());
+    println!("Input took {:?}.", Duration::from_micros(elapsed_time as u64));
+    let color = record.get_players().get_current_player().get_player_color();
+    if record.check_time(elapsed_time) {
+        println!("{:?} \"{}\" ran out of time", color, color);
+        return false;
+    }
+    match record.play(input, elapsed_time, new_trees) {
         Ok(_) => {
-            *turn_count += 1;
-            println!("Turn: {}", *turn_count / 2);
-            players.next_player()
+            agents.notify_move(&GameState::new(record), color, input, elapsed_time);
+            println!("Turn: {} -- {:?} \"{}\" played {}", record.get_turn_number(), color, color, format_input(input, record.get_board()));
+            if let GameStatus::PendingFive(color) = record.get_players().get_game_status() {
+                println!("{:?} \"{}\" aligned, last chance to break it", color, color);
+            }
         },
         Err(e) => println!("{}", e)
     };
@@ -119,92 +171,69 @@ fn game(board: &mut Board, players: &mut Players, trees: (&mut Option<Tree>, &mu
 }
 
 
-fn determinate_input_suggestion(board: &Board, players: &Players, trees: (&Option<Tree>, &Option<Tree>), turn_count: &mut usize, depth: usize) -> Option<Input> {
-    match opening_move(board, *turn_count) {
-        Some(o_move) => Some(o_move),
-        _ => {
-            match players.get_current_player().get_player_color() {
-                Color::Black => {
-                    let (bot_input, _) = get_bot_input(*players, &board, trees.0, depth);
-                    Some(bot_input)
-                },
-                Color::White => {
-                    let (bot_input, _) = get_bot_input(*players, &board, trees.1, depth);
-                    Some(bot_input)
-                },
-            }
-        }
+fn determinate_input_suggestion(record: &GameRecord, depth: usize) -> Option<Input> {
+    Some(record.get_bot_move(depth).0)
+}
+
+fn print_terminal(record: &GameRecord, suggestion: bool) {
+    let players = record.get_players();
+    let finished = matches!(players.get_game_status(), GameStatus::Finished(_));
+    let input_suggestion = if suggestion && !finished && players.get_current_player().get_player_type() == PlayerType::Human {
+        determinate_input_suggestion(record, DEPTH_SUGGESTION)
+    } else {
+        None
+    };
+    println!("{}", terminal_view::render(record.get_board(), players, record.get_last_input(), input_suggestion, None, finished));
+    if !record.get_clock().is_unlimited() {
+        println!("Clock: {} {} -- {} {}", Color::Black, record.get_clock().display(Color::Black, 0), Color::White, record.get_clock().display(Color::White, 0));
     }
 }
 
 fn get_human_input_graphic<E: GenericEvent>(_player_color: Color, mpos: [f64; 2], event: &E, view: &View) -> Input {
     if let Some(Button::Mouse(MouseButton::Left)) = event.press_args() {
-        if mpos[0] > view.get_grid_start() && mpos[0] < view.get_grid_end()
-            && mpos[1] > view.get_grid_start() && mpos[1] < view.get_grid_end() {
-            return (
-                (mpos[0] as usize - view.get_grid_start() as usize) / view.get_cell_size() as usize,
-                (mpos[1] as usize - view.get_grid_start() as usize) / view.get_cell_size() as usize
-            )
+        if let Some(input) = view.input_at(mpos) {
+            return input
         }
     }
     (usize::MAX, usize::MAX)
 }
 
+/* Position after the move played in the window, with the trees and the time an agent reported */
+type GraphicMove = (Board, Players, Trees, Option<u128>);
+
 fn game_graphic<E: GenericEvent>(
-    board: &Board,
-    players: &Players,
+    record: &GameRecord,
     mpos: [f64; 2],
     event: &E,
     view: &View,
-    trees: (&Option<Tree>, &Option<Tree>),
-    turn_count: &mut usize,
-    depth: usize
-) -> (Option<Option<Color>>, Option<(Board, Players, (Option<Tree>, Option<Tree>))>, Option<Input>) {
+    limits: &Limits,
+    agents: &mut Agents
+) -> (Option<Option<Color>>, Option<GraphicMove>, Option<Input>) {
+    let board = record.get_board();
+    let players = record.get_players();
     let mut option_ret = None;
-    match (board.is_finished(players.get_current_player()), players.is_finished()) {
-        (_, (true, Some(color))) => {
-            return (Some(Some(color)), None, None)
-        },
-        ((true, None), _) => {
+    if let GameStatus::Finished(winner) = players.get_game_status() {
+        return (Some(winner), None, None)
+    }
+    let click = Some(get_human_input_graphic(players.get_current_player().get_player_color(), mpos, event, view))
+        .filter(|input| board.is_in_bounds(*input));
+    let (input, new_trees, agent_time) = match agents.choose_move(&GameState::with_click(record, click), limits) {
+        Decision::Play(input, trees, time) => (input, *trees, time),
+        Decision::Failed(reason) => {
+            println!("{}", reason);
             return (Some(None), None, None)
         },
-        ((true, Some(color)), _) => {
-            return (Some(Some(color)), None, None)
-        },
-        _ => ()
+        Decision::Pending | Decision::Command(_) => return (None, None, None)
     };
-    let mut new_trees: (Option<Tree>, Option<Tree>) = (None, None);
-    let input = match players.get_current_player().get_player_type() {
-        PlayerType::Human => get_human_input_graphic(players.get_current_player().get_player_color(), mpos, event, view),
-        PlayerType::Bot(_) => {
-        let ret: Input = match opening_move(board, *turn_count) {
-            Some(o_move) => o_move,
-            _ => {
-                match players.get_current_player().get_player_color() {
-                    Color::Black => {
-                        let (bot_input, bot_tree) = get_bot_input(*players, &board, trees.0, depth);
-                        new_trees.0 = bot_tree;
-                        bot_input
-                    },
-                    Color::White => {
-                        let (bot_input, bot_tree) = get_bot_input(*players, &board, trees.1, depth);
-                        new_trees.1 = bot_tree;
-                        bot_input
-                    },
-                }
-            }
-        };
-        ret
-        },
-    };
-    if input.0 < board.get_size() && input.1 < board.get_size() {
+    if board.is_in_bounds(input) {
         let mut new_board = board.clone();
-        let mut new_players = players.clone();
+        let mut new_players = *players;
+        let color = new_players.get_current_player().get_player_color();
         match new_board.add_value(input, &mut new_players) {
             Ok(_) => {
-                *turn_count += 1;
                 new_players.next_player();
-                option_ret = Some((new_board, new_players, new_trees));
+                update_game_status(&new_board, &mut new_players, color);
+                option_ret = Some((new_board, new_players, new_trees, agent_time));
             },
             Err(_e) => ()
         }
@@ -212,21 +241,6 @@ fn game_graphic<E: GenericEvent>(
     (None, option_ret, Some(input))
 }
 
-fn get_mut_last<'a, T>(list: &'a mut Vec<T>) -> &'a mut T {
-    let len = list.len() - 1;
-    list.get_mut(len).unwrap()
-}
-
-fn get_last<'a, T>(list: &'a Vec<T>) -> &'a T {
-    let len = list.len() - 1;
-    list.get(len).unwrap()
-}
-
-fn get_last_protected<'a, T>(list: &'a Vec<T>) -> Option<&'a T> {
-    let len = list.len() - 1;
-    list.get(len)
-}
-
 fn print_time(us: u128) -> String {
     if us > 1000000 {
         format!("{},{} s", us / 1000000, (us % 1000000) / 1000)
@@ -248,64 +262,163 @@ fn calc_average(durations: &Vec<u128>) -> (u128, u128) {
             }
             acc
         });
-    let len1: u128 = match dur1.len() {
-        0 => 1,
-        _ => dur1.len() as u128,
-    };
-    let len2: u128 = match dur2.len() {
-        0 => 1,
-        _ => dur2.len() as u128,
-    };
-    (dur1.iter().fold(0, |acc, d| acc + d) / len1, dur2.iter().fold(0, |acc, d| acc + d) / len2)
+    (average_time(&dur1), average_time(&dur2))
+}
+
+fn exit_with_flag_error(e: FlagError, flag: Option<&String>) -> ! {
+    if e != FlagError::PrintHelper && e != FlagError::PrintRules {
+        match flag {
+            Some(flag) => println!("\n{} \'{}\' {}", "error:".red(), flag.yellow(), e),
+            None => println!("\n{} {}", "error:".red(), e)
+        }
+        println!("for more information use \"cargo run -- --help\"");
+    }
+    process::exit(1);
 }
 
 fn main() {
     let mut args: Vec<String> = env::args().collect();
-    let mut board: Vec<Board>;
-    let mut players: Vec<Players>;
+    let mut match_config = None;
+    let mut tournament_config = None;
+    let mut serve_config = None;
+    let mut json_protocol = false;
+    let mut export_config = None;
+    if args.len() > 1 && args[1] == "match" {
+        match match_leakser(&args[2..]) {
+            Ok((config, flags)) => {
+                match_config = Some(config);
+                args = [vec![args[0].clone()], flags].concat();
+            },
+            Err((e, f)) => exit_with_flag_error(e, f.checked_add(2).and_then(|f| args.get(f)))
+        }
+    } else if args.len() > 1 && args[1] == "tournament" {
+        match tournament_leakser(&args[2..]) {
+            Ok((config, flags)) => {
+                tournament_config = Some(config);
+                args = [vec![args[0].clone()], flags].concat();
+            },
+            Err((e, f)) => exit_with_flag_error(e, f.checked_add(2).and_then(|f| args.get(f)))
+        }
+    } else if args.len() > 1 && args[1] == "serve" {
+        match serve_leakser(&args[2..]) {
+            Ok((config, flags)) => {
+                serve_config = Some(config);
+                args = [vec![args[0].clone()], flags].concat();
+            },
+            Err((e, f)) => exit_with_flag_error(e, f.checked_add(2).and_then(|f| args.get(f)))
+        }
+    } else if args.len() > 1 && args[1] == "export" {
+        match export_leakser(&args[2..]) {
+            Ok((config, flags)) => {
+                export_config = Some(config);
+                args = [vec![args[0].clone()], flags].concat();
+            },
+            Err((e, f)) => exit_with_flag_error(e, f.checked_add(2).and_then(|f| args.get(f)))
+        }
+    } else if args.len() > 1 && args[1] == "json" {
+        json_protocol = true;
+        args.remove(1);
+    }
+    let mut record: GameRecord;
     let depth: usize;
     let mut suggestion: bool;
+    let terminal_suggestion: bool;
     let visual: bool;
+    let full_screen: bool;
+    let ponder: Option<PonderMode>;
+    let network_mode: Option<NetworkMode>;
+    let engines: (Option<String>, Option<String>);
     match leakser(&mut args[1..]) {
-        Ok((s, c, r, a, v, p1, p2, d, _sug)) => {
-            board = vec![Board::new(s, a, r)];
-            players = vec![Players::new(p1, p2, c, r)];
-            visual = v;
-            depth = d;
-            //suggestion = sug;
-            if p1.get_player_type() == PlayerType::Human && p2.get_player_type() == PlayerType::Human {
-                suggestion = true;
-            } else {
-                suggestion = false;
-            }
+        Ok(config) => {
+            record = match config.position {
+                Some(position) => match parse_position(&position, config.player1, config.player2) {
+                    Ok((board, players)) => GameRecord::new(board, players, config.time_control),
+                    Err(e) => {
+                        println!("\n{} \'{}\' {}", "error:".red(), position.yellow(), e);
+                        process::exit(1);
+                    }
+                },
+                None => GameRecord::new(
+                    Board::new(config.width, config.height, config.alignement_nb, config.capture_rules),
+                    Players::new(config.player1, config.player2, config.captured_nb, config.capture_rules, config.stones_per_turn),
+                    config.time_control
+                )
+            };
+            visual = config.visual;
+            full_screen = config.tui;
+            depth = config.depth;
+            terminal_suggestion = config.suggestion;
+            ponder = config.ponder_mode;
+            network_mode = config.network_mode;
+            engines = config.engines;
+            suggestion = config.player1.get_player_type() == PlayerType::Human && config.player2.get_player_type() == PlayerType::Human;
         },
-        Err((e, f)) => {
-            if e != FlagError::PrintHelper && e != FlagError::PrintRules {
-                if f == usize::MAX { 
-                    println!("\n{} {}", format!("error:").red(), e);
-                } else {
-                    println!("\n{} \'{}\' {}", format!("error:").red(), args[f + 1].yellow(), e);
-                }
-                println!("for more information use \"cargo run -- --help\"");
-            }
+        Err((e, f)) => exit_with_flag_error(e, f.checked_add(1).and_then(|f| args.get(f)))
+    };
+    if let Some(config) = match_config {
+        if let Err(e) = run_match(&config, &record, depth) {
+            println!("\n{} {}", "error:".red(), e);
+            process::exit(1);
+        }
+        return;
+    }
+    if let Some(config) = tournament_config {
+        if let Err(e) = run_tournament(&config, &record, depth) {
+            println!("\n{} {}", "error:".red(), e);
+            process::exit(1);
+        }
+        return;
+    }
+    if json_protocol {
+        run_protocol(&mut record, depth);
+        return;
+    }
+    if let Some(config) = export_config {
+        if let Err(e) = run_export(&config, &mut record, terminal_suggestion) {
+            println!("\n{} {}", "error:".red(), e);
+            process::exit(1);
+        }
+        return;
+    }
+    if let Some(config) = serve_config {
+        if let Err(e) = run_serve(&config, &record, depth) {
+            println!("\n{} {}", "error:".red(), e);
+            process::exit(1);
+        }
+        return;
+    }
+    let network = match network_mode {
+        Some(NetworkMode::Host(address, port, color)) => Some(Connection::host(&address, port, color, &mut record)),
+        Some(NetworkMode::Connect(address)) => Some(Connection::connect(&address, &mut record)),
+        None => None
+    }.map(|connection| match connection {
+        Ok(connection) => connection,
+        Err(e) => {
+            println!("\n{} {}", "error:".red(), e);
             process::exit(1);
         }
+    });
+    if network.is_some() {
+        suggestion = false;
+    }
+    let limits = Limits::new(depth, ENGINE_TIMEOUT);
+    let interface = match visual {
+        true => Interface::Graphic,
+        _ if full_screen => Interface::FullScreen,
+        _ => Interface::Terminal
     };
-    let mut tree_player_1: Vec<Option<Tree>> = vec![None];
-    let mut tree_player_2: Vec<Option<Tree>> = vec![None];
-    let mut turn_count: usize = 1;
-
+    let mut agents = Agents::new(interface, ponder, network, engines);
     match visual {
         true => {
             let mut finished: Option<Option<Color>> = None;
-            let view = View::new(board.get(board.len() - 1).unwrap());
+            let mut view = View::new(record.get_board());
             let opengl = OpenGL::V3_2;
-            let settings = WindowSettings::new("Gomoku", [view.get_window_size(), view.get_window_size()])
+            let settings = WindowSettings::new("Gomoku", view.get_window_size())
                 .graphics_api(opengl)
                 .exit_on_esc(true);
             let mut window: GlutinWindow = settings.build()
                 .expect("Could not create window");
-            let mut events = Events::new(EventSettings::new().lazy(true));
+            let mut events = Events::new(EventSettings::new().lazy(!record.get_players().has_remote_player()));
             let mut gl = GlGraphics::new(opengl);
             let mut mpos: [f64; 2] = [0.0; 2];
             let ref mut arrows_glyph = GlyphCache::new("assets/arrows.ttf", (), TextureSettings::new()).unwrap();
@@ -314,228 +427,236 @@ fn main() {
             let crown = Texture::from_path(&Path::new("./assets/crown.png"), &TextureSettings::new()).unwrap();
             let robot_black = Texture::from_path(&Path::new("./assets/robot.png"), &TextureSettings::new()).unwrap();
             let robot_white= Texture::from_path(&Path::new("./assets/robot_white.png"), &TextureSettings::new()).unwrap();
-            let mut last_input: Vec<Input> = Vec::new();
             let mut start_p1 = time::Instant::now();
             let mut start_p2 = time::Instant::now();
             let mut time_p1: Duration = Duration::new(0, 0);
             let mut time_p2: Duration = Duration::new(0, 0);
-            let mut time_storage: Vec<u128> = Vec::new();
             let mut input_suggestion: Option<Input> = None;
+            let mut show_numbers = false;
             while let Some(event) = events.next(&mut window) {
+                if let Some(args) = event.resize_args() {
+                    view.resize(record.get_board(), args.window_size);
+                }
                 if let Some(Button::Mouse(MouseButton::Left)) = event.press_args() {
-                    if mpos[0] > 50.0 && mpos[0] < 150.0
-                        && mpos[1] > 20.0 && mpos[1] < 70.0 {
-                            let mut new_board = board.pop().unwrap();
-                            let mut new_players = players.pop().unwrap();
-                            new_board.reset();
-                            new_players.reset();
-                            board = vec![new_board];
-                            players = vec![new_players];
-                            last_input = vec![];
-                            tree_player_1 = vec![None];
-                            tree_player_2 = vec![None];
-                            turn_count = 1;
+                    if !record.get_players().has_remote_player() && View::is_inside(view.get_reset_button(), mpos) {
+                            record.reset();
                             finished = None;
                             start_p1 = time::Instant::now();
                             start_p2 = time::Instant::now();
                             time_p1 = Duration::new(0, 0);
                             time_p2 = Duration::new(0, 0);
-                            time_storage = Vec::new();
                             input_suggestion = None;
-                    } else if mpos[0] > 200.0 && mpos[0] < 300.0
-                        && mpos[1] > 20.0 && mpos[1] < 70.0 {
-                            if turn_count > 1 && get_last(&players).get_player(get_last(&players).get_current_player().get_player_color().get_inverse_color()).get_player_type() == PlayerType::Human {
-                                if tree_player_1.len() > 1 {
-                                    tree_player_1 = (&tree_player_1[..tree_player_1.len() - 1]).to_vec();
-                                }
-                                if tree_player_2.len() > 1 {
-                                    tree_player_2 = (&tree_player_2[..tree_player_2.len() - 1]).to_vec();
-                                }
-                                board = (&board[..board.len() - 1]).to_vec();
-                                last_input = (&last_input[..last_input.len() - 1]).to_vec();
-                                players = (&players[..players.len() - 1]).to_vec();
-                                turn_count -= 1;
-                                time_storage.pop();
-                                finished = None;
-                            } else if turn_count > 2 {
-                                if tree_player_1.len() > 1 {
-                                    tree_player_1 = (&tree_player_1[..tree_player_1.len() - 1]).to_vec();
-                                }
-                                if tree_player_2.len() > 1 {
-                                    tree_player_2 = (&tree_player_2[..tree_player_2.len() - 1]).to_vec();
-                                }
-                                board = (&board[..board.len() - 2]).to_vec();
-                                last_input = (&last_input[..last_input.len() - 2]).to_vec();
-                                players = (&players[..players.len() - 2]).to_vec();
-                                turn_count -= 2;
-                                time_storage.pop();
-                                time_storage.pop();
-                                finished = None;
+                    } else if !record.get_players().has_remote_player() && View::is_inside(view.get_undo_button(), mpos) {
+                            let players = record.get_players();
+                            if players.get_player(players.get_current_player().get_player_color().get_inverse_color()).get_player_type() == PlayerType::Human {
+                                record.undo();
+                            } else if record.get_move_number() > 1 {
+                                record.undo();
+                                record.undo();
                             }
+                            finished = None;
+                            start_p1 = time::Instant::now();
+                            start_p2 = time::Instant::now();
+                            time_p1 = Duration::new(0, 0);
+                            time_p2 = Duration::new(0, 0);
+                            input_suggestion = None;
+                    } else if let (Some(move_number), false) = (view.move_list_at(mpos, record.get_move_number(), record.get_selected_line().len() + 1), record.get_players().has_remote_player()) {
+                        if move_number != record.get_move_number() && record.jump_to(move_number) {
+                            finished = None;
                             start_p1 = time::Instant::now();
                             start_p2 = time::Instant::now();
                             time_p1 = Duration::new(0, 0);
                             time_p2 = Duration::new(0, 0);
                             input_suggestion = None;
-                    } else if mpos[0] > 335.0 && mpos[0] < 375.0
-                        && mpos[1] > 40.0 && mpos[1] < 90.0 {
-                        players = players.iter().map(|x| {let mut ret = x.clone(); ret.change_player_type(Color::Black); ret}).collect();
-                        if get_last(&players).get_player(Color::Black).get_player_type() == PlayerType::Human && get_last(&players).get_player(Color::White).get_player_type() == PlayerType::Human {
-                            suggestion = true;
-                        } else {
-                            suggestion = false;
-                        }
-                    } else if mpos[0] > 435.0 && mpos[0] < 475.0
-                        && mpos[1] > 40.0 && mpos[1] < 90.0 {
-                        players = players.iter().map(|x| {let mut ret = x.clone(); ret.change_player_type(Color::White); ret}).collect();
-                        if get_last(&players).get_player(Color::Black).get_player_type() == PlayerType::Human && get_last(&players).get_player(Color::White).get_player_type() == PlayerType::Human {
-                            suggestion = true;
-                        } else {
-                            suggestion = false;
                         }
+                    } else if View::is_inside(view.get_player_toggle(Color::Black), mpos) {
+                        record.change_player_type(Color::Black);
+                        suggestion = record.get_players().get_player(Color::Black).get_player_type() == PlayerType::Human && record.get_players().get_player(Color::White).get_player_type() == PlayerType::Human;
+                    } else if View::is_inside(view.get_player_toggle(Color::White), mpos) {
+                        record.change_player_type(Color::White);
+                        suggestion = record.get_players().get_player(Color::Black).get_player_type() == PlayerType::Human && record.get_players().get_player(Color::White).get_player_type() == PlayerType::Human;
                         }
                 }
+                if let Some(Button::Keyboard(Key::N)) = event.press_args() {
+                    show_numbers = !show_numbers;
+                }
+                if let (Some(Button::Keyboard(key)), false) = (event.press_args(), record.get_players().has_remote_player()) {
+                    let moved = match key {
+                        Key::Left => record.undo(),
+                        Key::Right => record.redo(),
+                        Key::Up => record.switch_variation(false),
+                        Key::Down => record.switch_variation(true),
+                        Key::Home => record.jump_to(0),
+                        Key::End => {
+                            while record.redo() {}
+                            true
+                        },
+                        _ => false
+                    };
+                    if moved {
+                        finished = None;
+                        start_p1 = time::Instant::now();
+                        start_p2 = time::Instant::now();
+                        time_p1 = Duration::new(0, 0);
+                        time_p2 = Duration::new(0, 0);
+                        input_suggestion = None;
+                    }
+                }
                 if let Some(pos) = event.mouse_cursor_args() {
                     mpos = pos
                 }
-                if finished.is_none() {
-                    match game_graphic(get_last(&board), get_last(&players), mpos, &event, &view, (get_last(&tree_player_1), get_last(&tree_player_2)), &mut turn_count, depth) {
-                        (x, Some((new_board, new_players, (new_tree_1, new_tree_2))), Some(input)) => {
-                            if new_players.get_current_player().get_player_color() == Color::Black {
+                /* An earlier position is only left by a human move, which starts a variation */
+                let browsing = record.has_next_move() && record.get_players().get_current_player().get_player_type() != PlayerType::Human;
+                if finished.is_none() && !browsing {
+                    let thinking_time = match record.get_players().get_current_player().get_player_color() {
+                        Color::Black => start_p1.elapsed(),
+                        Color::White => start_p2.elapsed()
+                    };
+                    record.check_time(thinking_time.as_micros());
+                    match game_graphic(&record, mpos, &event, &view, &limits, &mut agents) {
+                        (x, Some((new_board, new_players, new_trees, agent_time)), Some(input)) => {
+                            let input_time = if new_players.get_current_player().get_player_color() == Color::Black {
                                 time_p2 = start_p2.elapsed();
-                                time_storage.push(time_p2.as_micros());
                                 start_p1 = time::Instant::now();
+                                time_p2.as_micros()
                             } else {
                                 time_p1 = start_p1.elapsed();
-                                time_storage.push(time_p1.as_micros());
                                 start_p2 = time::Instant::now();
-                            }
+                                time_p1.as_micros()
+                            };
+                            let input_time = agent_time.unwrap_or(input_time);
+                            let color = record.get_players().get_current_player().get_player_color();
                             finished = x;
-                            board.push(new_board);
-                            players.push(new_players);
-                            last_input.push(input);
-                            if let Some(tree_1) = new_tree_1 {
-                                tree_player_1.push(Some(tree_1));
-                            }
-                            if let Some(tree_2) = new_tree_2 {
-                                tree_player_2.push(Some(tree_2));
-                            }
+                            record.push(new_board, new_players, input, input_time, new_trees);
                             input_suggestion = None;
+                            agents.notify_move(&GameState::new(&record), color, input, input_time);
                         }
                         (x, _, _) => finished = x,
                     }
                 }
                 if suggestion == true {
-                    let suggestion_time = match get_last(&players).get_current_player().get_player_color() {
+                    let suggestion_time = match record.get_players().get_current_player().get_player_color() {
                         Color::Black => start_p1,
                         Color::White => start_p2
                     };
-                    if suggestion_time.elapsed() > Duration::from_secs(SUGGESTION_TIME) && get_last(&players).get_current_player().get_player_type() == PlayerType::Human && input_suggestion == None {
-                        input_suggestion = determinate_input_suggestion(get_last(&board), get_last(&players), (get_last(&tree_player_1), get_last(&tree_player_2)), &mut turn_count, DEPTH_SUGGESTION);
+                    if suggestion_time.elapsed() > Duration::from_secs(SUGGESTION_TIME) && record.get_players().get_current_player().get_player_type() == PlayerType::Human && input_suggestion.is_none() {
+                        input_suggestion = determinate_input_suggestion(&record, DEPTH_SUGGESTION);
                     }
                 }
                 if let Some(args) = event.render_args() {
                     gl.draw(args.viewport(), |context, graphics| {
+                        let scale = view.get_scale();
                         clear(view.get_background_color(), graphics);
-                        view.draw(get_last(&board), get_last(&players), &context, graphics, mpos, finished.is_some(), get_last_protected(&last_input), input_suggestion);
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 32).draw(
+                        view.draw_move_list(record.get_board(), &record.get_selected_line(), record.get_move_number(), text_glyph, &context, graphics);
+                        view.draw(&record, &context, graphics, text_glyph, mpos, finished.is_some(), input_suggestion, show_numbers);
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(32.0)).draw(
                             "M", // Reset
                             arrows_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(120.0, 30.0)
+                                .trans(120.0 * scale, 30.0 * scale)
                                 .flip_hv(),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 32).draw(
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(32.0)).draw(
                             "P", // Undo
                             arrows_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(267.0, 20.0)
+                                .trans(267.0 * scale, 20.0 * scale)
                                 .flip_hv()
                                 .rot_deg(-25.0),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 32).draw(
-                            &get_last(&players).get_player(Color::Black).get_player_captured().to_string(),
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(32.0)).draw(
+                            &record.get_players().get_player(Color::Black).get_player_captured().to_string(),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(390.0, 60.0),
+                                .trans(390.0 * scale, 60.0 * scale),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 32).draw(
-                            &get_last(&players).get_player(Color::White).get_player_captured().to_string(),
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(32.0)).draw(
+                            &record.get_players().get_player(Color::White).get_player_captured().to_string(),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(490.0, 60.0),
+                                .trans(490.0 * scale, 60.0 * scale),
                             graphics
                         ).unwrap();
                         let elapsed_time = if finished.is_some() {
                             (time_p1.as_micros(), time_p2.as_micros())
-                        } else if get_last(&players).get_current_player().get_player_color() == Color::Black {
+                        } else if record.get_players().get_current_player().get_player_color() == Color::Black {
                             (start_p1.elapsed().as_micros(), time_p2.as_micros())
                         } else {
                             (time_p1.as_micros(), start_p2.elapsed().as_micros())
                         };
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 12).draw(
-                            &print_time(elapsed_time.0),
+                        let clock_text = |color: Color, elapsed: u128| {
+                            let clock = record.get_clock();
+                            if clock.is_unlimited() {
+                                print_time(elapsed)
+                            } else if finished.is_none() && record.get_players().get_current_player().get_player_color() == color {
+                                clock.display(color, elapsed)
+                            } else {
+                                clock.display(color, 0)
+                            }
+                        };
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(12.0)).draw(
+                            &clock_text(Color::Black, elapsed_time.0),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(575.0, 55.0),
+                                .trans(575.0 * scale, 55.0 * scale),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 12).draw(
-                            &print_time(calc_average(&time_storage).0),
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(12.0)).draw(
+                            &print_time(calc_average(&record.get_times()).0),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(575.0, 75.0),
+                                .trans(575.0 * scale, 75.0 * scale),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 12).draw(
-                            &print_time(elapsed_time.1),
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(12.0)).draw(
+                            &clock_text(Color::White, elapsed_time.1),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(825.0, 55.0),
+                                .trans(825.0 * scale, 55.0 * scale),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 12).draw(
-                            &print_time(calc_average(&time_storage).1),
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(12.0)).draw(
+                            &print_time(calc_average(&record.get_times()).1),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(825.0, 75.0),
+                                .trans(825.0 * scale, 75.0 * scale),
                             graphics
                         ).unwrap();
-                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], 32).draw(
-                            &format!("[ Turn: {} ]", turn_count / 2),
+                        text::Text::new_color([0.0, 0.0, 0.0, 1.0], view.font_size(32.0)).draw(
+                            &format!("[ Turn: {} ]", record.get_turn_number()),
                             text_glyph,
                             &context.draw_state,
                             context.transform
-                                .trans(650.0, 60.0),
+                                .trans(650.0 * scale, 60.0 * scale),
                             graphics
                         ).unwrap();
-                        if get_last(&players).get_player(Color::Black).get_player_type() == PlayerType::Human {
-                            view.draw_stone(&context, graphics, view.black_color(false), [350.0, 40.0, 15.0, 15.0], 25.0);
+                        if record.get_players().get_player(Color::Black).get_player_type() == PlayerType::Human {
+                            view.draw_stone(&context, graphics, view.black_color(false), view.scaled([350.0, 40.0, 15.0, 15.0]), 25.0 * scale);
                         } else {
-                            image(&robot_black, context.transform.trans(330.0, 20.0), graphics);
+                            image(&robot_black, context.transform.trans(330.0 * scale, 20.0 * scale).scale(scale, scale), graphics);
                         }
-                        if get_last(&players).get_player(Color::White).get_player_type() == PlayerType::Human {
-                            view.draw_stone(&context, graphics, view.white_color(false), [450.0, 40.0, 15.0, 15.0], 25.0);
+                        if record.get_players().get_player(Color::White).get_player_type() == PlayerType::Human {
+                            view.draw_stone(&context, graphics, view.white_color(false), view.scaled([450.0, 40.0, 15.0, 15.0]), 25.0 * scale);
                         } else {
-                            image(&robot_white, context.transform.trans(430.0, 20.0), graphics);
+                            image(&robot_white, context.transform.trans(430.0 * scale, 20.0 * scale).scale(scale, scale), graphics);
                         }
                         if finished.is_some() {
-                            image(&bravo, context.transform.trans(20.0, 65.0), graphics);
+                            image(&bravo, context.transform.trans(20.0 * scale, 65.0 * scale).scale(scale, scale), graphics);
                             if let Some(winner_color) = finished {
                                 match winner_color {
-                                    Some(Color::Black) => image(&crown, context.transform.trans(330.0, 7.0), graphics),
-                                    Some(Color::White) => image(&crown, context.transform.trans(430.0, 7.0), graphics),
+                                    Some(Color::Black) => image(&crown, context.transform.trans(330.0 * scale, 7.0 * scale).scale(scale, scale), graphics),
+                                    Some(Color::White) => image(&crown, context.transform.trans(430.0 * scale, 7.0 * scale).scale(scale, scale), graphics),
                                     _ => ()
                                 }
                             }
@@ -544,13 +665,15 @@ fn main() {
                 }
             }
         },
+        _ if full_screen => tui::run(&mut record, &limits, agents),
         _ => {
+            print_terminal(&record, terminal_suggestion);
             loop {
-                if game(get_mut_last(&mut board), get_mut_last(&mut players), (get_mut_last(&mut tree_player_1), get_mut_last(&mut tree_player_2)), &mut turn_count, depth) {
-                    println!("{}", get_last(&board));
+                let finished = game(&mut record, &limits, &mut agents);
+                print_terminal(&record, terminal_suggestion);
+                if finished {
                     break;
                 }
-                println!("{}", get_last(&board));
             }
         }
     }
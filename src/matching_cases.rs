@@ -182,18 +182,19 @@ pub fn check_5_and_more(board: &Board, f_x: fn(usize, i32) -> usize, f_y: fn(usi
 }
 
 pub fn get_cases_size_2(board: &Board, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, coordinates: &Coordinates, color: Color) -> Option<i32> {
-    let size = board.get_size();
+    let width = board.get_width();
+    let height = board.get_height();
     let first_match;
     let last_match;
-    if f_x(coordinates.x, 1) >= size || f_y(coordinates.y, 1) >= size {
+    if f_x(coordinates.x, 1) >= width || f_y(coordinates.y, 1) >= height {
         return None
     }
-    if f_x(coordinates.x, -1) >= size || f_y(coordinates.y, -1) >= size {
+    if f_x(coordinates.x, -1) >= width || f_y(coordinates.y, -1) >= height {
         first_match = Tile::OutOfBounds;
     } else {
         first_match = board.get((f_x(coordinates.x, -1), f_y(coordinates.y, -1)))
     }
-    if f_x(coordinates.x, 2) >= size || f_y(coordinates.y, 2) >= size {
+    if f_x(coordinates.x, 2) >= width || f_y(coordinates.y, 2) >= height {
         last_match = Tile::OutOfBounds;
     } else {
         last_match = board.get((f_x(coordinates.x, 2), f_y(coordinates.y, 2)))
@@ -252,18 +253,19 @@ pub fn get_cases_size_2(board: &Board, f_x: fn(usize, i32) -> usize, f_y: fn(usi
     }
 }
 pub fn get_cases_size_3(board: &Board, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, coordinates: &Coordinates, color: Color) -> Option<i32> {
-    let size = board.get_size();
+    let width = board.get_width();
+    let height = board.get_height();
     let first_match;
     let last_match;
-    if f_x(coordinates.x, 2) >= size || f_y(coordinates.y, 2) >= size {
+    if f_x(coordinates.x, 2) >= width || f_y(coordinates.y, 2) >= height {
         return None
     }
-    if f_x(coordinates.x, -1) >= size || f_y(coordinates.y, -1) >= size {
+    if f_x(coordinates.x, -1) >= width || f_y(coordinates.y, -1) >= height {
         first_match = Tile::OutOfBounds;
     } else {
         first_match = board.get((f_x(coordinates.x, -1), f_y(coordinates.y, -1)))
     }
-    if f_x(coordinates.x, 3) >= size || f_y(coordinates.y, 3) >= size {
+    if f_x(coordinates.x, 3) >= width || f_y(coordinates.y, 3) >= height {
         last_match = Tile::OutOfBounds;
     } else {
         last_match = board.get((f_x(coordinates.x, 3), f_y(coordinates.y, 3)))
@@ -369,18 +371,19 @@ pub fn get_cases_size_3(board: &Board, f_x: fn(usize, i32) -> usize, f_y: fn(usi
 }
 
 pub fn get_cases_size_4(board: &Board, f_x: fn(usize, i32) -> usize, f_y: fn(usize, i32) -> usize, coordinates: &Coordinates, color: Color) -> Option<i32> {
-    let size = board.get_size();
+    let width = board.get_width();
+    let height = board.get_height();
     let first_match;
     let last_match;
-    if f_x(coordinates.x, 3) >= size || f_y(coordinates.y, 3) >= size {
+    if f_x(coordinates.x, 3) >= width || f_y(coordinates.y, 3) >= height {
         return None
     }
-    if f_x(coordinates.x, -1) >= size || f_y(coordinates.y, -1) >= size {
+    if f_x(coordinates.x, -1) >= width || f_y(coordinates.y, -1) >= height {
         first_match = &Tile::OutOfBounds;
     } else {
         first_match = board.get_ref((f_x(coordinates.x, -1), f_y(coordinates.y, -1)))
     }
-    if f_x(coordinates.x, 4) >= size || f_y(coordinates.y, 4) >= size {
+    if f_x(coordinates.x, 4) >= width || f_y(coordinates.y, 4) >= height {
         last_match = &Tile::OutOfBounds;
     } else {
         last_match = board.get_ref((f_x(coordinates.x, 4), f_y(coordinates.y, 4)))
@@ -0,0 +1,260 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use std::thread;
+
+use crate::board::{Board, Input};
+use crate::color::Color;
+use crate::players::{Player, PlayerType, Players};
+use crate::rules::update_game_status;
+use crate::clock::TimeControl;
+use crate::game_record::GameRecord;
+use crate::position::{format_position, parse_position};
+use crate::error::NetworkError;
+
+const PROTOCOL: &str = "gomoku 2";
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum NetworkMode {
+    /* Address listened on, port and the color played by the host */
+    Host(String, u16, Color),
+    Connect(String)
+}
+
+/* FNV-1a of the position string, so both sides get the same value whatever their platform */
+pub fn position_hash(board: &Board, players: &Players) -> u64 {
+    format_position(board, players).bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/*
+** Line based protocol: the client says "HELLO gomoku 2", the host answers with its rules
+** as "RULES <position>", "CLOCK <time control>" and "COLOR <x|o>" for the color of the client,
+** which confirms with "READY".
+** Every move is then sent as "MOVE <x> <y> <time> <hash>", the hash being the one of the
+** position reached, "DESYNC" when the hashes differ and "QUIT" when leaving
+*/
+pub struct Connection {
+    stream: TcpStream,
+    lines: Receiver<String>
+}
+
+impl Connection {
+    fn new(stream: TcpStream) -> Result<Connection, NetworkError> {
+        let reader = stream.try_clone().map_err(|_| NetworkError::CannotConnect)?;
+        let (sender, lines) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(reader).lines() {
+                match line {
+                    Ok(line) => if sender.send(line.trim().to_string()).is_err() {
+                        break;
+                    },
+                    Err(_) => break
+                }
+            }
+        });
+        Ok(Connection {
+            stream,
+            lines
+        })
+    }
+
+    /* Waits for an opponent on `address`, the host plays `color` with the rules of `record` */
+    pub fn host(address: &str, port: u16, color: Color, record: &mut GameRecord) -> Result<Connection, NetworkError> {
+        let listener = TcpListener::bind((address, port)).map_err(|_| NetworkError::CannotListen(port))?;
+        println!("Waiting for an opponent on {}:{}", address, port);
+        Connection::accept(&listener, color, record)
+    }
+
+    fn accept(listener: &TcpListener, color: Color, record: &mut GameRecord) -> Result<Connection, NetworkError> {
+        let (stream, address) = listener.accept().map_err(|_| NetworkError::CannotConnect)?;
+        let mut connection = Connection::new(stream)?;
+        let hello = connection.expect("HELLO")?;
+        if hello != PROTOCOL {
+            return Err(NetworkError::IncorrectMessage(format!("HELLO {}", hello)))
+        }
+        connection.send(&format!("RULES {}", format_position(record.get_board(), record.get_players())))?;
+        connection.send(&format!("CLOCK {}", record.get_clock().get_time_control()))?;
+        connection.send(&format!("COLOR {}", color.get_inverse_color().to_string().to_lowercase()))?;
+        connection.expect("READY")?;
+        record.set_player_type(color, PlayerType::Human);
+        record.set_player_type(color.get_inverse_color(), PlayerType::Remote);
+        println!("{} joined the game, you play {:?} \"{}\"", address, color, color);
+        Ok(connection)
+    }
+
+    /* Plays the color given by the host, whose rules replace the ones of `record` */
+    pub fn connect(address: &str, record: &mut GameRecord) -> Result<Connection, NetworkError> {
+        let stream = TcpStream::connect(address).map_err(|_| NetworkError::CannotConnect)?;
+        let mut connection = Connection::new(stream)?;
+        connection.send(&format!("HELLO {}", PROTOCOL))?;
+        let rules = connection.expect("RULES")?;
+        let (board, mut players) = parse_position(&rules, Player::new(Color::Black, PlayerType::Remote), Player::new(Color::White, PlayerType::Remote))
+            .map_err(|_| NetworkError::IncorrectMessage(format!("RULES {}", rules)))?;
+        let clock = connection.expect("CLOCK")?;
+        let time_control = TimeControl::parse(&clock).ok_or_else(|| NetworkError::IncorrectMessage(format!("CLOCK {}", clock)))?;
+        let color = match connection.expect("COLOR")?.as_str() {
+            "x" => Color::Black,
+            "o" => Color::White,
+            other => return Err(NetworkError::IncorrectMessage(format!("COLOR {}", other)))
+        };
+        players.set_player_type(color, PlayerType::Human);
+        if rules != format_position(record.get_board(), record.get_players()) || time_control != record.get_clock().get_time_control() {
+            println!("Playing with the host's rules: {} -- clock {}", rules, clock);
+        }
+        *record = GameRecord::new(board, players, time_control);
+        connection.send("READY")?;
+        println!("Connected to {}, you play {:?} \"{}\"", address, color, color);
+        Ok(connection)
+    }
+
+    fn send(&mut self, message: &str) -> Result<(), NetworkError> {
+        writeln!(self.stream, "{}", message)
+            .and_then(|_| self.stream.flush())
+            .map_err(|_| NetworkError::Disconnected)
+    }
+
+    fn check_line(line: String) -> Result<String, NetworkError> {
+        match line.as_str() {
+            "QUIT" => Err(NetworkError::OpponentLeft),
+            "DESYNC" => Err(NetworkError::Desync),
+            _ => Ok(line)
+        }
+    }
+
+    fn expect(&mut self, keyword: &str) -> Result<String, NetworkError> {
+        let line = self.lines.recv().map_err(|_| NetworkError::Disconnected)?;
+        let line = Connection::check_line(line)?;
+        match line.strip_prefix(keyword) {
+            Some(value) => Ok(value.trim().to_string()),
+            None => Err(NetworkError::IncorrectMessage(line))
+        }
+    }
+
+    /* Must be called once the move is played in `record` */
+    pub fn send_move(&mut self, record: &GameRecord, input: Input, time: u128) -> Result<(), NetworkError> {
+        let hash = position_hash(record.get_board(), record.get_players());
+        self.send(&format!("MOVE {} {} {} {:016x}", input.0, input.1, time, hash))
+    }
+
+    /* The move is replayed on a copy of the position to compare the hashes before it is returned */
+    fn check_move(&mut self, record: &GameRecord, line: String) -> Result<(Input, u128), NetworkError> {
+        let line = Connection::check_line(line)?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (input, time, hash) = match words.as_slice() {
+            ["MOVE", x, y, time, hash] => match (x.parse::<usize>(), y.parse::<usize>(), time.parse::<u128>(), u64::from_str_radix(hash, 16)) {
+                (Ok(x), Ok(y), Ok(time), Ok(hash)) => ((x, y), time, hash),
+                _ => return Err(NetworkError::IncorrectMessage(line))
+            },
+            _ => return Err(NetworkError::IncorrectMessage(line))
+        };
+        let mut board = record.get_board().clone();
+        let mut players = *record.get_players();
+        let color = players.get_current_player().get_player_color();
+        if !board.is_in_bounds(input) || board.add_value(input, &mut players).is_err() {
+            return Err(NetworkError::IllegalMove)
+        }
+        players.next_player();
+        update_game_status(&board, &mut players, color);
+        if position_hash(&board, &players) != hash {
+            let _ = self.send("DESYNC");
+            return Err(NetworkError::Desync)
+        }
+        Ok((input, time))
+    }
+
+    pub fn receive_move(&mut self, record: &GameRecord) -> Result<(Input, u128), NetworkError> {
+        let line = self.lines.recv().map_err(|_| NetworkError::Disconnected)?;
+        self.check_move(record, line)
+    }
+
+    /* None while the opponent is still thinking */
+    pub fn try_receive_move(&mut self, record: &GameRecord) -> Option<Result<(Input, u128), NetworkError>> {
+        match self.lines.try_recv() {
+            Ok(line) => Some(self.check_move(record, line)),
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => Some(Err(NetworkError::Disconnected))
+        }
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        let _ = self.send("QUIT");
+        let _ = self.stream.shutdown(Shutdown::Both);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_rules::CaptureRules;
+    use crate::error::PlacementError;
+
+    fn new_record(size: usize) -> GameRecord {
+        let rules = CaptureRules::new(2, true, true, true, true);
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, rules, 1);
+        GameRecord::new(Board::new(size, size, 5, rules), players, TimeControl::parse("300+5").unwrap())
+    }
+
+    /* The host plays `color` on a 15x15 board, the client starts from a 9x9 one */
+    fn connect(color: Color) -> ((Connection, GameRecord), (Connection, GameRecord)) {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let client = thread::spawn(move || {
+            let mut record = new_record(9);
+            Connection::connect(&address, &mut record).map(|connection| (connection, record))
+        });
+        let mut record = new_record(15);
+        let host = Connection::accept(&listener, color, &mut record).unwrap();
+        ((host, record), client.join().unwrap().unwrap())
+    }
+
+    fn play(record: &mut GameRecord, input: Input) -> Result<(), PlacementError> {
+        record.play(input, 0, (None, None))
+    }
+
+    #[test]
+    fn client_gets_the_rules_and_the_other_color() {
+        let ((_host, host_record), (_client, client_record)) = connect(Color::White);
+        let players = (host_record.get_players(), client_record.get_players());
+        assert_eq!(format_position(client_record.get_board(), players.1), format_position(host_record.get_board(), players.0));
+        assert_eq!(client_record.get_clock().get_time_control(), host_record.get_clock().get_time_control());
+        assert_eq!((players.0.get_player(Color::White).get_player_type(), players.0.get_player(Color::Black).get_player_type()), (PlayerType::Human, PlayerType::Remote));
+        assert_eq!((players.1.get_player(Color::Black).get_player_type(), players.1.get_player(Color::White).get_player_type()), (PlayerType::Human, PlayerType::Remote));
+    }
+
+    #[test]
+    fn moves_are_checked_against_the_position_hash() {
+        let ((mut host, mut host_record), (mut client, mut client_record)) = connect(Color::Black);
+        play(&mut host_record, (7, 7)).unwrap();
+        host.send_move(&host_record, (7, 7), 1_000).unwrap();
+        let received = client.receive_move(&client_record).unwrap();
+        assert_eq!(received, ((7, 7), 1_000));
+        play(&mut client_record, received.0).unwrap();
+        client.send(&format!("MOVE 8 8 0 {:016x}", position_hash(client_record.get_board(), client_record.get_players()))).unwrap();
+        assert_eq!(host.receive_move(&host_record), Err(NetworkError::Desync));
+        assert_eq!(client.receive_move(&client_record), Err(NetworkError::Desync));
+    }
+
+    #[test]
+    fn broken_messages_are_refused() {
+        let ((mut host, host_record), (mut client, client_record)) = connect(Color::Black);
+        client.send("MOVE 7 7").unwrap();
+        assert_eq!(host.receive_move(&host_record), Err(NetworkError::IncorrectMessage("MOVE 7 7".to_string())));
+        client.send(&format!("MOVE 20 20 0 {:016x}", 0)).unwrap();
+        assert_eq!(host.receive_move(&host_record), Err(NetworkError::IllegalMove));
+        drop(host);
+        assert_eq!(client.receive_move(&client_record), Err(NetworkError::OpponentLeft));
+    }
+
+    #[test]
+    fn host_refuses_another_protocol() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let mut stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        writeln!(stream, "HELLO gomoku 1").unwrap();
+        let result = Connection::accept(&listener, Color::Black, &mut new_record(15));
+        assert_eq!(result.err(), Some(NetworkError::IncorrectMessage("HELLO gomoku 1".to_string())));
+    }
+}
@@ -0,0 +1,110 @@
+use std::num::IntErrorKind;
+
+use crate::board::{Board, Input};
+use crate::error::PlacementError;
+
+/* Columns are lettered a..z then aa, ab.., rows are numbered from 1 at the bottom of the board */
+pub fn column_name(x: usize) -> String {
+    let mut name = String::new();
+    let mut x = x + 1;
+    while x > 0 {
+        name.insert(0, (b'a' + ((x - 1) % 26) as u8) as char);
+        x = (x - 1) / 26;
+    }
+    name
+}
+
+pub fn row_name(y: usize, height: usize) -> String {
+    (height - y).to_string()
+}
+
+pub fn format_input(input: Input, board: &Board) -> String {
+    format!("{}{}", column_name(input.0), row_name(input.1, board.get_height()))
+}
+
+/* A column too far to be counted is out of bounds, not an other column */
+fn parse_column(letters: &str) -> Result<usize, PlacementError> {
+    if letters.is_empty() {
+        return Err(PlacementError::IncorrectPlacement)
+    }
+    letters.chars().try_fold(0usize, |acc, c| {
+        if !c.is_ascii_lowercase() {
+            return Err(PlacementError::IncorrectPlacement)
+        }
+        acc.checked_mul(26)
+            .and_then(|x| x.checked_add(c as usize - 'a' as usize + 1))
+            .ok_or(PlacementError::OutOfBounds)
+    }).map(|x| x - 1)
+}
+
+pub fn parse_notation(word: &str, board: &Board) -> Result<Input, PlacementError> {
+    let word = word.to_lowercase();
+    let split = word.find(|c: char| c.is_ascii_digit()).unwrap_or(word.len());
+    let (letters, digits) = word.split_at(split);
+    let x = parse_column(letters)?;
+    let row = match digits.parse::<usize>() {
+        Ok(row) => row,
+        Err(e) if *e.kind() == IntErrorKind::PosOverflow => return Err(PlacementError::OutOfBounds),
+        Err(_) => return Err(PlacementError::IncorrectPlacement)
+    };
+    if x >= board.get_width() || row == 0 || row > board.get_height() {
+        return Err(PlacementError::OutOfBounds)
+    }
+    Ok((x, board.get_height() - row))
+}
+
+/* Accepts either "h8" or the zero-based numeric "x y" form */
+pub fn parse_input(args: &[&str], board: &Board) -> Result<Input, PlacementError> {
+    match args {
+        [word] => parse_notation(word, board),
+        [x, y] => match (x.parse::<usize>(), y.parse::<usize>()) {
+            (Ok(x), Ok(y)) => Ok((x, y)),
+            _ => Err(PlacementError::IncorrectPlacement)
+        },
+        _ => Err(PlacementError::IncorrectPlacement)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_rules::CaptureRules;
+
+    fn board() -> Board {
+        Board::new(30, 19, 5, CaptureRules::new(2, true, true, true, true))
+    }
+
+    #[test]
+    fn columns_are_named_like_spreadsheets() {
+        assert_eq!(column_name(0), "a");
+        assert_eq!(column_name(25), "z");
+        assert_eq!(column_name(26), "aa");
+        assert_eq!(parse_column("a"), Ok(0));
+        assert_eq!(parse_column("aa"), Ok(26));
+        for x in 0..100 {
+            assert_eq!(parse_column(&column_name(x)), Ok(x));
+        }
+    }
+
+    #[test]
+    fn notation_counts_rows_from_the_bottom() {
+        let board = board();
+        assert_eq!(parse_notation("a1", &board), Ok((0, 18)));
+        assert_eq!(parse_notation("aa10", &board), Ok((26, 9)));
+        assert_eq!(parse_notation("H8", &board), Ok((7, 11)));
+        assert_eq!(format_input((26, 9), &board), "aa10");
+    }
+
+    #[test]
+    fn notation_out_of_the_board_is_refused() {
+        let board = board();
+        assert_eq!(parse_notation("a0", &board), Err(PlacementError::OutOfBounds));
+        assert_eq!(parse_notation("a20", &board), Err(PlacementError::OutOfBounds));
+        assert_eq!(parse_notation("ae1", &board), Err(PlacementError::OutOfBounds));
+        assert_eq!(parse_notation(&format!("{}1", "z".repeat(40)), &board), Err(PlacementError::OutOfBounds));
+        assert_eq!(parse_notation("a99999999999999999999999", &board), Err(PlacementError::OutOfBounds));
+        assert_eq!(parse_notation("1", &board), Err(PlacementError::IncorrectPlacement));
+        assert_eq!(parse_notation("a", &board), Err(PlacementError::IncorrectPlacement));
+        assert_eq!(parse_notation("a-1", &board), Err(PlacementError::IncorrectPlacement));
+    }
+}
@@ -1,8 +1,12 @@
 use board::{Input, Board, Tile};
 use color::{Color};
+use players::{Players};
 
-pub fn opening_move(board: &Board, turn_count: usize) -> Option<Input> {
-    let middle_input: Input = (board.get_size() / 2, board.get_size() / 2);
+pub fn opening_move(board: &Board, players: &Players, turn_count: usize) -> Option<Input> {
+    let middle_input: Input = (board.get_width() / 2, board.get_height() / 2);
+    if players.get_stones_per_turn() > 1 {
+        return None
+    }
     match turn_count {
         1 => Some(middle_input),
         2 => opening_second_move(board, middle_input),
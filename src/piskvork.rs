@@ -0,0 +1,113 @@
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, Input, Tile};
+use crate::color::Color;
+use crate::error::EngineError;
+
+pub const ENGINE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/* An external program speaking the piskvork protocol, its answers are read on a separate thread so a stuck engine can time out */
+pub struct PiskvorkEngine {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>
+}
+
+impl PiskvorkEngine {
+    pub fn start(path: &str, board: &Board, timeout: Duration) -> Result<PiskvorkEngine, EngineError> {
+        let mut child = Command::new(path)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|_| EngineError::CannotStart)?;
+        let stdin = child.stdin.take().ok_or(EngineError::CannotStart)?;
+        let stdout = child.stdout.take().ok_or(EngineError::CannotStart)?;
+        let (sender, lines) = channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                match line {
+                    Ok(line) => if sender.send(line.trim().to_string()).is_err() {
+                        break;
+                    },
+                    Err(_) => break
+                }
+            }
+        });
+        let mut engine = PiskvorkEngine {
+            child,
+            stdin,
+            lines
+        };
+        if board.get_width() == board.get_height() {
+            engine.send(&format!("START {}", board.get_width()))?;
+        } else {
+            engine.send(&format!("RECTSTART {},{}", board.get_width(), board.get_height()))?;
+        }
+        match engine.read_answer(timeout)?.as_str() {
+            "OK" => (),
+            answer => return Err(EngineError::IncorrectAnswer(answer.to_string()))
+        }
+        engine.send(&format!("INFO timeout_turn {}", timeout.as_millis()))?;
+        Ok(engine)
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{}", command)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|_| EngineError::Crashed)
+    }
+
+    /* Skips the MESSAGE and DEBUG lines the engine is allowed to print at any time */
+    fn read_answer(&mut self, timeout: Duration) -> Result<String, EngineError> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let left = deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(left) {
+                Ok(line) => {
+                    if line.is_empty() || line.starts_with("MESSAGE") || line.starts_with("DEBUG") {
+                        continue;
+                    }
+                    return Ok(line)
+                },
+                Err(RecvTimeoutError::Timeout) => return Err(EngineError::Timeout),
+                Err(RecvTimeoutError::Disconnected) => return Err(EngineError::Crashed)
+            }
+        }
+    }
+
+    /* The whole position is sent every turn, stones removed by captures are unknown to piskvork engines otherwise */
+    pub fn get_move(&mut self, board: &Board, color: Color, timeout: Duration) -> Result<Input, EngineError> {
+        self.send(&format!("INFO timeout_turn {}", timeout.as_millis()))?;
+        self.send("BOARD")?;
+        for (i, tile) in board.get_board().iter().enumerate() {
+            if let Tile::Color(stone) = tile {
+                let (x, y) = board.get_input(i);
+                self.send(&format!("{},{},{}", x, y, if *stone == color { 1 } else { 2 }))?;
+            }
+        }
+        self.send("DONE")?;
+        let answer = self.read_answer(timeout)?;
+        let coordinates: Vec<&str> = answer.split(',').map(|x| x.trim()).collect();
+        match coordinates.as_slice() {
+            [x, y] => match (x.parse::<usize>(), y.parse::<usize>()) {
+                (Ok(x), Ok(y)) => Ok((x, y)),
+                _ => Err(EngineError::IncorrectAnswer(answer))
+            },
+            _ => Err(EngineError::IncorrectAnswer(answer))
+        }
+    }
+}
+
+impl Drop for PiskvorkEngine {
+    fn drop(&mut self) {
+        let _ = self.send("END");
+        thread::sleep(Duration::from_millis(50));
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -1,166 +1,242 @@
-use crate::color::Color;
-use std::fmt;
-
-
-#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
-pub enum Algorithm {
-    Pvs,
-    Minimax 
-}
-
-impl Algorithm {
-
-    pub fn usize_to_algorithm(i: usize) -> Algorithm {
-        match i {
-            0 => Algorithm::Minimax,
-            _ => Algorithm::Pvs
-        }
-    }
-
-    pub fn basic_algorithm() -> Algorithm {
-        Algorithm::usize_to_algorithm(0)
-    }
-}
-
-#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
-pub enum PlayerType {
-    Bot(Algorithm),
-    Human
-}
-
-#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
-pub struct Player {
-    color: Color,
-    player_type: PlayerType,
-    captured: usize,
-}
-
-impl fmt::Display for Player {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} -- {}", self.color, self.captured)
-    }
-}
-
-impl Player {
-    pub fn new(color: Color, player_type: PlayerType) -> Player {
-        Player {color, player_type, captured: 0}
-    }
-
-    pub fn reset(&mut self) {
-        self.captured = 0;
-    }
-
-    fn change_player_type(&mut self) {
-        match self.player_type {
-            PlayerType::Bot(_) => self.player_type = PlayerType::Human,
-            _ => self.player_type = PlayerType::Bot(Algorithm::basic_algorithm()),
-        }
-    }
-
-    pub fn get_player_type(&self) -> PlayerType {
-        self.player_type
-    }
-
-    pub fn get_player_color(&self) -> Color {
-        self.color
-    }
-
-    pub fn get_player_captured(&self) -> usize {
-        self.captured
-    }
-
-    pub fn add_capture(&mut self, capture_range: usize) {
-        self.captured += capture_range;
-    }
-}
-
-#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
-pub struct Players {
-    player1: Player,
-    player2: Player,
-    current_player: bool,
-    captured_nb: usize,
-    capture_range: usize
-}
-
-impl fmt::Display for Players {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let current_player = self.get_current_player();
-        let mut is_player_1 = "".to_owned();
-        let mut is_player_2 = "".to_owned();
-        match current_player.get_player_color() {
-            Color::Black => is_player_1 = "<".to_owned(),
-            _ => is_player_2 = "<".to_owned()
-        }
-        write!(f, "Player 1: {} {}\nPlayer 2: {} {}", self.get_player(Color::Black), is_player_1, self.get_player(Color::White), is_player_2)
-    }
-}
-
-impl Players {
-    pub fn new(player1: Player, player2: Player, captured_nb: usize, capture_range: usize) -> Players {
-        Players {
-            player1,
-            player2,
-            current_player: true,
-            captured_nb,
-            capture_range
-        }
-    }
-
-    pub fn reset(&mut self) {
-        self.player1.reset();
-        self.player2.reset();
-        self.current_player = true;
-    }
-
-    pub fn next_player(&mut self) {
-        self.current_player = !self.current_player;
-    }
-
-    pub fn is_finished(&self) -> (bool, Option<Color>) {
-        if self.player1.get_player_captured() >= self.get_captured_nb() {
-            return (true, Some(self.player1.color));
-        } else if self.player2.get_player_captured() >= self.get_captured_nb() {
-            return (true, Some(self.player2.color));
-        } else {
-            return (false, None)
-        }
-    }
-
-    pub fn get_player(&self, color: Color) -> Player {
-        match color {
-            Color::Black => self.player1,
-            _ => self.player2
-        }
-    }
-
-    pub fn get_current_player(&self) -> &Player {
-        if self.current_player {
-            &self.player1
-        } else {
-            &self.player2
-        }
-    }
-
-    pub fn change_player_type(&mut self, color: Color) {
-        match color {
-            Color::Black => self.player1.change_player_type(),
-            _ => self.player2.change_player_type()
-        }
-    }
-
-    pub fn get_captured_nb(&self) -> usize {
-        self.captured_nb
-    }
-
-    fn get_capture_range(&self) -> usize {
-        self.capture_range
-    }
-
-    pub fn add_capture(&mut self, color: Color) {
-        match color {
-            Color::Black => self.player1.add_capture(self.get_capture_range()),
-            _ => self.player2.add_capture(self.get_capture_range())
-        }
-    }
-}
+use crate::color::Color;
+use crate::capture_rules::CaptureRules;
+use crate::rules::GameStatus;
+use crate::error::PlacementError;
+use std::fmt;
+
+
+#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
+pub enum Algorithm {
+    Pvs,
+    Minimax 
+}
+
+impl Algorithm {
+
+    pub fn usize_to_algorithm(i: usize) -> Algorithm {
+        match i {
+            0 => Algorithm::Minimax,
+            _ => Algorithm::Pvs
+        }
+    }
+
+    pub fn basic_algorithm() -> Algorithm {
+        Algorithm::usize_to_algorithm(0)
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
+pub enum PlayerType {
+    Bot(Algorithm),
+    Human,
+    Remote,
+    Random,
+    External
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
+pub struct Player {
+    color: Color,
+    player_type: PlayerType,
+    captured: usize,
+}
+
+impl fmt::Display for Player {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} -- {}", self.color, self.captured)
+    }
+}
+
+impl Player {
+    pub fn new(color: Color, player_type: PlayerType) -> Player {
+        Player {color, player_type, captured: 0}
+    }
+
+    fn change_player_type(&mut self) {
+        match self.player_type {
+            PlayerType::Bot(_) => self.player_type = PlayerType::Human,
+            PlayerType::Human => self.player_type = PlayerType::Bot(Algorithm::basic_algorithm()),
+            PlayerType::Random | PlayerType::External => self.player_type = PlayerType::Human,
+            PlayerType::Remote => ()
+        }
+    }
+
+    pub fn get_player_type(&self) -> PlayerType {
+        self.player_type
+    }
+
+    pub fn get_player_color(&self) -> Color {
+        self.color
+    }
+
+    pub fn get_player_captured(&self) -> usize {
+        self.captured
+    }
+
+    pub fn add_capture(&mut self, stones: usize) {
+        self.captured += stones;
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub struct Players {
+    player1: Player,
+    player2: Player,
+    current_player: bool,
+    captured_nb: usize,
+    capture_rules: CaptureRules,
+    stones_per_turn: usize,
+    stones_left: usize,
+    double_three: bool,
+    game_status: GameStatus
+}
+
+impl fmt::Display for Players {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let current_player = self.get_current_player();
+        let mut is_player_1 = "".to_owned();
+        let mut is_player_2 = "".to_owned();
+        match current_player.get_player_color() {
+            Color::Black => is_player_1 = "<".to_owned(),
+            _ => is_player_2 = "<".to_owned()
+        }
+        write!(f, "Player 1: {} {}\nPlayer 2: {} {}", self.get_player(Color::Black), is_player_1, self.get_player(Color::White), is_player_2)
+    }
+}
+
+impl Players {
+    pub fn new(player1: Player, player2: Player, captured_nb: usize, capture_rules: CaptureRules, stones_per_turn: usize) -> Players {
+        Players {
+            player1,
+            player2,
+            current_player: true,
+            captured_nb,
+            capture_rules,
+            stones_per_turn,
+            stones_left: 1,
+            double_three: true,
+            game_status: GameStatus::Playing
+        }
+    }
+
+    pub fn next_player(&mut self) {
+        if self.stones_left > 1 {
+            self.stones_left -= 1;
+            return
+        }
+        self.current_player = !self.current_player;
+        self.stones_left = self.stones_per_turn;
+    }
+
+    pub fn get_game_status(&self) -> GameStatus {
+        self.game_status
+    }
+
+    pub fn set_game_status(&mut self, game_status: GameStatus) {
+        self.game_status = game_status;
+    }
+
+    pub fn get_stones_per_turn(&self) -> usize {
+        self.stones_per_turn
+    }
+
+    /* Freestyle games allow a move making two free threes, the rule is never used with several stones per turn */
+    pub fn is_double_three_forbidden(&self) -> bool {
+        self.double_three
+    }
+
+    pub fn set_double_three_forbidden(&mut self, forbidden: bool) {
+        self.double_three = forbidden;
+    }
+
+    pub fn is_finished(&self) -> (bool, Option<Color>) {
+        if !self.get_capture_rules().is_capture_win() {
+            return (false, None)
+        } else if self.player1.get_player_captured() >= self.get_captured_nb() {
+            return (true, Some(self.player1.color));
+        } else if self.player2.get_player_captured() >= self.get_captured_nb() {
+            return (true, Some(self.player2.color));
+        } else {
+            return (false, None)
+        }
+    }
+
+    pub fn get_player(&self, color: Color) -> Player {
+        match color {
+            Color::Black => self.player1,
+            _ => self.player2
+        }
+    }
+
+    pub fn get_current_player(&self) -> &Player {
+        if self.current_player {
+            &self.player1
+        } else {
+            &self.player2
+        }
+    }
+
+    pub fn change_player_type(&mut self, color: Color) {
+        match color {
+            Color::Black => self.player1.change_player_type(),
+            _ => self.player2.change_player_type()
+        }
+    }
+
+    pub fn has_remote_player(&self) -> bool {
+        self.player1.player_type == PlayerType::Remote || self.player2.player_type == PlayerType::Remote
+    }
+
+    pub fn get_captured_nb(&self) -> usize {
+        self.captured_nb
+    }
+
+    pub fn get_capture_rules(&self) -> CaptureRules {
+        self.capture_rules
+    }
+
+    pub fn add_capture(&mut self, color: Color, stones: usize) {
+        match color {
+            Color::Black => self.player1.add_capture(stones),
+            _ => self.player2.add_capture(stones)
+        }
+    }
+
+    pub fn set_player_type(&mut self, color: Color, player_type: PlayerType) {
+        match color {
+            Color::Black => self.player1.player_type = player_type,
+            _ => self.player2.player_type = player_type
+        }
+    }
+
+    /* Side to move followed by its stones left when several are played per turn, then the captures of each player: "x 0,2" */
+    pub fn format_state(&self) -> String {
+        let side = self.get_current_player().get_player_color().to_string().to_lowercase();
+        let stones_left = if self.stones_per_turn > 1 { self.stones_left.to_string() } else { String::new() };
+        format!("{}{} {},{}", side, stones_left, self.player1.captured, self.player2.captured)
+    }
+
+    pub fn parse_state(&mut self, side: &str, captures: &str) -> Result<(), PlacementError> {
+        let mut chars = side.chars();
+        self.current_player = match chars.next() {
+            Some('x') | Some('X') => true,
+            Some('o') | Some('O') => false,
+            _ => return Err(PlacementError::IncorrectPosition)
+        };
+        self.stones_left = match chars.as_str() {
+            "" => 1,
+            stones_left => match stones_left.parse::<usize>() {
+                Ok(x) if (1..=self.stones_per_turn).contains(&x) => x,
+                _ => return Err(PlacementError::IncorrectPosition)
+            }
+        };
+        match captures.split_once(',').map(|(x, y)| (x.parse::<usize>(), y.parse::<usize>())) {
+            Some((Ok(black), Ok(white))) => {
+                self.player1.captured = black;
+                self.player2.captured = white;
+                Ok(())
+            },
+            _ => Err(PlacementError::IncorrectPosition)
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+
+use crate::board::Board;
+use crate::color::Color;
+use crate::players::{PlayerType, Players};
+use crate::rules::{GameStatus, update_game_status};
+use crate::algo::predicted_replies;
+use crate::game_record::{BotMove, GameRecord, search_bot_move};
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum PonderMode {
+    Predicted,
+    All
+}
+
+impl PonderMode {
+    pub fn parse(value: &str) -> Option<PonderMode> {
+        match value.to_lowercase().as_str() {
+            "predicted" => Some(PonderMode::Predicted),
+            "all" => Some(PonderMode::All),
+            _ => None
+        }
+    }
+}
+
+type Position = (Board, Players);
+
+struct PonderState {
+    results: Vec<(Position, BotMove)>,
+    searching: Option<Position>
+}
+
+/* `stop` aborts the reply being searched, `finish` only keeps the next ones from being started */
+struct PonderSearch {
+    position: Position,
+    state: Arc<Mutex<PonderState>>,
+    stop: Arc<AtomicBool>,
+    finish: Arc<AtomicBool>,
+    handle: JoinHandle<()>
+}
+
+impl PonderSearch {
+    fn abort(self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let _ = self.handle.join();
+    }
+}
+
+/* Searches the bot's answers to the human's likely replies while the human thinks */
+pub struct Ponder {
+    mode: Option<PonderMode>,
+    search: Option<PonderSearch>
+}
+
+impl Ponder {
+    pub fn new(mode: Option<PonderMode>) -> Ponder {
+        Ponder {
+            mode,
+            search: None
+        }
+    }
+
+    /* Does nothing unless a human facing a bot is to move, or when this position is already pondered */
+    pub fn start(&mut self, record: &GameRecord, depth: usize) {
+        let mode = match self.mode {
+            Some(mode) => mode,
+            None => return
+        };
+        let board = record.get_board();
+        let players = *record.get_players();
+        let color = players.get_current_player().get_player_color();
+        if matches!(players.get_game_status(), GameStatus::Finished(_))
+            || players.get_current_player().get_player_type() != PlayerType::Human
+            || !matches!(players.get_player(color.get_inverse_color()).get_player_type(), PlayerType::Bot(_)) {
+            return
+        }
+        if let Some(search) = &self.search {
+            if search.position.0 == *board && search.position.1 == players {
+                return
+            }
+        }
+        self.stop();
+        let trees = record.get_trees();
+        let trees = (trees.0.clone(), trees.1.clone());
+        let bot_tree = match color {
+            Color::Black => &trees.1,
+            Color::White => &trees.0
+        };
+        let mut replies = predicted_replies(bot_tree, board, &players);
+        if mode == PonderMode::Predicted {
+            replies.truncate(1);
+        }
+        let state = Arc::new(Mutex::new(PonderState { results: vec![], searching: None }));
+        let (stop, finish) = (Arc::new(AtomicBool::new(false)), Arc::new(AtomicBool::new(false)));
+        let (thread_state, thread_stop, thread_finish) = (Arc::clone(&state), Arc::clone(&stop), Arc::clone(&finish));
+        let (thread_board, clock, move_number) = (board.clone(), *record.get_clock(), record.get_played_moves() + 1);
+        let handle = thread::spawn(move || {
+            for input in replies {
+                if thread_stop.load(Ordering::Relaxed) || thread_finish.load(Ordering::Relaxed) {
+                    break;
+                }
+                let mut new_board = thread_board.clone();
+                let mut new_players = players;
+                if new_board.add_value(input, &mut new_players).is_err() {
+                    continue;
+                }
+                new_players.next_player();
+                update_game_status(&new_board, &mut new_players, color);
+                if matches!(new_players.get_game_status(), GameStatus::Finished(_))
+                    || !matches!(new_players.get_current_player().get_player_type(), PlayerType::Bot(_)) {
+                    continue;
+                }
+                thread_state.lock().unwrap().searching = Some((new_board.clone(), new_players));
+                let bot_move = search_bot_move(&new_board, &new_players, &trees, &clock, move_number, depth, &thread_stop);
+                if thread_stop.load(Ordering::Relaxed) {
+                    break;
+                }
+                let mut state = thread_state.lock().unwrap();
+                state.results.push(((new_board, new_players), bot_move));
+                state.searching = None;
+            }
+        });
+        self.search = Some(PonderSearch {
+            position: (board.clone(), players),
+            state,
+            stop,
+            finish,
+            handle
+        });
+    }
+
+    /* The reply being searched is aborted, the thread is gone when this returns */
+    pub fn stop(&mut self) {
+        if let Some(search) = self.search.take() {
+            search.abort();
+        }
+    }
+
+    /* Pondered answer when the human played one of the searched replies, a new search otherwise */
+    pub fn get_bot_move(&mut self, record: &GameRecord, depth: usize) -> BotMove {
+        if let Some(search) = self.search.take() {
+            search.finish.store(true, Ordering::Relaxed);
+            let position = (record.get_board().clone(), *record.get_players());
+            let take_result = |state: &mut PonderState| {
+                state.results.iter().position(|(searched, _)| *searched == position)
+                    .map(|index| state.results.swap_remove(index).1)
+            };
+            let (found, searching) = {
+                let mut state = search.state.lock().unwrap();
+                let found = take_result(&mut state);
+                let searching = state.searching.as_ref() == Some(&position);
+                (found, searching)
+            };
+            if let Some(bot_move) = found {
+                search.abort();
+                return bot_move
+            } else if searching {
+                let state = Arc::clone(&search.state);
+                if search.handle.join().is_ok() {
+                    if let Some(bot_move) = take_result(&mut state.lock().unwrap()) {
+                        return bot_move
+                    }
+                }
+            } else {
+                search.abort();
+            }
+        }
+        record.get_bot_move(depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Algorithm, Player};
+    use crate::capture_rules::CaptureRules;
+    use crate::clock::TimeControl;
+
+    fn new_record(black: PlayerType, white: PlayerType) -> GameRecord {
+        let rules = CaptureRules::new(2, true, true, true, true);
+        let board = Board::parse_rows("19/19/19/19/19/19/19/19/8OX9/8XO9/19/19/19/19/19/19/19/19/19", 5, rules).unwrap();
+        let players = Players::new(Player::new(Color::Black, black), Player::new(Color::White, white), 10, rules, 1);
+        GameRecord::new(board, players, TimeControl::Unlimited)
+    }
+
+    #[test]
+    fn only_a_human_facing_a_bot_is_pondered_on() {
+        let bot = PlayerType::Bot(Algorithm::Minimax);
+        let mut ponder = Ponder::new(None);
+        ponder.start(&new_record(PlayerType::Human, bot), 2);
+        assert!(ponder.search.is_none());
+        let mut ponder = Ponder::new(Some(PonderMode::All));
+        ponder.start(&new_record(bot, bot), 2);
+        ponder.start(&new_record(PlayerType::Human, PlayerType::Human), 2);
+        assert!(ponder.search.is_none());
+    }
+
+    #[test]
+    fn stop_aborts_the_reply_being_searched() {
+        let mut ponder = Ponder::new(Some(PonderMode::All));
+        ponder.start(&new_record(PlayerType::Human, PlayerType::Bot(Algorithm::Minimax)), 10);
+        assert!(ponder.search.is_some());
+        ponder.stop();
+        assert!(ponder.search.is_none());
+    }
+
+    #[test]
+    fn pondered_answer_is_the_one_searched_after_the_move() {
+        for played_predicted in [true, false] {
+            let mut record = new_record(PlayerType::Human, PlayerType::Bot(Algorithm::Minimax));
+            let mut ponder = Ponder::new(Some(PonderMode::Predicted));
+            ponder.start(&record, 2);
+            let replies = predicted_replies(&None, record.get_board(), record.get_players());
+            let reply = if played_predicted { replies[0] } else { replies[replies.len() - 1] };
+            record.play(reply, 0, (None, None)).unwrap();
+            let (input, _) = ponder.get_bot_move(&record, 2);
+            assert!(ponder.search.is_none());
+            assert_eq!(input, record.get_bot_move(2).0);
+        }
+    }
+}
@@ -0,0 +1,130 @@
+use crate::board::Board;
+use crate::players::{Player, Players};
+use crate::capture_rules::CaptureRules;
+use crate::rules::update_game_status;
+use crate::error::PlacementError;
+use crate::leakser::{BOARD_LENGTH_LIMIT, CAPTURED_NB_LIMIT};
+
+/*
+** "<rows> <side> <captures> <rules>", for instance "7/7/2XO3/3X3/7/7/7 o 0,0 a5,c10,r2,t1"
+** rules are a alignement, c stones to capture, r capture range, t stones per turn,
+** followed by u (up to range), n (no capture win), b (no break), f (unsafe flank) and d (double threes allowed) when set
+*/
+pub fn format_position(board: &Board, players: &Players) -> String {
+    let capture_rules = board.get_capture_rules();
+    let mut rules = vec![
+        format!("a{}", board.get_alignement_nb()),
+        format!("c{}", players.get_captured_nb()),
+        format!("r{}", capture_rules.get_range()),
+        format!("t{}", players.get_stones_per_turn())
+    ];
+    for (name, set) in [
+        ("u", !capture_rules.is_exact_range()),
+        ("n", !capture_rules.is_capture_win()),
+        ("b", !capture_rules.is_break_alignement()),
+        ("f", !capture_rules.is_safe_flank()),
+        ("d", !players.is_double_three_forbidden())
+    ] {
+        if set {
+            rules.push(name.to_string());
+        }
+    }
+    format!("{} {} {}", board.format_rows(), players.format_state(), rules.join(","))
+}
+
+pub fn parse_position(value: &str, player1: Player, player2: Player) -> Result<(Board, Players), PlacementError> {
+    let fields: Vec<&str> = value.split_whitespace().collect();
+    let (rows, side, captures, rules) = match fields.as_slice() {
+        [rows, side, captures, rules] => (*rows, *side, *captures, *rules),
+        _ => return Err(PlacementError::IncorrectPosition)
+    };
+    let (mut alignement_nb, mut captured_nb, mut range, mut stones_per_turn) = (None, None, None, None);
+    let (mut up_to_range, mut no_capture_win, mut no_break, mut unsafe_flank, mut double_three) = (false, false, false, false, false);
+    for rule in rules.split(',') {
+        let (name, number) = rule.split_at(rule.find(|c: char| c.is_ascii_digit()).unwrap_or(rule.len()));
+        let number = if number.is_empty() { None } else { number.parse::<usize>().ok() };
+        match (name, number) {
+            ("a", Some(x)) => alignement_nb = Some(x),
+            ("c", Some(x)) => captured_nb = Some(x),
+            ("r", Some(x)) => range = Some(x),
+            ("t", Some(x)) => stones_per_turn = Some(x),
+            ("u", None) => up_to_range = true,
+            ("n", None) => no_capture_win = true,
+            ("b", None) => no_break = true,
+            ("f", None) => unsafe_flank = true,
+            ("d", None) => double_three = true,
+            _ => return Err(PlacementError::IncorrectPosition)
+        }
+    }
+    let (alignement_nb, captured_nb, range, stones_per_turn) = match (alignement_nb, captured_nb, range, stones_per_turn) {
+        (Some(a), Some(c), Some(r), Some(t)) => (a, c, r, t),
+        _ => return Err(PlacementError::IncorrectPosition)
+    };
+    let capture_rules = CaptureRules::new(range, !up_to_range, !no_capture_win, !no_break, !unsafe_flank);
+    let board = Board::parse_rows(rows, alignement_nb, capture_rules)?;
+    let longest = board.get_width().max(board.get_height());
+    if !(3..=BOARD_LENGTH_LIMIT).contains(&longest) || longest < range + 2
+        || !(2..=longest).contains(&alignement_nb) || range >= alignement_nb
+        || !(1..=CAPTURED_NB_LIMIT).contains(&captured_nb) || stones_per_turn < 1 {
+        return Err(PlacementError::IncorrectPosition)
+    }
+    let mut players = Players::new(player1, player2, captured_nb, capture_rules, stones_per_turn);
+    players.parse_state(side, captures)?;
+    players.set_double_three_forbidden(!double_three);
+    /* The status is recomputed as if the player not to move had just played */
+    let last_player = players.get_current_player().get_player_color().get_inverse_color();
+    update_game_status(&board, &mut players, last_player);
+    Ok((board, players))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::Color;
+    use crate::players::PlayerType;
+    use crate::rules::GameStatus;
+
+    fn parse(value: &str) -> Result<(Board, Players), PlacementError> {
+        parse_position(value, Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human))
+    }
+
+    #[test]
+    fn position_is_written_as_parsed() {
+        for value in [
+            "7/7/2XO3/3X3/7/7/7 o 0,0 a5,c10,r2,t1",
+            "9/9/9/4X4/3OX4/9/9 x 2,4 a5,c5,r3,t1,u,n,b,f",
+            "9/9/9/4X4/3OX4/9/9 o 0,0 a5,c10,r0,t1,d",
+            "6/6/2X3/2O3/6/6 o2 0,0 a6,c10,r2,t2",
+            "9/4X4/9 x 0,0 a4,c10,r1,t1"
+        ].iter() {
+            let (board, players) = parse(value).unwrap();
+            assert_eq!(format_position(&board, &players), *value);
+        }
+    }
+
+    #[test]
+    fn incomplete_or_inconsistent_positions_are_refused() {
+        for value in [
+            "7/7/7/7/7/7/7 x 0,0",
+            "7/7/7/7/7/7/7 x 0,0 a5,c10,r2",
+            "7/7/7/7/7/7/7 x 0,0 a5,c10,r2,t1,z",
+            "7/7/7/7/7/7/7 y 0,0 a5,c10,r2,t1",
+            "7/7/7/7/7/7/7 x3 0,0 a5,c10,r2,t2",
+            "7/7/7/7/7/7/7 x 0 a5,c10,r2,t1",
+            "7/7/7/7/7/7/7 x 0,0 a5,c10,r5,t1",
+            "7/7/7/7/7/7/7 x 0,0 a8,c10,r2,t1",
+            "7/7/7/7/7/7/7 x 0,0 a5,c0,r2,t1",
+            "7/7/7/7/7/7/7 x 0,0 a5,c10,r2,t0"
+        ].iter() {
+            assert_eq!(parse(value).err(), Some(PlacementError::IncorrectPosition), "{}", value);
+        }
+    }
+
+    #[test]
+    fn finished_position_keeps_its_winner() {
+        let (_, players) = parse("5/5/XXXXX/5/OO3 o 0,0 a5,c10,r2,t1").unwrap();
+        assert_eq!(players.get_game_status(), GameStatus::Finished(Some(Color::Black)));
+        let (_, players) = parse("5/5/1X3/5/5 x 0,10 a5,c10,r2,t1").unwrap();
+        assert_eq!(players.get_game_status(), GameStatus::Finished(Some(Color::White)));
+    }
+}
@@ -0,0 +1,319 @@
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::board::Board;
+use crate::players::{Algorithm, PlayerType, Players};
+use crate::rules::GameStatus;
+use crate::heuristic::heuristic;
+use crate::game_record::GameRecord;
+use crate::clock::TimeControl;
+use crate::notation::{format_input, parse_notation};
+use crate::position::parse_position;
+use crate::self_play::bot_players;
+use crate::serve::{color_name, search_json, status_json};
+use crate::json::{object, parse_json, Json};
+use crate::leakser::{leakser, MINMAX_DEPTH_LIMIT};
+use crate::error::{FlagError, PlacementError, ProtocolError};
+
+/* {"type": "PlacementError", "kind": "NotEmpty", "message": "Not Empty"}, the kind being the variant name */
+fn error_json<E: fmt::Debug + fmt::Display>(error_type: &str, e: &E) -> Json {
+    let kind = format!("{:?}", e);
+    object(vec![
+        ("type", Json::String(error_type.to_string())),
+        ("kind", Json::String(kind.split('(').next().unwrap_or("").to_string())),
+        ("message", Json::String(e.to_string()))
+    ])
+}
+
+fn placement_error(e: PlacementError) -> Json {
+    error_json("PlacementError", &e)
+}
+
+fn protocol_error(e: ProtocolError) -> Json {
+    error_json("ProtocolError", &e)
+}
+
+fn flag_error(e: FlagError, flag: Option<&String>) -> Json {
+    let mut error = error_json("FlagError", &e);
+    if let (Json::Object(fields), Some(flag)) = (&mut error, flag) {
+        fields.push(("flag".to_string(), Json::String(flag.clone())));
+    }
+    error
+}
+
+/* Every field but "cmd" and "id" becomes a flag: {"size": 15, "no-break": true} is "--size 15 --no-break" */
+fn new_game(request: &Json) -> Result<(GameRecord, usize), Json> {
+    let mut flags = vec![];
+    if let Json::Object(fields) = request {
+        for (name, value) in fields.iter().filter(|(name, _)| name != "cmd" && name != "id") {
+            if name == "help" || name == "rules" {
+                return Err(flag_error(FlagError::WrongFlag, Some(name)))
+            }
+            match value {
+                Json::Bool(true) => flags.push(format!("--{}", name)),
+                Json::Bool(false) => (),
+                Json::String(value) => flags.extend([format!("--{}", name), value.clone()]),
+                Json::Number(_) => flags.extend([format!("--{}", name), value.to_string()]),
+                _ => return Err(protocol_error(ProtocolError::IncorrectField(name.clone())))
+            }
+        }
+    }
+    let config = leakser(&mut flags)
+        .map_err(|(e, f)| flag_error(e, flags.get(f)))?;
+    let record = match config.position {
+        Some(position) => {
+            let (board, players) = parse_position(&position, config.player1, config.player2).map_err(placement_error)?;
+            GameRecord::new(board, players, config.time_control)
+        },
+        None => GameRecord::new(
+            Board::new(config.width, config.height, config.alignement_nb, config.capture_rules),
+            Players::new(config.player1, config.player2, config.captured_nb, config.capture_rules, config.stones_per_turn),
+            config.time_control
+        )
+    };
+    Ok((record, config.depth))
+}
+
+fn get_move(request: &Json, board: &Board) -> Result<(usize, usize), Json> {
+    let word = request.get("move").ok_or_else(|| protocol_error(ProtocolError::MissingField("move".to_string())))?;
+    let word = word.as_str().ok_or_else(|| protocol_error(ProtocolError::IncorrectField("move".to_string())))?;
+    parse_notation(word, board).map_err(placement_error)
+}
+
+fn play(request: &Json, record: &mut GameRecord) -> Result<Json, Json> {
+    if matches!(record.get_players().get_game_status(), GameStatus::Finished(_)) {
+        return Err(protocol_error(ProtocolError::GameFinished))
+    }
+    let input = get_move(request, record.get_board())?;
+    record.play(input, 0, (None, None)).map_err(placement_error)?;
+    Ok(status_json(record))
+}
+
+/* Searches a copy of the game with bot players, "time" being a clock such as "move:5" */
+fn go(request: &Json, record: &GameRecord, default_depth: usize) -> Result<Json, Json> {
+    if matches!(record.get_players().get_game_status(), GameStatus::Finished(_)) {
+        return Err(protocol_error(ProtocolError::GameFinished))
+    }
+    let depth = match request.get("depth") {
+        None => default_depth,
+        Some(value) => match value.as_usize() {
+            Some(depth) if (1..=MINMAX_DEPTH_LIMIT).contains(&depth) => depth,
+            _ => return Err(flag_error(FlagError::IncorectDepth, None))
+        }
+    };
+    let time_control = match request.get("time") {
+        None => record.get_clock().get_time_control(),
+        Some(value) => value.as_str().and_then(TimeControl::parse).ok_or_else(|| protocol_error(ProtocolError::IncorrectField("time".to_string())))?
+    };
+    let players = record.get_players();
+    let algorithm = match players.get_current_player().get_player_type() {
+        PlayerType::Bot(algorithm) => algorithm,
+        _ => Algorithm::basic_algorithm()
+    };
+    let search = GameRecord::new(record.get_board().clone(), bot_players(players, algorithm, algorithm), time_control);
+    Ok(search_json(&search, depth))
+}
+
+fn eval(record: &GameRecord) -> Json {
+    let color = record.get_players().get_current_player().get_player_color();
+    object(vec![
+        ("side", color_name(color)),
+        ("score", Json::Number(heuristic(record.get_board(), record.get_players(), color) as f64))
+    ])
+}
+
+fn legal_moves(record: &GameRecord) -> Json {
+    let board = record.get_board();
+    let moves: Vec<Json> = match record.get_players().get_game_status() {
+        GameStatus::Finished(_) => vec![],
+        _ => board.legal_moves(record.get_players()).into_iter()
+            .map(|input| Json::String(format_input(input, board)))
+            .collect()
+    };
+    object(vec![("moves", Json::Array(moves))])
+}
+
+/* Answers one command, the boolean being true when the session must end */
+fn execute(line: &str, record: &mut GameRecord, depth: &mut usize) -> (Json, bool) {
+    let request = match parse_json(line) {
+        Some(request @ Json::Object(_)) => request,
+        _ => return (object(vec![("ok", Json::Bool(false)), ("error", protocol_error(ProtocolError::IncorrectJson))]), false)
+    };
+    let command = request.get("cmd").and_then(|x| x.as_str()).unwrap_or("");
+    let result = match command {
+        "new_game" => new_game(&request).map(|(new_record, new_depth)| {
+            *record = new_record;
+            *depth = new_depth;
+            status_json(record)
+        }),
+        "play" => play(&request, record),
+        "undo" => if record.undo() {
+            Ok(status_json(record))
+        } else {
+            Err(placement_error(PlacementError::NothingToUndo))
+        },
+        "go" => go(&request, record, *depth),
+        "eval" => Ok(eval(record)),
+        "legal_moves" => Ok(legal_moves(record)),
+        "status" => Ok(status_json(record)),
+        "quit" => Ok(Json::Object(vec![])),
+        "" => Err(protocol_error(ProtocolError::IncorrectJson)),
+        _ => Err(protocol_error(ProtocolError::UnknownCommand(command.to_string())))
+    };
+    let mut fields = vec![("ok".to_string(), Json::Bool(result.is_ok()))];
+    if let Some(id) = request.get("id") {
+        fields.push(("id".to_string(), id.clone()));
+    }
+    match result {
+        Ok(Json::Object(values)) => fields.extend(values),
+        Ok(value) => fields.push(("result".to_string(), value)),
+        Err(error) => fields.push(("error".to_string(), error))
+    }
+    (Json::Object(fields), command == "quit")
+}
+
+/* One JSON command per line on stdin, one JSON answer per line on stdout */
+pub fn run_protocol(record: &mut GameRecord, depth: usize) {
+    let mut depth = depth;
+    let stdin = io::stdin();
+    let mut stdout = io::stdout();
+    for line in stdin.lock().lines() {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => break
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (response, quit) = execute(&line, record, &mut depth);
+        if writeln!(stdout, "{}", response).and_then(|_| stdout.flush()).is_err() || quit {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_session() -> (GameRecord, usize) {
+        let mut session = new_game(&object(vec![])).unwrap();
+        let (response, quit) = execute(r#"{"cmd": "new_game", "size": 9, "depth": 2}"#, &mut session.0, &mut session.1);
+        assert_eq!(response.get("ok"), Some(&Json::Bool(true)));
+        assert!(!quit);
+        session
+    }
+
+    fn error_kind(response: &Json) -> Option<&str> {
+        assert_eq!(response.get("ok"), Some(&Json::Bool(false)));
+        response.get("error").and_then(|x| x.get("kind")).and_then(|x| x.as_str())
+    }
+
+    #[test]
+    fn new_game_takes_its_fields_as_flags() {
+        let (record, depth) = new_session();
+        assert_eq!((record.get_board().get_width(), record.get_board().get_height()), (9, 9));
+        assert_eq!(depth, 2);
+        let (mut record, mut depth) = new_session();
+        let (response, _) = execute(r#"{"cmd": "new_game", "depth": 99}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("IncorectDepth"));
+        let (response, _) = execute(r#"{"cmd": "new_game", "depth": "deep"}"#, &mut record, &mut depth);
+        assert_eq!(response.get("error").and_then(|x| x.get("type")), Some(&Json::String("FlagError".to_string())));
+        assert_eq!(response.get("error").and_then(|x| x.get("flag")), Some(&Json::String("deep".to_string())));
+        let (response, _) = execute(r#"{"cmd": "new_game", "help": true}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("WrongFlag"));
+        let (response, _) = execute(r#"{"cmd": "new_game", "size": [9]}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("IncorrectField"));
+        assert_eq!(record.get_board().get_width(), 9);
+    }
+
+    #[test]
+    fn play_and_undo_answer_the_status() {
+        let (mut record, mut depth) = new_session();
+        let (response, _) = execute(r#"{"cmd": "play", "move": "e5", "id": 7}"#, &mut record, &mut depth);
+        assert_eq!(response.get("ok"), Some(&Json::Bool(true)));
+        assert_eq!(response.get("id"), Some(&Json::Number(7.0)));
+        assert_eq!(response.get("side"), Some(&Json::String("o".to_string())));
+        let (response, _) = execute(r#"{"cmd": "play", "move": "e5"}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("NotEmpty"));
+        let (response, _) = execute(r#"{"cmd": "play", "move": "z42"}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("OutOfBounds"));
+        let (response, _) = execute(r#"{"cmd": "play"}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("MissingField"));
+        let (response, _) = execute(r#"{"cmd": "undo"}"#, &mut record, &mut depth);
+        assert_eq!(response.get("side"), Some(&Json::String("x".to_string())));
+        let (response, _) = execute(r#"{"cmd": "undo"}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("NothingToUndo"));
+    }
+
+    #[test]
+    fn go_searches_without_playing() {
+        let (mut record, mut depth) = new_session();
+        execute(r#"{"cmd": "play", "move": "e5"}"#, &mut record, &mut depth);
+        let (response, _) = execute(r#"{"cmd": "go", "depth": 1}"#, &mut record, &mut depth);
+        assert_eq!(response.get("ok"), Some(&Json::Bool(true)));
+        assert_eq!(response.get("depth"), Some(&Json::Number(1.0)));
+        let word = response.get("move").and_then(|x| x.as_str()).unwrap();
+        assert!(record.get_board().check_add_value(parse_notation(word, record.get_board()).unwrap(), record.get_players()).is_ok());
+        assert_eq!(record.get_line().len(), 1);
+        let (response, _) = execute(r#"{"cmd": "go", "depth": 0}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("IncorectDepth"));
+        let (response, _) = execute(r#"{"cmd": "go", "time": "soon"}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("IncorrectField"));
+    }
+
+    #[test]
+    fn eval_legal_moves_and_status_describe_the_position() {
+        let (mut record, mut depth) = new_session();
+        let (response, _) = execute(r#"{"cmd": "legal_moves"}"#, &mut record, &mut depth);
+        assert!(matches!(response.get("moves"), Some(Json::Array(moves)) if moves.len() == 81));
+        execute(r#"{"cmd": "play", "move": "e5"}"#, &mut record, &mut depth);
+        let (response, _) = execute(r#"{"cmd": "legal_moves"}"#, &mut record, &mut depth);
+        assert!(matches!(response.get("moves"), Some(Json::Array(moves)) if moves.len() == 80 && !moves.contains(&Json::String("e5".to_string()))));
+        let (response, _) = execute(r#"{"cmd": "eval"}"#, &mut record, &mut depth);
+        assert_eq!(response.get("side"), Some(&Json::String("o".to_string())));
+        let score = heuristic(record.get_board(), record.get_players(), crate::color::Color::White);
+        assert_eq!(response.get("score"), Some(&Json::Number(score as f64)));
+        let (response, _) = execute(r#"{"cmd": "status"}"#, &mut record, &mut depth);
+        assert_eq!(response, {
+            let mut fields = vec![("ok".to_string(), Json::Bool(true))];
+            if let Json::Object(values) = status_json(&record) {
+                fields.extend(values);
+            }
+            Json::Object(fields)
+        });
+        assert_eq!(response.get("finished"), Some(&Json::Bool(false)));
+    }
+
+    #[test]
+    fn finished_games_refuse_play_and_go() {
+        let (mut record, mut depth) = new_session();
+        for word in ["a1", "a9", "b1", "b9", "c1", "c9", "d1", "d9", "e1"].iter() {
+            execute(&format!(r#"{{"cmd": "play", "move": "{}"}}"#, word), &mut record, &mut depth);
+        }
+        let (response, _) = execute(r#"{"cmd": "status"}"#, &mut record, &mut depth);
+        assert_eq!(response.get("winner"), Some(&Json::String("x".to_string())));
+        let (response, _) = execute(r#"{"cmd": "legal_moves"}"#, &mut record, &mut depth);
+        assert_eq!(response.get("moves"), Some(&Json::Array(vec![])));
+        for line in [r#"{"cmd": "play", "move": "f5"}"#, r#"{"cmd": "go"}"#].iter() {
+            let (response, _) = execute(line, &mut record, &mut depth);
+            assert_eq!(error_kind(&response), Some("GameFinished"));
+        }
+    }
+
+    #[test]
+    fn malformed_lines_are_answered_with_an_error() {
+        let (mut record, mut depth) = new_session();
+        for line in ["{\"cmd\": \"play\"", "[1, 2]", "play e5", "{}", r#"{"cmd": 3}"#].iter() {
+            let (response, quit) = execute(line, &mut record, &mut depth);
+            assert_eq!(error_kind(&response), Some("IncorrectJson"));
+            assert!(!quit);
+        }
+        let (response, _) = execute(r#"{"cmd": "resign", "id": "a"}"#, &mut record, &mut depth);
+        assert_eq!(error_kind(&response), Some("UnknownCommand"));
+        assert_eq!(response.get("id"), Some(&Json::String("a".to_string())));
+        let (response, quit) = execute(r#"{"cmd": "quit"}"#, &mut record, &mut depth);
+        assert_eq!(response, object(vec![("ok", Json::Bool(true))]));
+        assert!(quit);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::board::Input;
+use crate::error::PlacementError;
+use crate::leakser::BOARD_LENGTH_LIMIT;
+
+/*
+** Piskvork records: a "Piskvork <width>x<height>, <x>:<y>, <0|1>" header,
+** one "x,y,time" line per move, 1-based from the top left with the time in milliseconds,
+** then the engine names and the result which are ignored
+*/
+pub type PsqGame = ((usize, usize), Vec<(Input, u128)>);
+
+pub fn is_psq(path: &str) -> bool {
+    path.to_lowercase().ends_with(".psq")
+}
+
+/* RenLib libraries are binary trees of variations, they are not read */
+pub fn is_renlib(path: &str) -> bool {
+    path.to_lowercase().ends_with(".lib")
+}
+
+fn parse_header(line: &str) -> Option<(usize, usize)> {
+    let size = line.strip_prefix("Piskvork")?.split(',').next()?.trim();
+    let (width, height) = size.split_once('x')?;
+    match (width.parse::<usize>(), height.parse::<usize>()) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 && width.max(height) <= BOARD_LENGTH_LIMIT => Some((width, height)),
+        _ => None
+    }
+}
+
+fn parse_move(line: &str) -> Option<(Input, u128)> {
+    let values: Vec<usize> = line.split(',').map(|x| x.trim().parse::<usize>()).collect::<Result<_, _>>().ok()?;
+    match values.as_slice() {
+        [x, y, time] if *x > 0 && *y > 0 => Some(((x - 1, y - 1), *time as u128 * 1000)),
+        [x, y] if *x > 0 && *y > 0 => Some(((x - 1, y - 1), 0)),
+        _ => None
+    }
+}
+
+/* Board size and moves with their time in microseconds, the moves stop at the first line that is not one */
+pub fn parse_psq(content: &str) -> Result<PsqGame, PlacementError> {
+    let mut lines = content.lines().map(|x| x.trim()).filter(|x| !x.is_empty());
+    let size = lines.next().and_then(parse_header).ok_or(PlacementError::IncorrectFile)?;
+    let moves: Vec<(Input, u128)> = lines.map_while(parse_move).collect();
+    if moves.iter().any(|((x, y), _)| *x >= size.0 || *y >= size.1) {
+        return Err(PlacementError::OutOfBounds)
+    }
+    Ok((size, moves))
+}
+
+pub fn format_psq(size: (usize, usize), moves: &[(Input, u128)]) -> String {
+    let last = moves.last().map(|x| x.0).unwrap_or((size.0 / 2, size.1 / 2));
+    let mut content = format!("Piskvork {}x{}, {}:{}, 0\n", size.0, size.1, last.0 + 1, last.1 + 1);
+    for ((x, y), time) in moves.iter() {
+        content += &format!("{},{},{}\n", x + 1, y + 1, time / 1000);
+    }
+    content += "-1\n";
+    content
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn psq_is_read_back() {
+        let moves = vec![((9, 9), 1_000), ((10, 8), 2_500_000), ((0, 14), 0)];
+        let content = format_psq((20, 15), &moves);
+        assert_eq!(content, "Piskvork 20x15, 1:15, 0\n10,10,1\n11,9,2500\n1,15,0\n-1\n");
+        assert_eq!(parse_psq(&content), Ok(((20, 15), moves)));
+        assert_eq!(parse_psq(&format_psq((19, 19), &[])), Ok(((19, 19), vec![])));
+    }
+
+    #[test]
+    fn moves_stop_at_the_first_other_line() {
+        let content = "Piskvork 15x15, 11:11, 0\r\n8,8,120\r\n\r\n9,9\r\n-1\r\npbrain-a.exe\r\n-1\r\n";
+        assert_eq!(parse_psq(content), Ok(((15, 15), vec![((7, 7), 120_000), ((8, 8), 0)])));
+    }
+
+    #[test]
+    fn broken_psq_is_refused() {
+        assert_eq!(parse_psq("Gomoku 15x15\n8,8,0\n"), Err(PlacementError::IncorrectFile));
+        assert_eq!(parse_psq("Piskvork 0x15, 1:1, 0\n"), Err(PlacementError::IncorrectFile));
+        assert_eq!(parse_psq(&format!("Piskvork {}x1, 1:1, 0\n", BOARD_LENGTH_LIMIT + 1)), Err(PlacementError::IncorrectFile));
+        assert_eq!(parse_psq("Piskvork 15x15, 1:1, 0\n16,1,0\n"), Err(PlacementError::OutOfBounds));
+    }
+}
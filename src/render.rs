@@ -0,0 +1,469 @@
+use std::fs;
+use std::fmt::Write;
+
+use image::{Delay, Frame, ImageBuffer, Rgba, RgbaImage};
+use image::codecs::gif::{GifEncoder, Repeat};
+use rusttype::{point, Font, Scale};
+
+use crate::board::{Board, Input, Tile};
+use crate::players::Players;
+use crate::color::Color;
+use crate::rules::GameStatus;
+use crate::game_record::GameRecord;
+use crate::view::View;
+use crate::leakser::DEPTH_SUGGESTION;
+use crate::error::{FlagError, PlacementError};
+
+const FONT: &[u8] = include_bytes!("../assets/AlegreyaSansSC-ExtraBold.ttf");
+const LAST_MOVE_COLOR: [f32; 4] = [0.85, 0.1, 0.1, 1.0];
+const SUGGESTION_COLOR: [f32; 4] = [0.19, 0.67, 0.06, 1.0];
+const FORBIDDEN_COLOR: [f32; 4] = [0.67, 0.19, 0.06, 1.0];
+const WINNING_LINE_COLOR: [f32; 4] = [0.95, 0.75, 0.1, 1.0];
+const FRAME_DELAY: u32 = 800;
+const LAST_FRAME_DELAY: u32 = 3000;
+/* Fastest quantization, the boards only have a few colors */
+const GIF_SPEED: i32 = 30;
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ImageFormat {
+    Svg,
+    Png,
+    Gif
+}
+
+impl ImageFormat {
+    /* Only replays can be animated */
+    pub fn from_path(path: &str, animated: bool) -> Result<ImageFormat, PlacementError> {
+        match path.rsplit('.').next().map(|x| x.to_lowercase()) {
+            Some(extension) if extension == "svg" => Ok(ImageFormat::Svg),
+            Some(extension) if extension == "png" => Ok(ImageFormat::Png),
+            Some(extension) if extension == "gif" && animated => Ok(ImageFormat::Gif),
+            _ => Err(PlacementError::IncorrectImage)
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Debug)]
+enum Shape {
+    /* [x1, y1, x2, y2], thickness */
+    Line([f64; 4], f64, [f32; 4]),
+    /* [x, y, radius] */
+    Disc([f64; 3], [f32; 4]),
+    Ring([f64; 3], f64, [f32; 4]),
+    /* Centered on [x, y], the last value being the font size */
+    Text(String, [f64; 3], [f32; 4])
+}
+
+impl Shape {
+    fn shift(&mut self, dx: f64, dy: f64) {
+        match self {
+            Shape::Line(line, _, _) => *line = [line[0] - dx, line[1] - dy, line[2] - dx, line[3] - dy],
+            Shape::Disc(disc, _) | Shape::Ring(disc, _, _) | Shape::Text(_, disc, _) => {
+                disc[0] -= dx;
+                disc[1] -= dy;
+            }
+        }
+    }
+}
+
+/* A board drawn with the window's geometry, as shapes any backend can paint */
+pub struct Picture {
+    width: u32,
+    height: u32,
+    background: [f32; 4],
+    shapes: Vec<Shape>
+}
+
+impl Picture {
+    /* `numbers` gives the move number of each tile's stone, indexed like the board */
+    pub fn new(board: &Board, players: &Players, numbers: &[Option<usize>], last_input: Option<Input>, suggestion: Option<Input>) -> Picture {
+        let view = View::new(board);
+        let finished = matches!(players.get_game_status(), GameStatus::Finished(_));
+        let mut shapes = vec![];
+        let cell = view.get_cell_size();
+        let end_x = view.get_grid_end_x() - view.get_imprecision();
+        let end_y = view.get_grid_end_y() - view.get_imprecision();
+        for i in 0..board.get_width() {
+            let x = i as f64 * cell + cell / 2.0 + view.get_grid_start_x();
+            shapes.push(Shape::Line([x, view.get_grid_start_y(), x, end_y], view.get_grid_thickness(), view.black_color(false)));
+        }
+        for i in 0..board.get_height() {
+            let y = i as f64 * cell + cell / 2.0 + view.get_grid_start_y();
+            shapes.push(Shape::Line([view.get_grid_start_x(), y, end_x, y], view.get_grid_thickness(), view.black_color(false)));
+        }
+        for (i, tile) in board.get_board().iter().enumerate() {
+            let input = board.get_input(i);
+            let [x, y, radius] = view.stone_disc(input);
+            match tile {
+                Tile::Color(color) => {
+                    let (fill, ink) = match color {
+                        Color::Black => (view.black_color(false), view.white_color(false)),
+                        Color::White => (view.white_color(false), view.black_color(false))
+                    };
+                    shapes.push(Shape::Disc([x, y, radius], fill));
+                    if let Some(number) = numbers.get(i).copied().flatten() {
+                        shapes.push(Shape::Text(number.to_string(), [x, y, radius * 1.2], ink));
+                    }
+                    if last_input == Some(input) {
+                        shapes.push(Shape::Ring([x, y, radius], radius / 6.0, LAST_MOVE_COLOR));
+                    }
+                },
+                _ if suggestion == Some(input) => shapes.push(Shape::Disc([x, y, radius], SUGGESTION_COLOR)),
+                _ if !finished && board.check_add_value(input, players).is_err() => shapes.push(Shape::Disc([x, y, radius / 3.0], FORBIDDEN_COLOR)),
+                _ => ()
+            }
+        }
+        if let GameStatus::Finished(Some(winner)) = players.get_game_status() {
+            for input in board.get_alignement(winner).unwrap_or_default() {
+                let [x, y, radius] = view.stone_disc(input);
+                shapes.push(Shape::Ring([x, y, radius], radius / 4.0, WINNING_LINE_COLOR));
+            }
+        }
+        /* Above the grid, the stone of each color followed by its captures */
+        if players.get_capture_rules().is_enabled() {
+            let y = view.get_grid_start_y() - view.get_margin() / 2.0;
+            let radius = view.get_margin() / 5.0;
+            for (color, x) in [(Color::Black, view.get_grid_start_x()), (Color::White, view.get_grid_end_x() - view.get_margin() * 1.5)] {
+                let fill = match color {
+                    Color::Black => view.black_color(false),
+                    Color::White => view.white_color(false)
+                };
+                let captured = format!("{}/{}", players.get_player(color).get_player_captured(), players.get_captured_nb());
+                shapes.push(Shape::Disc([x + radius, y, radius], fill));
+                shapes.push(Shape::Text(captured, [x + radius * 5.0, y, radius * 1.8], view.white_color(false)));
+            }
+        }
+        /* The window centers the grid, the picture only keeps a margin around it */
+        let left = view.get_grid_start_x() - view.get_margin();
+        let top = view.get_grid_start_y() - view.get_margin();
+        shapes.iter_mut().for_each(|shape| shape.shift(left, top));
+        Picture {
+            width: (view.get_grid_end_x() - left + view.get_margin()) as u32,
+            height: (view.get_grid_end_y() - top + view.get_margin()) as u32,
+            background: view.get_background_color(),
+            shapes
+        }
+    }
+
+    /* The current position of the record, its stones numbered in the order they were played */
+    pub fn from_record(record: &GameRecord, suggestion: Option<Input>) -> Picture {
+        Picture::new(record.get_board(), record.get_players(), &record.get_move_numbers(), record.get_last_input().copied(), suggestion)
+    }
+
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\">\n", self.width, self.height, self.width, self.height);
+        let _ = writeln!(svg, "<rect width=\"100%\" height=\"100%\" {}/>", svg_paint("fill", self.background));
+        for shape in self.shapes.iter() {
+            let _ = match shape {
+                Shape::Line([x1, y1, x2, y2], thickness, color) =>
+                    writeln!(svg, "<line x1=\"{:.1}\" y1=\"{:.1}\" x2=\"{:.1}\" y2=\"{:.1}\" stroke-width=\"{:.1}\" {}/>", x1, y1, x2, y2, thickness, svg_paint("stroke", *color)),
+                Shape::Disc([x, y, radius], color) =>
+                    writeln!(svg, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" {}/>", x, y, radius, svg_paint("fill", *color)),
+                Shape::Ring([x, y, radius], thickness, color) =>
+                    writeln!(svg, "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"{:.1}\" fill=\"none\" stroke-width=\"{:.1}\" {}/>", x, y, radius, thickness, svg_paint("stroke", *color)),
+                Shape::Text(text, [x, y, size], color) =>
+                    writeln!(svg, "<text x=\"{:.1}\" y=\"{:.1}\" font-size=\"{:.1}\" font-family=\"sans-serif\" font-weight=\"bold\" text-anchor=\"middle\" dominant-baseline=\"central\" {}>{}</text>", x, y, size, svg_paint("fill", *color), text)
+            };
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn to_pixels(&self) -> RgbaImage {
+        let mut canvas = ImageBuffer::from_pixel(self.width, self.height, to_rgba(self.background));
+        let font = Font::try_from_bytes(FONT);
+        for shape in self.shapes.iter() {
+            match shape {
+                Shape::Line(coords, thickness, color) => paint_line(&mut canvas, *coords, *thickness, *color),
+                Shape::Disc([x, y, radius], color) => paint_circle(&mut canvas, [*x, *y, *radius], *color, |d| radius - d + 0.5),
+                Shape::Ring([x, y, radius], thickness, color) => paint_circle(&mut canvas, [*x, *y, radius + thickness], *color, |d| thickness / 2.0 - (d - radius).abs() + 0.5),
+                Shape::Text(text, [x, y, size], color) => if let Some(font) = font.as_ref() {
+                    paint_text(&mut canvas, font, text, [*x, *y, *size], *color);
+                }
+            }
+        }
+        canvas
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), PlacementError> {
+        match ImageFormat::from_path(path, false)? {
+            ImageFormat::Svg => fs::write(path, self.to_svg()).map_err(|_| PlacementError::IncorrectFile),
+            _ => self.to_pixels().save(path).map_err(|_| PlacementError::IncorrectFile)
+        }
+    }
+}
+
+fn svg_paint(attribute: &str, color: [f32; 4]) -> String {
+    let [r, g, b, a] = color.map(|x| (x.clamp(0.0, 1.0) * 255.0).round() as u8);
+    match a {
+        255 => format!("{}=\"#{:02x}{:02x}{:02x}\"", attribute, r, g, b),
+        _ => format!("{}=\"#{:02x}{:02x}{:02x}\" {}-opacity=\"{:.2}\"", attribute, r, g, b, attribute, a as f32 / 255.0)
+    }
+}
+
+fn to_rgba(color: [f32; 4]) -> Rgba<u8> {
+    Rgba(color.map(|x| (x.clamp(0.0, 1.0) * 255.0).round() as u8))
+}
+
+/* Mixes `color` over the pixel, `coverage` being the part of the pixel inside the shape */
+fn blend(canvas: &mut RgbaImage, x: i64, y: i64, color: [f32; 4], coverage: f64) {
+    if x < 0 || y < 0 || x >= canvas.width() as i64 || y >= canvas.height() as i64 || coverage <= 0.0 {
+        return
+    }
+    let alpha = coverage.min(1.0) as f32 * color[3];
+    let pixel = canvas.get_pixel_mut(x as u32, y as u32);
+    for (channel, value) in pixel.0.iter_mut().zip(color.iter()).take(3) {
+        let current = *channel as f32 / 255.0;
+        *channel = ((current * (1.0 - alpha) + value * alpha) * 255.0).round() as u8;
+    }
+    pixel.0[3] = 255;
+}
+
+/* Anti-aliased from the distance of each pixel center to [x, y], nothing is painted further than `reach` */
+fn paint_circle<F: Fn(f64) -> f64>(canvas: &mut RgbaImage, center: [f64; 3], color: [f32; 4], coverage: F) {
+    let reach = center[2].ceil() as i64 + 1;
+    let (cx, cy) = (center[0] as i64, center[1] as i64);
+    for y in cy - reach..=cy + reach {
+        for x in cx - reach..=cx + reach {
+            let distance = ((x as f64 + 0.5 - center[0]).powi(2) + (y as f64 + 0.5 - center[1]).powi(2)).sqrt();
+            blend(canvas, x, y, color, coverage(distance));
+        }
+    }
+}
+
+fn paint_line(canvas: &mut RgbaImage, coords: [f64; 4], thickness: f64, color: [f32; 4]) {
+    let [x1, y1, x2, y2] = coords;
+    let half = thickness / 2.0;
+    let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt().max(f64::EPSILON);
+    for y in (y1.min(y2) - half).floor() as i64..=(y1.max(y2) + half).ceil() as i64 {
+        for x in (x1.min(x2) - half).floor() as i64..=(x1.max(x2) + half).ceil() as i64 {
+            let (px, py) = (x as f64 + 0.5, y as f64 + 0.5);
+            let along = (((px - x1) * (x2 - x1) + (py - y1) * (y2 - y1)) / length).clamp(0.0, length);
+            let distance = ((px - x1 - along * (x2 - x1) / length).powi(2) + (py - y1 - along * (y2 - y1) / length).powi(2)).sqrt();
+            blend(canvas, x, y, color, half - distance + 0.5);
+        }
+    }
+}
+
+fn paint_text(canvas: &mut RgbaImage, font: &Font, text: &str, position: [f64; 3], color: [f32; 4]) {
+    let scale = Scale::uniform(position[2] as f32);
+    let metrics = font.v_metrics(scale);
+    let width: f32 = font.layout(text, scale, point(0.0, 0.0)).map(|x| x.unpositioned().h_metrics().advance_width).sum();
+    let origin = point(position[0] as f32 - width / 2.0, position[1] as f32 + (metrics.ascent + metrics.descent) / 2.0);
+    for glyph in font.layout(text, scale, origin) {
+        if let Some(bounds) = glyph.pixel_bounding_box() {
+            glyph.draw(|x, y, coverage| blend(canvas, bounds.min.x as i64 + x as i64, bounds.min.y as i64 + y as i64, color, coverage as f64));
+        }
+    }
+}
+
+pub fn export_image(record: &GameRecord, suggestion: Option<Input>, path: &str) -> Result<(), PlacementError> {
+    Picture::from_record(record, suggestion).save(path)
+}
+
+/* One picture per position of the current line up to the current move, which the record is back on afterwards */
+fn replay_pictures(record: &mut GameRecord) -> Vec<Picture> {
+    let last = record.get_move_number();
+    let pictures = (0..=last).map(|move_number| {
+        record.jump_to(move_number);
+        Picture::from_record(record, None)
+    }).collect();
+    record.jump_to(last);
+    pictures
+}
+
+/* An animated .gif, or "game-000.png", "game-001.png"... for a .png or .svg path, returns the number of frames */
+pub fn export_replay(record: &mut GameRecord, path: &str, delay: u32) -> Result<usize, PlacementError> {
+    let format = ImageFormat::from_path(path, true)?;
+    let pictures = replay_pictures(record);
+    if format == ImageFormat::Gif {
+        let file = fs::File::create(path).map_err(|_| PlacementError::IncorrectFile)?;
+        let mut encoder = GifEncoder::new_with_speed(file, GIF_SPEED);
+        encoder.set_repeat(Repeat::Infinite).map_err(|_| PlacementError::IncorrectFile)?;
+        for (i, picture) in pictures.iter().enumerate() {
+            let delay = if i + 1 == pictures.len() { delay.max(LAST_FRAME_DELAY) } else { delay };
+            encoder.encode_frame(Frame::from_parts(picture.to_pixels(), 0, 0, Delay::from_numer_denom_ms(delay, 1)))
+                .map_err(|_| PlacementError::IncorrectFile)?;
+        }
+        return Ok(pictures.len())
+    }
+    let (stem, extension) = path.rsplit_once('.').unwrap_or((path, ""));
+    let digits = (pictures.len() - 1).to_string().len().max(3);
+    for (i, picture) in pictures.iter().enumerate() {
+        picture.save(&format!("{}-{:0width$}.{}", stem, i, extension, width = digits))?;
+    }
+    Ok(pictures.len())
+}
+
+pub struct ExportConfig {
+    image: Option<String>,
+    replay: Option<String>,
+    delay: u32,
+    game: Option<String>,
+    move_number: Option<usize>
+}
+
+/* Takes the export options out of the flags, the other ones describe the board the game is replayed on */
+pub fn export_leakser(flags: &[String]) -> Result<(ExportConfig, Vec<String>), (FlagError, usize)> {
+    let mut config = ExportConfig {
+        image: None,
+        replay: None,
+        delay: FRAME_DELAY,
+        game: None,
+        move_number: None
+    };
+    let mut remaining = vec![];
+    let mut i = 0;
+    while i < flags.len() {
+        match flags[i].as_str() {
+            "--export-image" | "--replay" | "--delay" | "--game" | "--move" => {
+                if i + 1 >= flags.len() {
+                    return Err((FlagError::FlagNeedValue, i));
+                }
+                let value = flags[i + 1].as_str();
+                match flags[i].as_str() {
+                    "--export-image" => {
+                        ImageFormat::from_path(value, false).map_err(|_| (FlagError::IncorrectValue, i + 1))?;
+                        config.image = Some(value.to_string());
+                    },
+                    "--replay" => {
+                        ImageFormat::from_path(value, true).map_err(|_| (FlagError::IncorrectValue, i + 1))?;
+                        config.replay = Some(value.to_string());
+                    },
+                    "--game" => config.game = Some(value.to_string()),
+                    "--delay" => match value.parse::<u32>() {
+                        Ok(delay) if delay > 0 => config.delay = delay,
+                        Ok(_) => return Err((FlagError::IncorrectValue, i + 1)),
+                        _ => return Err((FlagError::NoNumberValue, i + 1))
+                    },
+                    _ => match value.parse::<usize>() {
+                        Ok(move_number) => config.move_number = Some(move_number),
+                        _ => return Err((FlagError::NoNumberValue, i + 1))
+                    }
+                }
+                i += 1;
+            },
+            _ => remaining.push(flags[i].clone())
+        }
+        i += 1;
+    }
+    if config.image.is_none() && config.replay.is_none() {
+        return Err((FlagError::MissingImage, usize::MAX));
+    }
+    Ok((config, remaining))
+}
+
+/* Draws the position reached after `--move` moves of the game, its last position by default, and the moves leading to it for a replay */
+pub fn run_export(config: &ExportConfig, record: &mut GameRecord, suggestion: bool) -> Result<(), PlacementError> {
+    if let Some(game) = config.game.as_ref() {
+        record.load(game)?;
+    }
+    if let Some(move_number) = config.move_number {
+        if !record.jump_to(move_number) {
+            return Err(PlacementError::IncorrectMoveNumber)
+        }
+    }
+    let finished = matches!(record.get_players().get_game_status(), GameStatus::Finished(_));
+    let hint = match suggestion && !finished {
+        true => Some(record.get_bot_move(DEPTH_SUGGESTION).0),
+        false => None
+    };
+    if let Some(path) = config.image.as_ref() {
+        export_image(record, hint, path)?;
+        println!("Position written in {}", path);
+    }
+    if let Some(path) = config.replay.as_ref() {
+        let frames = export_replay(record, path, config.delay)?;
+        println!("Replay of {} frames written in {}", frames, path);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Player, PlayerType};
+    use crate::capture_rules::CaptureRules;
+    use crate::clock::TimeControl;
+
+    fn new_record(capture_rules: CaptureRules, moves: &[Input]) -> GameRecord {
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, capture_rules, 1);
+        let mut record = GameRecord::new(Board::new(9, 9, 5, capture_rules), players, TimeControl::Unlimited);
+        for input in moves.iter() {
+            record.play(*input, 0, (None, None)).unwrap();
+        }
+        record
+    }
+
+    fn attribute<'a>(element: &'a str, name: &str) -> &'a str {
+        element.split(&format!(" {}=\"", name)).nth(1).and_then(|x| x.split('"').next()).unwrap()
+    }
+
+    #[test]
+    fn svg_draws_the_stones_their_numbers_and_the_last_move() {
+        let record = new_record(CaptureRules::new(0, true, false, false, true), &[(4, 4), (5, 5), (3, 3)]);
+        let svg = Picture::from_record(&record, None).to_svg();
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>\n"));
+        let circles: Vec<&str> = svg.lines().filter(|x| x.starts_with("<circle")).collect();
+        let stones = |fill: &str| circles.iter().filter(|x| x.ends_with(&format!("fill=\"{}\"/>", fill))).count();
+        assert_eq!((stones("#000000"), stones("#ffffff")), (2, 1));
+        let rings: Vec<&&str> = circles.iter().filter(|x| x.contains(&svg_paint("stroke", LAST_MOVE_COLOR))).collect();
+        assert_eq!(rings.len(), 1);
+        let numbers: Vec<&str> = svg.lines().filter(|x| x.starts_with("<text")).collect();
+        assert_eq!(numbers.len(), 3);
+        for (number, fill) in [("1", "#ffffff"), ("2", "#000000"), ("3", "#ffffff")] {
+            let text = numbers.iter().find(|x| x.ends_with(&format!(">{}</text>", number))).unwrap();
+            assert!(text.contains(&format!("fill=\"{}\"", fill)));
+        }
+        let last = numbers.iter().find(|x| x.ends_with(">3</text>")).unwrap();
+        assert_eq!((attribute(rings[0], "cx"), attribute(rings[0], "cy")), (attribute(last, "x"), attribute(last, "y")));
+    }
+
+    fn discs(picture: &Picture, fill: [f32; 4]) -> usize {
+        let view = View::new(&Board::new(9, 9, 5, CaptureRules::new(0, true, false, false, true)));
+        let radius = view.stone_disc((0, 0))[2];
+        picture.shapes.iter().filter(|x| matches!(x, Shape::Disc([_, _, r], color) if *r == radius && *color == fill)).count()
+    }
+
+    fn rings(picture: &Picture, stroke: [f32; 4]) -> usize {
+        picture.shapes.iter().filter(|x| matches!(x, Shape::Ring(_, _, color) if *color == stroke)).count()
+    }
+
+    #[test]
+    fn replay_has_a_frame_per_position_and_captured_stones_disappear() {
+        let mut record = new_record(CaptureRules::new(2, true, true, true, true), &[(1, 0), (2, 0), (5, 5), (3, 0), (4, 0)]);
+        record.jump_to(4);
+        let pictures = replay_pictures(&mut record);
+        assert_eq!(pictures.len(), 5);
+        assert_eq!(record.get_move_number(), 4);
+        assert_eq!(pictures.iter().map(|x| discs(x, [1.0, 1.0, 1.0, 1.0])).collect::<Vec<usize>>(), vec![0, 0, 1, 1, 2]);
+        record.jump_to(5);
+        let pictures = replay_pictures(&mut record);
+        assert_eq!(pictures.len(), 6);
+        assert_eq!(discs(&pictures[5], [1.0, 1.0, 1.0, 1.0]), 0);
+        assert_eq!(discs(&pictures[5], [0.0, 0.0, 0.0, 1.0]), 3);
+        let dir = std::env::temp_dir().join(format!("gomoku-replay-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("game.svg").to_string_lossy().to_string();
+        assert_eq!(export_replay(&mut record, &path, FRAME_DELAY), Ok(6));
+        let mut frames: Vec<String> = std::fs::read_dir(&dir).unwrap().map(|x| x.unwrap().file_name().to_string_lossy().to_string()).collect();
+        frames.sort();
+        assert_eq!(frames, (0..6).map(|i| format!("game-{:03}.svg", i)).collect::<Vec<String>>());
+        let path = dir.join("game.gif");
+        assert_eq!(export_replay(&mut record, &path.to_string_lossy(), FRAME_DELAY), Ok(6));
+        let decoder = image::codecs::gif::GifDecoder::new(std::io::BufReader::new(fs::File::open(&path).unwrap())).unwrap();
+        assert_eq!(image::AnimationDecoder::into_frames(decoder).count(), 6);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn winning_line_is_highlighted_on_the_last_frame() {
+        let moves = [(0, 0), (0, 8), (1, 0), (1, 8), (2, 0), (2, 8), (3, 0), (3, 8), (4, 0)];
+        let mut record = new_record(CaptureRules::new(0, true, false, false, true), &moves);
+        let pictures = replay_pictures(&mut record);
+        assert_eq!(pictures.len(), moves.len() + 1);
+        let (last, previous) = pictures.split_last().unwrap();
+        assert_eq!(rings(last, WINNING_LINE_COLOR), 5);
+        assert!(previous.iter().all(|x| rings(x, WINNING_LINE_COLOR) == 0));
+    }
+}
@@ -0,0 +1,133 @@
+use crate::board::{Board, Tile};
+use crate::players::Players;
+use crate::color::Color;
+
+#[derive(PartialEq, Clone, Copy, Debug, Eq, Hash)]
+pub enum GameStatus {
+    Playing,
+    PendingFive(Color),
+    Finished(Option<Color>)
+}
+
+/* Must be called once `color` has placed a stone and `next_player` has been called */
+pub fn update_game_status(board: &Board, players: &mut Players, color: Color) {
+    let status = next_game_status(board, players, color);
+    players.set_game_status(status);
+}
+
+fn next_game_status(board: &Board, players: &Players, color: Color) -> GameStatus {
+    let opponent = color.get_inverse_color();
+    let turn_passed = players.get_current_player().get_player_color() != color;
+    if let (true, Some(winner)) = players.is_finished() {
+        return GameStatus::Finished(Some(winner))
+    }
+    match players.get_game_status() {
+        GameStatus::Finished(winner) => return GameStatus::Finished(winner),
+        GameStatus::PendingFive(pending) if pending == opponent => {
+            if !turn_passed {
+                return pending_five(board, players, pending)
+            } else if board.has_alignement(opponent, true) {
+                return GameStatus::Finished(Some(opponent))
+            }
+        },
+        _ => ()
+    }
+    if board.has_alignement(color, true) {
+        if board.has_alignement(color, false) && !can_win_by_capture(board, players, opponent) {
+            return GameStatus::Finished(Some(color))
+        }
+        return pending_five(board, players, color)
+    } else if board.is_full() {
+        return GameStatus::Finished(None)
+    }
+    GameStatus::Playing
+}
+
+/* A five the player to move has no stone left to break is a win */
+fn pending_five(board: &Board, players: &Players, color: Color) -> GameStatus {
+    if board.has_legal_move(players) {
+        GameStatus::PendingFive(color)
+    } else {
+        GameStatus::Finished(Some(color))
+    }
+}
+
+fn can_win_by_capture(board: &Board, players: &Players, color: Color) -> bool {
+    let rules = players.get_capture_rules();
+    if !rules.is_enabled() || !rules.is_capture_win()
+        || players.get_current_player().get_player_color() != color
+        || players.get_player(color).get_player_captured() + rules.get_range() < players.get_captured_nb() {
+        return false
+    }
+    for (i, tile) in board.get_board().iter().enumerate() {
+        if *tile != Tile::Empty {
+            continue;
+        }
+        let input = board.get_input(i);
+        let mut new_board = board.clone();
+        let mut new_players = *players;
+        if new_board.add_value(input, &mut new_players).is_ok() && new_players.is_finished() == (true, Some(color)) {
+            return true
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Player, PlayerType};
+    use crate::capture_rules::CaptureRules;
+
+    const RULES: CaptureRules = CaptureRules::new(2, true, true, true, true);
+
+    fn players(stones_per_turn: usize) -> Players {
+        Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, RULES, stones_per_turn)
+    }
+
+    #[test]
+    fn breakable_five_waits_for_the_opponent() {
+        let mut board = Board::parse_rows("1X2/OXX1/1X2/4", 3, RULES).unwrap();
+        let mut players = players(1);
+        players.next_player();
+        update_game_status(&board, &mut players, Color::Black);
+        assert_eq!(players.get_game_status(), GameStatus::PendingFive(Color::Black));
+        board.add_value((3, 3), &mut players).unwrap();
+        players.next_player();
+        update_game_status(&board, &mut players, Color::White);
+        assert_eq!(players.get_game_status(), GameStatus::Finished(Some(Color::Black)));
+    }
+
+    #[test]
+    fn broken_five_goes_on() {
+        let mut board = Board::parse_rows("1X2/OXX1/1X2/4", 3, RULES).unwrap();
+        let mut players = players(1);
+        players.next_player();
+        update_game_status(&board, &mut players, Color::Black);
+        board.add_value((3, 1), &mut players).unwrap();
+        players.next_player();
+        update_game_status(&board, &mut players, Color::White);
+        assert_eq!(players.get_game_status(), GameStatus::Playing);
+    }
+
+    #[test]
+    fn pending_five_without_move_left_is_won() {
+        let board = Board::parse_rows("XXX/OOX/XOO", 3, RULES).unwrap();
+        let mut players = players(2);
+        players.set_game_status(GameStatus::PendingFive(Color::Black));
+        players.next_player();
+        players.next_player();
+        players.next_player();
+        update_game_status(&board, &mut players, Color::White);
+        assert_eq!(players.get_game_status(), GameStatus::Finished(Some(Color::Black)));
+    }
+
+    #[test]
+    fn full_board_without_five_is_a_draw() {
+        let board = Board::parse_rows("XOX/XOO/OXX", 3, RULES).unwrap();
+        let mut players = players(1);
+        players.next_player();
+        update_game_status(&board, &mut players, Color::Black);
+        assert_eq!(players.get_game_status(), GameStatus::Finished(None));
+    }
+}
@@ -0,0 +1,302 @@
+use std::fmt;
+use std::fs;
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, Input};
+use crate::color::Color;
+use crate::players::{Algorithm, PlayerType, Players};
+use crate::rules::GameStatus;
+use crate::game_record::GameRecord;
+use crate::clock::{Clock, TimeControl, average_time};
+use crate::notation::{format_input, parse_notation};
+use crate::error::{FlagError, PlacementError};
+use crate::leakser::MINMAX_DEPTH_LIMIT;
+
+const MATCH_GAMES: usize = 10;
+
+/* The evaluation weights are the fixed pattern scores of matching_cases, two configurations only differ by their search */
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct EngineConfig {
+    algorithm: Algorithm,
+    depth: Option<usize>,
+    time_control: Option<TimeControl>
+}
+
+impl fmt::Display for EngineConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self.algorithm {
+            Algorithm::Pvs => "pvs",
+            Algorithm::Minimax => "minimax"
+        };
+        match self.depth {
+            Some(depth) => write!(f, "{}:{}", algorithm, depth)?,
+            None => write!(f, "{}", algorithm)?
+        };
+        match self.time_control {
+            Some(time_control) => write!(f, "@{}", time_control),
+            None => Ok(())
+        }
+    }
+}
+
+impl EngineConfig {
+    /* "<algorithm>[:<depth>][@<time control>]", the depth given with -d is used when omitted */
+    pub fn parse(value: &str) -> Result<EngineConfig, FlagError> {
+        let (value, time_control) = match value.split_once('@') {
+            Some((value, time_control)) => match TimeControl::parse(time_control) {
+                Some(time_control) => (value, Some(time_control)),
+                None => return Err(FlagError::IncorrectValue)
+            },
+            None => (value, None)
+        };
+        let mut parts = value.splitn(2, ':');
+        let algorithm = match parts.next().unwrap_or("").to_lowercase().as_str() {
+            "pvs" => Algorithm::Pvs,
+            "bot" | "minimax" => Algorithm::Minimax,
+            _ => return Err(FlagError::IncorrectValue)
+        };
+        let depth = match parts.next() {
+            Some(depth) => match depth.parse::<usize>() {
+                Ok(depth) if (1..=MINMAX_DEPTH_LIMIT).contains(&depth) => Some(depth),
+                Ok(_) => return Err(FlagError::IncorectDepth),
+                _ => return Err(FlagError::NoNumberValue)
+            },
+            None => None
+        };
+        Ok(EngineConfig { algorithm, depth, time_control })
+    }
+
+    pub fn get_algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn get_depth(&self, default_depth: usize) -> usize {
+        self.depth.unwrap_or(default_depth)
+    }
+
+    /* Time control of the engine, the one of the game when it has none */
+    pub fn get_time_control(&self, default_time_control: TimeControl) -> TimeControl {
+        self.time_control.unwrap_or(default_time_control)
+    }
+
+    fn with_default_depth(&self, default_depth: usize) -> EngineConfig {
+        EngineConfig { depth: Some(self.get_depth(default_depth)), ..*self }
+    }
+}
+
+pub struct MatchConfig {
+    games: usize,
+    engines: (EngineConfig, EngineConfig),
+    openings: Option<String>
+}
+
+/* Takes the match flags out of `flags`, what is left is given to the leakser */
+pub fn match_leakser(flags: &[String]) -> Result<(MatchConfig, Vec<String>), (FlagError, usize)> {
+    let mut config = MatchConfig {
+        games: MATCH_GAMES,
+        engines: (EngineConfig::parse("pvs").unwrap(), EngineConfig::parse("minimax").unwrap()),
+        openings: None
+    };
+    let mut remaining = vec![];
+    let mut i = 0;
+    while i < flags.len() {
+        match flags[i].as_str() {
+            "--games" | "--engine1" | "--engine2" | "--openings" => {
+                if i + 1 >= flags.len() {
+                    return Err((FlagError::FlagNeedValue, i));
+                }
+                let value = flags[i + 1].as_str();
+                match flags[i].as_str() {
+                    "--games" => match value.parse::<usize>() {
+                        Ok(games) if games > 0 => config.games = games,
+                        Ok(_) => return Err((FlagError::IncorrectValue, i + 1)),
+                        _ => return Err((FlagError::NoNumberValue, i + 1))
+                    },
+                    "--engine1" => config.engines.0 = EngineConfig::parse(value).map_err(|e| (e, i + 1))?,
+                    "--engine2" => config.engines.1 = EngineConfig::parse(value).map_err(|e| (e, i + 1))?,
+                    _ => config.openings = Some(value.to_string())
+                }
+                i += 1;
+            },
+            _ => remaining.push(flags[i].clone())
+        }
+        i += 1;
+    }
+    Ok((config, remaining))
+}
+
+/* One opening per line, moves written in the h8 notation, '#' starts a comment */
+pub fn read_openings(path: &str, board: &Board) -> Result<Vec<Vec<Input>>, PlacementError> {
+    let content = fs::read_to_string(path).map_err(|_| PlacementError::IncorrectFile)?;
+    content.lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| line.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .map(|word| parse_notation(word, board))
+            .collect())
+        .collect()
+}
+
+/* Plays a whole game between two bots given in color order, each one thinking on its own clock */
+pub fn play_game(board: &Board, players: Players, time_control: TimeControl, opening: &[Input], engines: (EngineConfig, EngineConfig), default_depth: usize) -> Result<GameRecord, PlacementError> {
+    /* The engines' clocks are the only ones charged, the record keeps the times without a clock of its own */
+    let mut record = GameRecord::new(board.clone(), players, TimeControl::Unlimited);
+    let mut clocks = (Clock::new(engines.0.get_time_control(time_control)), Clock::new(engines.1.get_time_control(time_control)));
+    for input in opening.iter() {
+        record.play(*input, 0, (None, None))?;
+    }
+    while !matches!(record.get_players().get_game_status(), GameStatus::Finished(_)) {
+        let color = record.get_players().get_current_player().get_player_color();
+        let (engine, clock) = match color {
+            Color::Black => (engines.0, &mut clocks.0),
+            Color::White => (engines.1, &mut clocks.1)
+        };
+        let now = Instant::now();
+        let (input, trees) = record.get_bot_move_on_clock(engine.get_depth(default_depth), clock);
+        let elapsed = now.elapsed().as_micros();
+        if !clock.spend(color, elapsed) {
+            record.lose_on_time();
+            break;
+        }
+        record.play(input, elapsed, trees)?;
+    }
+    Ok(record)
+}
+
+/* Keeps the side to move and the captures of the starting position */
+pub fn bot_players(players: &Players, black: Algorithm, white: Algorithm) -> Players {
+    let mut players = *players;
+    players.set_player_type(Color::Black, PlayerType::Bot(black));
+    players.set_player_type(Color::White, PlayerType::Bot(white));
+    players
+}
+
+pub fn get_winner(record: &GameRecord) -> Option<Color> {
+    match record.get_players().get_game_status() {
+        GameStatus::Finished(winner) => winner,
+        _ => None
+    }
+}
+
+fn score_to_elo(score: f64) -> f64 {
+    -400.0 * (1.0 / score - 1.0).log10()
+}
+
+/* Elo difference with its 95% confidence margin, None when one side scored every point */
+pub fn elo_difference(wins: usize, losses: usize, draws: usize) -> Option<(f64, f64)> {
+    let games = (wins + losses + draws) as f64;
+    let score = (wins as f64 + draws as f64 / 2.0) / games;
+    if games == 0.0 || score <= 0.0 || score >= 1.0 {
+        return None
+    }
+    let variance = (wins as f64 * (1.0 - score).powi(2)
+        + draws as f64 * (0.5 - score).powi(2)
+        + losses as f64 * score.powi(2)) / games;
+    let margin = 1.96 * (variance / games).sqrt();
+    let low = score_to_elo((score - margin).max(f64::EPSILON));
+    let high = score_to_elo((score + margin).min(1.0 - f64::EPSILON));
+    Some((score_to_elo(score), (high - low) / 2.0))
+}
+
+pub fn run_match(config: &MatchConfig, record: &GameRecord, default_depth: usize) -> Result<(), PlacementError> {
+    let board = record.get_board();
+    let players = record.get_players();
+    let time_control = record.get_clock().get_time_control();
+    let engines = (config.engines.0.with_default_depth(default_depth), config.engines.1.with_default_depth(default_depth));
+    let openings = match &config.openings {
+        Some(path) => read_openings(path, board)?,
+        None => vec![]
+    };
+    let openings = if openings.is_empty() { vec![vec![]] } else { openings };
+    let (mut wins, mut losses, mut draws) = (0, 0, 0);
+    let mut lengths = vec![];
+    let mut times: (Vec<u128>, Vec<u128>) = (vec![], vec![]);
+    for game in 0..config.games {
+        let opening = &openings[(game / 2) % openings.len()];
+        let (black, white) = if game % 2 == 0 { engines } else { (engines.1, engines.0) };
+        let game_players = bot_players(players, black.get_algorithm(), white.get_algorithm());
+        let record = match play_game(board, game_players, time_control, opening, (black, white), default_depth) {
+            Ok(record) => record,
+            Err(e) => {
+                println!("Game {}/{}: aborted, {}", game + 1, config.games, e);
+                continue;
+            }
+        };
+        let winner = get_winner(&record);
+        let engine1_color = if game % 2 == 0 { Color::Black } else { Color::White };
+        match winner {
+            Some(color) if color == engine1_color => wins += 1,
+            Some(_) => losses += 1,
+            None => draws += 1
+        };
+        let result = match winner {
+            Some(Color::Black) => "1-0",
+            Some(Color::White) => "0-1",
+            None => "1/2-1/2"
+        };
+        let moves = record.get_moves();
+        for (color, _, time) in moves.iter().skip(opening.len()) {
+            if *color == engine1_color {
+                times.0.push(*time);
+            } else {
+                times.1.push(*time);
+            }
+        }
+        lengths.push(moves.len());
+        let line: Vec<String> = moves.iter().map(|(_, input, _)| format_input(*input, board)).collect();
+        println!("Game {}/{}: {} ({}) - {} ({}) {} in {} moves: {}",
+            game + 1, config.games, black, Color::Black, white, Color::White, result, moves.len(), line.join(" "));
+    }
+    println!("\n{} vs {}: +{} -{} ={}", engines.0, engines.1, wins, losses, draws);
+    if !lengths.is_empty() {
+        println!("Average game length: {:.1} moves", lengths.iter().sum::<usize>() as f64 / lengths.len() as f64);
+    }
+    println!("Average move time: {} {:?} -- {} {:?}", engines.0, Duration::from_micros(average_time(&times.0) as u64),
+        engines.1, Duration::from_micros(average_time(&times.1) as u64));
+    match elo_difference(wins, losses, draws) {
+        Some((elo, margin)) => println!("Elo difference: {:.1} +/- {:.1}", elo, margin),
+        None => println!("Elo difference: cannot be estimated from these results")
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::Player;
+    use crate::capture_rules::CaptureRules;
+
+    #[test]
+    fn engine_is_written_as_parsed() {
+        for value in ["pvs", "minimax:3", "pvs:6@move:2", "minimax@300+5"].iter() {
+            assert_eq!(EngineConfig::parse(value).unwrap().to_string(), *value);
+        }
+        assert_eq!(EngineConfig::parse("pvs:4@soon"), Err(FlagError::IncorrectValue));
+    }
+
+    #[test]
+    fn engine_time_control_replaces_the_game_one() {
+        let engine = EngineConfig::parse("pvs@move:2").unwrap();
+        assert_eq!(engine.get_time_control(TimeControl::Unlimited), TimeControl::PerMove(2_000_000));
+        assert_eq!(EngineConfig::parse("pvs").unwrap().get_time_control(TimeControl::Unlimited), TimeControl::Unlimited);
+    }
+
+    fn play(time_control: &str, engines: (&str, &str)) -> GameRecord {
+        let capture_rules = CaptureRules::new(2, true, true, true, true);
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, capture_rules, 1);
+        let engines = (EngineConfig::parse(engines.0).unwrap(), EngineConfig::parse(engines.1).unwrap());
+        let players = bot_players(&players, engines.0.get_algorithm(), engines.1.get_algorithm());
+        play_game(&Board::new(9, 9, 5, capture_rules), players, TimeControl::parse(time_control).unwrap(), &[(4, 4), (4, 5), (5, 5)], engines, 1).unwrap()
+    }
+
+    #[test]
+    fn moves_are_only_charged_on_the_engine_clocks() {
+        let record = play("move:0.000001", ("minimax@300+0", "minimax@300+0"));
+        assert!(record.get_clock().is_unlimited());
+        assert!(record.get_moves().len() > 4);
+        let record = play("300+0", ("minimax", "minimax@move:0.000001"));
+        assert_eq!(record.get_moves().len(), 3);
+        assert_eq!(get_winner(&record), Some(Color::Black));
+    }
+}
@@ -0,0 +1,379 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::Board;
+use crate::color::Color;
+use crate::players::{Algorithm, Player, PlayerType, Players};
+use crate::rules::GameStatus;
+use crate::algo::{principal_variation, root_value};
+use crate::heuristic::heuristic;
+use crate::game_record::GameRecord;
+use crate::clock::TimeControl;
+use crate::notation::{format_input, parse_notation};
+use crate::position::{format_position, parse_position};
+use crate::self_play::bot_players;
+use crate::json::{object, parse_json, Json};
+use crate::leakser::MINMAX_DEPTH_LIMIT;
+use crate::error::{FlagError, NetworkError};
+
+const SERVE_PORT: u16 = 7878;
+const MAX_BODY_LENGTH: usize = 1 << 16;
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct ServeConfig {
+    port: u16
+}
+
+/* Takes the serve flags out of `flags`, what is left is given to the leakser */
+pub fn serve_leakser(flags: &[String]) -> Result<(ServeConfig, Vec<String>), (FlagError, usize)> {
+    let mut config = ServeConfig {
+        port: SERVE_PORT
+    };
+    let mut remaining = vec![];
+    let mut i = 0;
+    while i < flags.len() {
+        match flags[i].as_str() {
+            "--port" => {
+                if i + 1 >= flags.len() {
+                    return Err((FlagError::FlagNeedValue, i));
+                }
+                config.port = flags[i + 1].parse::<u16>().map_err(|_| (FlagError::NoNumberValue, i + 1))?;
+                i += 1;
+            },
+            _ => remaining.push(flags[i].clone())
+        }
+        i += 1;
+    }
+    Ok((config, remaining))
+}
+
+pub fn color_name(color: Color) -> Json {
+    Json::String(color.to_string().to_lowercase())
+}
+
+/*
+** The position of a request is its "position" string, the one given on the command line when omitted,
+** then its "moves" are played from there in the h8 notation
+*/
+fn request_record(request: &Json, default: &GameRecord, time_control: TimeControl) -> Result<GameRecord, String> {
+    let algorithm = match request.get("engine").map(|x| x.as_str()) {
+        None => Algorithm::basic_algorithm(),
+        Some(Some("pvs")) => Algorithm::Pvs,
+        Some(Some("minimax")) | Some(Some("bot")) => Algorithm::Minimax,
+        Some(_) => return Err("\"engine\" must be pvs or minimax".to_string())
+    };
+    let (board, players) = match request.get("position") {
+        None => (default.get_board().clone(), *default.get_players()),
+        Some(Json::String(position)) => parse_position(position, Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human))
+            .map_err(|e| e.to_string())?,
+        Some(_) => return Err("\"position\" must be a string".to_string())
+    };
+    let mut record = GameRecord::new(board, bot_players(&players, algorithm, algorithm), time_control);
+    let moves = match request.get("moves") {
+        None => vec![],
+        Some(Json::Array(moves)) => moves.clone(),
+        Some(_) => return Err("\"moves\" must be an array".to_string())
+    };
+    for value in moves.iter() {
+        let word = value.as_str().ok_or_else(|| "\"moves\" must only contain strings".to_string())?;
+        let input = parse_notation(word, record.get_board()).map_err(|e| format!("{}: {}", word, e))?;
+        record.play(input, 0, (None, None)).map_err(|e| format!("{}: {}", word, e))?;
+    }
+    Ok(record)
+}
+
+fn rules_description(board: &Board, players: &Players) -> Vec<Json> {
+    let capture_rules = board.get_capture_rules();
+    let mut lines = vec![format!("Align {} stones on a {}x{} board to win.", board.get_alignement_nb(), board.get_width(), board.get_height())];
+    if players.get_stones_per_turn() > 1 {
+        lines.push(format!("Each turn is {} stones.", players.get_stones_per_turn()));
+    }
+    let range = if capture_rules.is_exact_range() {
+        format!("exactly {}", capture_rules.get_range())
+    } else {
+        format!("1 to {}", capture_rules.get_range())
+    };
+    lines.push(format!("Flanking a run of {} opponent's stones captures them.", range));
+    if capture_rules.is_capture_win() {
+        lines.push(format!("Capturing {} stones wins the game.", players.get_captured_nb()));
+    }
+    if capture_rules.is_break_alignement() {
+        lines.push("An alignement only wins if the opponent can not break it by a capture.".to_string());
+    }
+    if !capture_rules.is_safe_flank() {
+        lines.push("Playing into a flanked position gets captured.".to_string());
+    }
+    if players.is_double_three_forbidden() {
+        lines.push("A move making two free-three alignements at once is forbidden.".to_string());
+    }
+    lines.into_iter().map(Json::String).collect()
+}
+
+fn rules(request: &Json, default: &GameRecord) -> Result<Json, String> {
+    let record = request_record(request, default, TimeControl::Unlimited)?;
+    let (board, players) = (record.get_board(), record.get_players());
+    let capture_rules = board.get_capture_rules();
+    Ok(object(vec![
+        ("position", Json::String(format_position(board, players))),
+        ("width", Json::Number(board.get_width() as f64)),
+        ("height", Json::Number(board.get_height() as f64)),
+        ("alignement", Json::Number(board.get_alignement_nb() as f64)),
+        ("captures_to_win", Json::Number(players.get_captured_nb() as f64)),
+        ("capture_range", Json::Number(capture_rules.get_range() as f64)),
+        ("stones_per_turn", Json::Number(players.get_stones_per_turn() as f64)),
+        ("exact_range", Json::Bool(capture_rules.is_exact_range())),
+        ("capture_win", Json::Bool(capture_rules.is_capture_win())),
+        ("break_alignement", Json::Bool(capture_rules.is_break_alignement())),
+        ("safe_flank", Json::Bool(capture_rules.is_safe_flank())),
+        ("description", Json::Array(rules_description(board, players)))
+    ]))
+}
+
+fn status(request: &Json, default: &GameRecord) -> Result<Json, String> {
+    let record = request_record(request, default, TimeControl::Unlimited)?;
+    Ok(status_json(&record))
+}
+
+pub fn status_json(record: &GameRecord) -> Json {
+    let players = record.get_players();
+    let (finished, winner, pending) = match players.get_game_status() {
+        GameStatus::Finished(winner) => (true, winner.map(color_name).unwrap_or(Json::Null), Json::Null),
+        GameStatus::PendingFive(color) => (false, Json::Null, color_name(color)),
+        GameStatus::Playing => (false, Json::Null, Json::Null)
+    };
+    object(vec![
+        ("position", Json::String(format_position(record.get_board(), players))),
+        ("finished", Json::Bool(finished)),
+        ("winner", winner),
+        ("pending_five", pending),
+        ("side", color_name(players.get_current_player().get_player_color())),
+        ("captures", Json::Array(vec![
+            Json::Number(players.get_player(Color::Black).get_player_captured() as f64),
+            Json::Number(players.get_player(Color::White).get_player_captured() as f64)
+        ]))
+    ])
+}
+
+fn check(request: &Json, default: &GameRecord) -> Result<Json, String> {
+    let record = request_record(request, default, TimeControl::Unlimited)?;
+    let word = request.get("move").and_then(|x| x.as_str()).ok_or_else(|| "\"move\" is missing".to_string())?;
+    let input = parse_notation(word, record.get_board()).map_err(|e| e.to_string())?;
+    let reason = match record.get_players().get_game_status() {
+        GameStatus::Finished(_) => Some("The game is finished".to_string()),
+        _ => record.get_board().check_add_value(input, record.get_players()).err().map(|e| e.to_string())
+    };
+    Ok(object(vec![
+        ("move", Json::String(format_input(input, record.get_board()))),
+        ("x", Json::Number(input.0 as f64)),
+        ("y", Json::Number(input.1 as f64)),
+        ("legal", Json::Bool(reason.is_none())),
+        ("reason", reason.map(Json::String).unwrap_or(Json::Null))
+    ]))
+}
+
+/* "depth" defaults to --depth, "time" is a clock as given to --clock and makes the search stop deepening in time */
+fn best_move(request: &Json, default: &GameRecord, default_depth: usize) -> Result<Json, String> {
+    let depth = match request.get("depth") {
+        None => default_depth,
+        Some(value) => match value.as_usize() {
+            Some(depth) if (1..=MINMAX_DEPTH_LIMIT).contains(&depth) => depth,
+            _ => return Err(FlagError::IncorectDepth.to_string())
+        }
+    };
+    let time_control = match request.get("time") {
+        None => TimeControl::Unlimited,
+        Some(value) => value.as_str().and_then(TimeControl::parse).ok_or_else(|| "\"time\" must be a clock such as \"move:5\"".to_string())?
+    };
+    let record = request_record(request, default, time_control)?;
+    if matches!(record.get_players().get_game_status(), GameStatus::Finished(_)) {
+        return Err("The game is finished".to_string())
+    }
+    Ok(search_json(&record, depth))
+}
+
+/* Searches the position of `record` which must not be finished, the score is the search value of the move for its player */
+pub fn search_json(record: &GameRecord, depth: usize) -> Json {
+    let color = record.get_players().get_current_player().get_player_color();
+    let now = Instant::now();
+    let (input, trees) = record.get_bot_move(depth);
+    let elapsed = now.elapsed();
+    let tree = match color {
+        Color::Black => trees.0,
+        Color::White => trees.1
+    };
+    let mut line = principal_variation(&tree);
+    let score = match root_value(&tree) {
+        Some(value) if line.first() == Some(&input) => value,
+        _ => {
+            let mut after = record.get_board().clone();
+            let mut players = *record.get_players();
+            after.add_value_checked(input, &mut players);
+            line = vec![input];
+            heuristic(&after, &players, color)
+        }
+    };
+    line.truncate(depth.max(1));
+    object(vec![
+        ("move", Json::String(format_input(input, record.get_board()))),
+        ("x", Json::Number(input.0 as f64)),
+        ("y", Json::Number(input.1 as f64)),
+        ("score", Json::Number(score as f64)),
+        ("pv", Json::Array(line.iter().map(|x| Json::String(format_input(*x, record.get_board()))).collect())),
+        ("depth", Json::Number(depth as f64)),
+        ("time_ms", Json::Number(elapsed.as_millis() as f64))
+    ])
+}
+
+fn respond(stream: &mut TcpStream, code: u16, body: &Json) {
+    let reason = match code {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error"
+    };
+    let body = body.to_string();
+    let _ = write!(stream, "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        code, reason, body.len(), body);
+    let _ = stream.flush();
+}
+
+fn error(message: &str) -> Json {
+    object(vec![("error", Json::String(message.to_string()))])
+}
+
+/* Method, path and JSON body of one request, an empty body being an empty object, or the code and message of the error */
+fn read_request(stream: &TcpStream) -> Result<(String, String, Json), (u16, String)> {
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader.read_line(&mut line).map_err(|e| (400, e.to_string()))?;
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let (method, path) = match words.as_slice() {
+        [method, path, _] => (method.to_string(), path.split('?').next().unwrap_or("").to_string()),
+        _ => return Err((400, "Incorrect request line".to_string()))
+    };
+    let mut length = 0;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).map_err(|e| (400, e.to_string()))? == 0 || header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = value.trim().parse::<usize>().map_err(|_| (400, "Incorrect Content-Length".to_string()))?;
+            }
+        }
+    }
+    if length > MAX_BODY_LENGTH {
+        return Err((413, format!("The body must not exceed {} bytes", MAX_BODY_LENGTH)))
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body).map_err(|e| (400, e.to_string()))?;
+    let body = String::from_utf8(body).map_err(|_| (400, "The body is not UTF-8".to_string()))?;
+    let json = if body.trim().is_empty() {
+        Json::Object(vec![])
+    } else {
+        parse_json(&body).ok_or_else(|| (400, "The body is not valid JSON".to_string()))?
+    };
+    match json {
+        Json::Object(_) => Ok((method, path, json)),
+        _ => Err((400, "The body must be a JSON object".to_string()))
+    }
+}
+
+fn answer(method: &str, path: &str, request: &Json, default: &GameRecord, default_depth: usize) -> (u16, Json) {
+    let result = match (method, path) {
+        ("GET", "/rules") | ("POST", "/rules") => rules(request, default),
+        ("POST", "/bestmove") => best_move(request, default, default_depth),
+        ("POST", "/check") => check(request, default),
+        ("POST", "/status") => status(request, default),
+        (_, "/rules") | (_, "/bestmove") | (_, "/check") | (_, "/status") => return (405, error("Method not allowed")),
+        _ => return (404, error("Unknown endpoint, use /bestmove, /check, /status or /rules"))
+    };
+    match result {
+        Ok(json) => (200, json),
+        Err(e) => (400, error(&e))
+    }
+}
+
+/* A request making the engine panic is answered with a 500 instead of taking the server down */
+fn handle(mut stream: TcpStream, default: &GameRecord, default_depth: usize) {
+    let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+    let (method, path, request) = match read_request(&stream) {
+        Ok(request) => request,
+        Err((code, e)) => return respond(&mut stream, code, &error(&e))
+    };
+    let (code, json) = panic::catch_unwind(AssertUnwindSafe(|| answer(&method, &path, &request, default, default_depth)))
+        .unwrap_or_else(|_| (500, error("The request could not be handled")));
+    println!("{} {} {}", method, path, code);
+    respond(&mut stream, code, &json);
+}
+
+/* Answers on localhost, each connection in its own thread, the position of `record` being the default one */
+pub fn run_serve(config: &ServeConfig, record: &GameRecord, default_depth: usize) -> Result<(), NetworkError> {
+    let listener = TcpListener::bind(("127.0.0.1", config.port)).map_err(|_| NetworkError::CannotListen(config.port))?;
+    println!("Serving on http://127.0.0.1:{}", config.port);
+    thread::scope(|scope| {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    scope.spawn(move || handle(stream, record, default_depth));
+                },
+                Err(_) => return Err(NetworkError::Disconnected)
+            }
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(moves: &[&str], engine: &str, depth: usize) -> (GameRecord, Json) {
+        let board = Board::new(9, 9, 5, crate::capture_rules::CaptureRules::new(2, true, true, true, true));
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, board.get_capture_rules(), 1);
+        let default = GameRecord::new(board, players, TimeControl::Unlimited);
+        let request = object(vec![
+            ("engine", Json::String(engine.to_string())),
+            ("moves", Json::Array(moves.iter().map(|x| Json::String(x.to_string())).collect()))
+        ]);
+        let record = request_record(&request, &default, TimeControl::Unlimited).unwrap();
+        let json = search_json(&record, depth);
+        (record, json)
+    }
+
+    #[test]
+    fn winning_move_is_scored_as_a_win() {
+        let (mut record, json) = search(&["e5", "a1", "e6", "a2", "e7", "a3", "e8", "b9"], "minimax", 3);
+        assert_eq!(json.get("score"), Some(&Json::Number(i32::MAX as f64)));
+        if let Some(Json::Array(line)) = json.get("pv") {
+            for word in line.iter() {
+                record.play(parse_notation(word.as_str().unwrap(), record.get_board()).unwrap(), 0, (None, None)).unwrap();
+            }
+        }
+        assert_eq!(record.get_players().get_game_status(), GameStatus::Finished(Some(Color::Black)));
+    }
+
+    #[test]
+    fn principal_variation_is_a_legal_line_starting_with_the_move() {
+        for engine in ["minimax", "pvs"].iter() {
+            let (record, json) = search(&["e5", "d4", "f5"], engine, 3);
+            let line = match json.get("pv") {
+                Some(Json::Array(line)) => line.clone(),
+                _ => panic!("no pv")
+            };
+            assert!(!line.is_empty() && line.len() <= 3);
+            assert_eq!(line[0], *json.get("move").unwrap());
+            let mut record = record;
+            for word in line.iter() {
+                let input = parse_notation(word.as_str().unwrap(), record.get_board()).unwrap();
+                assert!(record.play(input, 0, (None, None)).is_ok());
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use colored::{ColoredString, Colorize};
+
+use crate::board::{Board, Input, Tile};
+use crate::players::Players;
+use crate::color::Color;
+use crate::notation::{column_name, row_name};
+
+fn render_tile(board: &Board, players: &Players, input: Input, last_played: bool, suggested: bool, finished: bool) -> ColoredString {
+    let tile = match board.get(input) {
+        Tile::Color(Color::Black) => Color::Black.to_string().bright_cyan().bold(),
+        Tile::Color(Color::White) => Color::White.to_string().bright_yellow().bold(),
+        _ if finished => ".".dimmed(),
+        _ if suggested => "+".bright_green().bold(),
+        _ if board.check_add_value(input, players) != Ok(()) => "x".red(),
+        _ => ".".dimmed()
+    };
+    if last_played {
+        tile.reversed()
+    } else {
+        tile
+    }
+}
+
+fn render_legend(board: &Board) -> String {
+    (0..board.get_width()).fold("     ".to_string(), |acc, x| format!("{}{:<3}", acc, column_name(x)))
+}
+
+fn render_captures(players: &Players, color: Color) -> String {
+    let turn = if players.get_current_player().get_player_color() == color { "<" } else { " " };
+    if players.get_capture_rules().is_enabled() {
+        format!("{} captured {}/{} {}", color, players.get_player(color).get_player_captured(), players.get_captured_nb(), turn)
+    } else {
+        format!("{} {}", color, turn)
+    }
+}
+
+/* Terminal counterpart of View::draw, every information drawn on the window is printed here */
+pub fn render(board: &Board, players: &Players, last_input: Option<&Input>, input_suggestion: Option<Input>, cursor: Option<Input>, finished: bool) -> String {
+    let border = "─".repeat(board.get_width() * 3);
+    let mut lines = vec![
+        render_legend(board),
+        format!("   ┌{}┐", border)
+    ];
+    for y in 0..board.get_height() {
+        let row: Vec<String> = (0..board.get_width())
+            .map(|x| {
+                let tile = render_tile(board, players, (x, y), last_input == Some(&(x, y)), input_suggestion == Some((x, y)), finished);
+                match cursor {
+                    Some(cursor) if cursor == (x, y) => tile.on_blue().to_string(),
+                    _ => tile.to_string()
+                }
+            })
+            .collect();
+        let row_label = row_name(y, board.get_height());
+        lines.push(format!("{:>2} │ {} │ {}", row_label, row.join("  "), row_label));
+    }
+    lines.push(format!("   └{}┘", border));
+    lines.push(render_legend(board));
+    lines.push(format!("{}     {}", render_captures(players, Color::Black), render_captures(players, Color::White)));
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Player, PlayerType};
+    use crate::position::parse_position;
+
+    fn render_plain(position: &str, last_input: Option<&Input>, finished: bool) -> Vec<String> {
+        colored::control::set_override(false);
+        let (board, players) = parse_position(position, Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human)).unwrap();
+        render(&board, &players, last_input, None, None, finished).lines().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn forbidden_points_are_marked_until_the_end() {
+        let position = "7/7/3X3/1XX4/3X3/7/7 x 0,0 a5,c10,r2,t1";
+        assert_eq!(render_plain(position, Some(&(2, 3)), false)[5], " 4 │ .  X  X  x  .  .  . │ 4");
+        assert_eq!(render_plain(position, Some(&(2, 3)), true)[5], " 4 │ .  X  X  .  .  .  . │ 4");
+        assert_eq!(render_plain("7/7/3X3/1XX4/3X3/7/7 x 0,0 a5,c10,r2,t2", None, false)[5], " 4 │ .  X  X  .  .  .  . │ 4");
+    }
+
+    #[test]
+    fn captures_are_shown_only_when_enabled() {
+        let lines = render_plain("7/7/7/3X3/7/7/7 o 2,4 a5,c5,r2,t1", None, false);
+        assert_eq!(lines[0], "     a  b  c  d  e  f  g  ");
+        assert_eq!(lines[11], "X captured 2/5       O captured 4/5 <");
+        assert_eq!(render_plain("7/7/7/3X3/7/7/7 x 0,0 a5,c5,r0,t1", None, false)[11], "X <     O  ");
+    }
+}
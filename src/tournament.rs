@@ -0,0 +1,471 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::sync::mpsc::channel;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::board::{Board, Input};
+use crate::color::Color;
+use crate::players::{Algorithm, Players};
+use crate::rules::GameStatus;
+use crate::game_record::GameRecord;
+use crate::clock::{Clock, TimeControl};
+use crate::notation::format_input;
+use crate::self_play::{EngineConfig, bot_players, read_openings};
+use crate::piskvork::ENGINE_TIMEOUT;
+use crate::agent::{Agent, Decision, EngineAgent, GameState, Limits, Trees};
+use crate::error::{EngineError, FlagError, PlacementError};
+
+const TOURNAMENT_GAMES: usize = 2;
+const TOURNAMENT_DIRECTORY: &str = "tournament";
+const PRIOR_DRAWS: f64 = 2.0;
+const RATING_ITERATIONS: usize = 1000;
+
+#[derive(PartialEq, Clone, Debug)]
+pub enum Engine {
+    Internal(EngineConfig),
+    External(String)
+}
+
+impl fmt::Display for Engine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Engine::Internal(config) => write!(f, "{}", config),
+            Engine::External(path) => write!(f, "exe:{}", path)
+        }
+    }
+}
+
+impl Engine {
+    /* "<algorithm>[:<depth>][@<time control>]" for our bots, "exe:<path>" for a piskvork engine */
+    pub fn parse(value: &str) -> Result<Engine, FlagError> {
+        if let Some(path) = value.strip_prefix("exe:") {
+            if path.is_empty() {
+                return Err(FlagError::IncorrectValue)
+            }
+            return Ok(Engine::External(path.to_string()))
+        }
+        EngineConfig::parse(value).map(Engine::Internal)
+    }
+
+    fn get_algorithm(&self) -> Algorithm {
+        match self {
+            Engine::Internal(config) => config.get_algorithm(),
+            Engine::External(_) => Algorithm::basic_algorithm()
+        }
+    }
+}
+
+pub struct TournamentConfig {
+    engines: Vec<Engine>,
+    games: usize,
+    gauntlet: bool,
+    threads: usize,
+    directory: String,
+    openings: Option<String>
+}
+
+/* Takes the tournament flags out of `flags`, what is left is given to the leakser */
+pub fn tournament_leakser(flags: &[String]) -> Result<(TournamentConfig, Vec<String>), (FlagError, usize)> {
+    let mut config = TournamentConfig {
+        engines: vec![],
+        games: TOURNAMENT_GAMES,
+        gauntlet: false,
+        threads: thread::available_parallelism().map(|x| x.get()).unwrap_or(1),
+        directory: TOURNAMENT_DIRECTORY.to_string(),
+        openings: None
+    };
+    let mut remaining = vec![];
+    let mut i = 0;
+    while i < flags.len() {
+        match flags[i].as_str() {
+            "--gauntlet" => config.gauntlet = !config.gauntlet,
+            "--engine" | "--games" | "--threads" | "--output" | "--openings" => {
+                if i + 1 >= flags.len() {
+                    return Err((FlagError::FlagNeedValue, i));
+                }
+                let value = flags[i + 1].as_str();
+                match flags[i].as_str() {
+                    "--engine" => config.engines.push(Engine::parse(value).map_err(|e| (e, i + 1))?),
+                    "--games" | "--threads" => match value.parse::<usize>() {
+                        Ok(0) => return Err((FlagError::IncorrectValue, i + 1)),
+                        Ok(number) if flags[i] == "--games" => config.games = number,
+                        Ok(number) => config.threads = number,
+                        _ => return Err((FlagError::NoNumberValue, i + 1))
+                    },
+                    "--output" => config.directory = value.to_string(),
+                    _ => config.openings = Some(value.to_string())
+                }
+                i += 1;
+            },
+            _ => remaining.push(flags[i].clone())
+        }
+        i += 1;
+    }
+    if config.engines.len() < 2 {
+        return Err((FlagError::NotEnoughEngines, usize::MAX));
+    }
+    Ok((config, remaining))
+}
+
+/* Our engines search on their own clock, a piskvork engine is given what is left of its clock as timeout */
+struct Contestant {
+    agent: Option<EngineAgent>,
+    depth: usize,
+    clock: Clock
+}
+
+fn new_contestant(engine: &Engine, board: &Board, time_control: TimeControl, default_depth: usize) -> Result<Contestant, EngineError> {
+    match engine {
+        Engine::Internal(config) => Ok(Contestant {
+            agent: None,
+            depth: config.get_depth(default_depth),
+            clock: Clock::new(config.get_time_control(time_control))
+        }),
+        Engine::External(path) => EngineAgent::start(path, board, ENGINE_TIMEOUT).map(|agent| Contestant {
+            agent: Some(agent),
+            depth: default_depth,
+            clock: Clock::new(time_control)
+        })
+    }
+}
+
+impl Contestant {
+    fn choose_move(&mut self, record: &GameRecord) -> Result<(Input, Trees), String> {
+        let agent = match self.agent.as_mut() {
+            Some(agent) => agent,
+            None => return Ok(record.get_bot_move_on_clock(self.depth, &self.clock))
+        };
+        let color = record.get_players().get_current_player().get_player_color();
+        let timeout = match self.clock.get_time_left(color) {
+            Some(left) => ENGINE_TIMEOUT.min(Duration::from_micros(left as u64)),
+            None => ENGINE_TIMEOUT
+        };
+        match agent.choose_move(&GameState::new(record), &Limits::new(self.depth, timeout)) {
+            Decision::Play(input, trees, _) => Ok((input, *trees)),
+            Decision::Failed(reason) => Err(reason),
+            Decision::Pending | Decision::Command(_) => unreachable!()
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Copy, Debug)]
+enum Outcome {
+    Winner(Option<Color>),
+    /* The opening cannot be played with these rules, the game counts for nobody */
+    Skipped
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Winner(Some(Color::Black)) => write!(f, "1-0"),
+            Outcome::Winner(Some(Color::White)) => write!(f, "0-1"),
+            Outcome::Winner(None) => write!(f, "1/2-1/2"),
+            Outcome::Skipped => write!(f, "*")
+        }
+    }
+}
+
+struct GameJob {
+    number: usize,
+    engines: (usize, usize),
+    opening: Vec<Input>
+}
+
+struct GameResult {
+    number: usize,
+    engines: (usize, usize),
+    outcome: Outcome,
+    moves: usize,
+    reason: Option<String>
+}
+
+/* A contestant that fails to answer, plays an illegal move or runs out of time forfeits the game */
+fn play_job(job: &GameJob, engines: &[Engine], board: &Board, players: &Players, time_control: TimeControl, default_depth: usize) -> (GameRecord, Outcome, Option<String>) {
+    let black = &engines[job.engines.0];
+    let white = &engines[job.engines.1];
+    let mut record = GameRecord::new(board.clone(), bot_players(players, black.get_algorithm(), white.get_algorithm()), TimeControl::Unlimited);
+    for input in job.opening.iter() {
+        if record.play(*input, 0, (None, None)).is_err() {
+            return (record, Outcome::Skipped, Some(format!("opening skipped: {}", PlacementError::IncorrectPlacement)))
+        }
+    }
+    let mut contestants = (
+        new_contestant(black, board, time_control, default_depth),
+        new_contestant(white, board, time_control, default_depth)
+    );
+    loop {
+        if let GameStatus::Finished(winner) = record.get_players().get_game_status() {
+            return (record, Outcome::Winner(winner), None)
+        }
+        let color = record.get_players().get_current_player().get_player_color();
+        let contestant = match color {
+            Color::Black => &mut contestants.0,
+            Color::White => &mut contestants.1
+        };
+        let now = Instant::now();
+        let result = match contestant {
+            Ok(contestant) => contestant.choose_move(&record).and_then(|(input, trees)| {
+                let elapsed = now.elapsed().as_micros();
+                if !contestant.clock.spend(color, elapsed) {
+                    return Err(EngineError::Timeout.to_string())
+                }
+                record.play(input, elapsed, trees).map_err(|_| EngineError::IllegalMove.to_string())
+            }),
+            Err(e) => Err(e.to_string())
+        };
+        if let Err(e) = result {
+            return (record, Outcome::Winner(Some(color.get_inverse_color())), Some(format!("{:?} \"{}\" forfeits: {}", color, color, e)))
+        }
+    }
+}
+
+fn save_game(path: &Path, record: &GameRecord, engines: (&Engine, &Engine), outcome: Outcome, reason: &Option<String>) -> Result<(), PlacementError> {
+    let mut content = format!("# {} ({}) - {} ({}) {}\n", engines.0, Color::Black, engines.1, Color::White, outcome);
+    if let Some(reason) = reason {
+        content += &format!("# {}\n", reason);
+    }
+    for (_, input, _) in record.get_moves().iter() {
+        content += &format!("{}\n", format_input(*input, record.get_board()));
+    }
+    fs::write(path, content).map_err(|_| PlacementError::IncorrectFile)
+}
+
+fn create_jobs(config: &TournamentConfig, openings: &[Vec<Input>]) -> VecDeque<GameJob> {
+    let mut pairings = vec![];
+    for i in 0..config.engines.len() {
+        for j in (i + 1)..config.engines.len() {
+            if !config.gauntlet || i == 0 {
+                pairings.push((i, j));
+            }
+        }
+    }
+    let mut jobs = VecDeque::new();
+    for (i, j) in pairings {
+        for game in 0..config.games {
+            jobs.push_back(GameJob {
+                number: jobs.len() + 1,
+                engines: if game % 2 == 0 { (i, j) } else { (j, i) },
+                opening: openings[(game / 2) % openings.len()].clone()
+            });
+        }
+    }
+    jobs
+}
+
+/* Bradley-Terry ratings through minorization-maximization, every pairing starts with virtual draws like bayeselo's prior */
+fn compute_ratings(scores: &[Vec<f64>], games: &[Vec<f64>]) -> Vec<f64> {
+    let n = scores.len();
+    let prior_games: Vec<Vec<f64>> = (0..n).map(|i| (0..n).map(|j| {
+        if games[i][j] > 0.0 { games[i][j] + PRIOR_DRAWS } else { 0.0 }
+    }).collect()).collect();
+    let prior_scores: Vec<f64> = (0..n).map(|i| (0..n).map(|j| {
+        if games[i][j] > 0.0 { scores[i][j] + PRIOR_DRAWS / 2.0 } else { 0.0 }
+    }).sum()).collect();
+    let mut gammas = vec![1.0; n];
+    for _ in 0..RATING_ITERATIONS {
+        let new_gammas: Vec<f64> = (0..n).map(|i| {
+            let denominator: f64 = (0..n).filter(|j| *j != i)
+                .map(|j| prior_games[i][j] / (gammas[i] + gammas[j]))
+                .sum();
+            if denominator > 0.0 { prior_scores[i] / denominator } else { gammas[i] }
+        }).collect();
+        let mean = new_gammas.iter().map(|x: &f64| x.ln()).sum::<f64>() / n as f64;
+        gammas = new_gammas.iter().map(|x| x / mean.exp()).collect();
+    }
+    gammas.iter().map(|x| 400.0 * x.log10()).collect()
+}
+
+/* Skipped games are left out of the scores, and so of the ratings */
+fn add_outcome(scores: &mut [Vec<f64>], games: &mut [Vec<f64>], (black, white): (usize, usize), outcome: Outcome) {
+    let black_score = match outcome {
+        Outcome::Winner(Some(Color::Black)) => 1.0,
+        Outcome::Winner(Some(Color::White)) => 0.0,
+        Outcome::Winner(None) => 0.5,
+        Outcome::Skipped => return
+    };
+    scores[black][white] += black_score;
+    scores[white][black] += 1.0 - black_score;
+    games[black][white] += 1.0;
+    games[white][black] += 1.0;
+}
+
+fn format_cross_table(engines: &[Engine], scores: &[Vec<f64>], games: &[Vec<f64>], ratings: &[f64]) -> String {
+    let name_width = engines.iter().map(|x| x.to_string().len()).max().unwrap_or(0);
+    let mut order: Vec<usize> = (0..engines.len()).collect();
+    order.sort_by(|a, b| ratings[*b].partial_cmp(&ratings[*a]).unwrap());
+    let header: String = order.iter().map(|x| format!("{:>8}", x + 1)).collect();
+    let mut lines = vec![format!("{:>3} {:<width$} {:>7} {:>8}{}", "#", "Engine", "Elo", "Score", header, width = name_width)];
+    for i in order.iter() {
+        let cells: String = order.iter().map(|j| {
+            if i == j || games[*i][*j] == 0.0 {
+                format!("{:>8}", "-")
+            } else {
+                format!("{:>8}", format!("{}/{}", scores[*i][*j], games[*i][*j]))
+            }
+        }).collect();
+        let score: f64 = scores[*i].iter().sum();
+        let total: f64 = games[*i].iter().sum();
+        lines.push(format!("{:>3} {:<width$} {:>7.1} {:>8}{}", i + 1, engines[*i].to_string(), ratings[*i], format!("{}/{}", score, total), cells, width = name_width));
+    }
+    lines.join("\n")
+}
+
+pub fn run_tournament(config: &TournamentConfig, record: &GameRecord, default_depth: usize) -> Result<(), PlacementError> {
+    let board = record.get_board();
+    let players = record.get_players();
+    let time_control = record.get_clock().get_time_control();
+    let openings = match &config.openings {
+        Some(path) => read_openings(path, board)?,
+        None => vec![]
+    };
+    let openings = if openings.is_empty() { vec![vec![]] } else { openings };
+    fs::create_dir_all(&config.directory).map_err(|_| PlacementError::IncorrectFile)?;
+    let jobs = create_jobs(config, &openings);
+    let total = jobs.len();
+    let jobs = Arc::new(Mutex::new(jobs));
+    let (sender, receiver) = channel();
+    let mut workers = vec![];
+    for _ in 0..config.threads.min(total) {
+        let jobs = Arc::clone(&jobs);
+        let sender = sender.clone();
+        let engines = config.engines.clone();
+        let directory = config.directory.clone();
+        let board = board.clone();
+        let players = *players;
+        workers.push(thread::spawn(move || {
+            loop {
+                let job = match jobs.lock().unwrap().pop_front() {
+                    Some(job) => job,
+                    None => break
+                };
+                let (record, outcome, reason) = play_job(&job, &engines, &board, &players, time_control, default_depth);
+                let path = Path::new(&directory).join(format!("game_{:04}.txt", job.number));
+                let saved = save_game(&path, &record, (&engines[job.engines.0], &engines[job.engines.1]), outcome, &reason);
+                let reason = match saved {
+                    Ok(_) => reason,
+                    Err(e) => Some(format!("{} {}", e, path.display()))
+                };
+                let result = GameResult {
+                    number: job.number,
+                    engines: job.engines,
+                    outcome,
+                    moves: record.get_moves().len(),
+                    reason
+                };
+                if sender.send(result).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+    drop(sender);
+    let n = config.engines.len();
+    let mut scores = vec![vec![0.0; n]; n];
+    let mut games = vec![vec![0.0; n]; n];
+    for (done, result) in receiver.iter().enumerate() {
+        let (black, white) = result.engines;
+        add_outcome(&mut scores, &mut games, result.engines, result.outcome);
+        println!("[{}/{}] Game {}: {} ({}) - {} ({}) {} in {} moves{}",
+            done + 1, total, result.number,
+            config.engines[black], Color::Black, config.engines[white], Color::White,
+            result.outcome, result.moves,
+            result.reason.map(|x| format!(" -- {}", x)).unwrap_or_default());
+    }
+    for worker in workers {
+        let _ = worker.join();
+    }
+    println!("\n{}", format_cross_table(&config.engines, &scores, &games, &compute_ratings(&scores, &games)));
+    println!("\nGames written in {}", config.directory);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::players::{Player, PlayerType};
+    use crate::capture_rules::CaptureRules;
+
+    fn new_config(engines: &[&str], gauntlet: bool) -> TournamentConfig {
+        TournamentConfig {
+            engines: engines.iter().map(|x| Engine::parse(x).unwrap()).collect(),
+            games: 2,
+            gauntlet,
+            threads: 1,
+            directory: TOURNAMENT_DIRECTORY.to_string(),
+            openings: None
+        }
+    }
+
+    fn play(opening: &[Input], engines: &[&str]) -> (GameRecord, Outcome, Option<String>) {
+        let capture_rules = CaptureRules::new(2, true, true, true, true);
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, PlayerType::Human), 10, capture_rules, 1);
+        let job = GameJob { number: 1, engines: (0, 1), opening: opening.to_vec() };
+        play_job(&job, &new_config(engines, false).engines, &Board::new(9, 9, 5, capture_rules), &players, TimeControl::Unlimited, 1)
+    }
+
+    #[test]
+    fn every_pairing_is_played_with_both_colors() {
+        let openings = vec![vec![(4, 4)], vec![(3, 3)]];
+        let jobs = create_jobs(&new_config(&["pvs", "minimax", "pvs:2"], false), &openings);
+        let pairings: Vec<(usize, usize)> = jobs.iter().map(|x| x.engines).collect();
+        assert_eq!(pairings, vec![(0, 1), (1, 0), (0, 2), (2, 0), (1, 2), (2, 1)]);
+        assert_eq!(jobs.iter().map(|x| x.number).collect::<Vec<_>>(), vec![1, 2, 3, 4, 5, 6]);
+        assert!(jobs.iter().all(|x| x.opening == vec![(4, 4)]));
+        let jobs = create_jobs(&new_config(&["pvs", "minimax", "pvs:2"], true), &openings);
+        assert_eq!(jobs.iter().map(|x| x.engines).collect::<Vec<_>>(), vec![(0, 1), (1, 0), (0, 2), (2, 0)]);
+    }
+
+    #[test]
+    fn ratings_follow_the_scores() {
+        let games = vec![vec![0.0, 4.0], vec![4.0, 0.0]];
+        let ratings = compute_ratings(&[vec![0.0, 2.0], vec![2.0, 0.0]], &games);
+        assert!(ratings[0].abs() < 1e-9 && ratings[1].abs() < 1e-9);
+        let ratings = compute_ratings(&[vec![0.0, 4.0], vec![0.0, 0.0]], &games);
+        assert!(ratings[0] > 0.0 && ratings[0].is_finite());
+        assert!((ratings[0] + ratings[1]).abs() < 1e-9);
+        let games = vec![vec![0.0, 2.0, 0.0], vec![2.0, 0.0, 2.0], vec![0.0, 2.0, 0.0]];
+        let ratings = compute_ratings(&[vec![0.0, 2.0, 0.0], vec![0.0, 0.0, 2.0], vec![0.0, 0.0, 0.0]], &games);
+        assert!(ratings[0] > ratings[1] && ratings[1] > ratings[2]);
+    }
+
+    #[test]
+    fn skipped_games_count_for_nobody() {
+        let (mut scores, mut games) = (vec![vec![0.0; 2]; 2], vec![vec![0.0; 2]; 2]);
+        add_outcome(&mut scores, &mut games, (1, 0), Outcome::Skipped);
+        assert_eq!((&scores, &games), (&vec![vec![0.0; 2]; 2], &vec![vec![0.0; 2]; 2]));
+        add_outcome(&mut scores, &mut games, (1, 0), Outcome::Winner(Some(Color::Black)));
+        add_outcome(&mut scores, &mut games, (0, 1), Outcome::Winner(None));
+        assert_eq!(scores, vec![vec![0.0, 0.5], vec![1.5, 0.0]]);
+        assert_eq!(games, vec![vec![0.0, 2.0], vec![2.0, 0.0]]);
+        let (record, outcome, reason) = play(&[(4, 4), (4, 4)], &["pvs", "minimax"]);
+        assert_eq!((outcome, outcome.to_string()), (Outcome::Skipped, "*".to_string()));
+        assert_eq!(record.get_moves().len(), 1);
+        assert!(reason.is_some());
+    }
+
+    #[test]
+    fn each_contestant_plays_on_its_own_clock() {
+        let (record, outcome, reason) = play(&[(4, 4), (4, 5), (5, 5)], &["minimax", "minimax@move:0.000001"]);
+        assert_eq!(outcome, Outcome::Winner(Some(Color::Black)));
+        assert_eq!(record.get_moves().len(), 3);
+        assert_eq!(reason, Some(format!("White \"O\" forfeits: {}", EngineError::Timeout)));
+        let (record, outcome, _) = play(&[(4, 4), (4, 5), (5, 5)], &["minimax@move:0.000001", "minimax"]);
+        assert_eq!(outcome, Outcome::Winner(Some(Color::White)));
+        assert_eq!(record.get_moves().len(), 4);
+    }
+
+    #[test]
+    fn cross_table_is_sorted_by_rating() {
+        let engines = new_config(&["pvs", "minimax:3"], false).engines;
+        let table = format_cross_table(&engines, &[vec![0.0, 0.5], vec![1.5, 0.0]], &[vec![0.0, 2.0], vec![2.0, 0.0]], &[-60.0, 60.0]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "  # Engine        Elo    Score       2       1");
+        assert_eq!(lines[1], "  2 minimax:3    60.0    1.5/2       -   1.5/2");
+        assert_eq!(lines[2], "  1 pvs         -60.0    0.5/2   0.5/2       -");
+    }
+}
@@ -0,0 +1,367 @@
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::time::Instant;
+
+use colored::Colorize;
+
+use crate::board::{Board, Input};
+use crate::color::Color;
+use crate::players::PlayerType;
+use crate::rules::GameStatus;
+use crate::game_record::GameRecord;
+use crate::agent::{Agents, Decision, GameState, Limits, Trees};
+use crate::heuristic::heuristic;
+use crate::notation::format_input;
+use crate::terminal_view::render;
+use crate::leakser::DEPTH_SUGGESTION;
+use crate::error::PlacementError;
+
+const MOVE_LIST_LEN: usize = 12;
+const ENGINE_OUTPUT_LEN: usize = 5;
+
+enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Char(char),
+    Eof
+}
+
+/* Puts the terminal in non canonical mode on an alternate screen, everything is restored on drop */
+struct RawMode {
+    saved: Option<String>
+}
+
+impl RawMode {
+    fn enable() -> RawMode {
+        let saved = Command::new("stty").arg("-g").stdin(Stdio::inherit()).output().ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string());
+        if saved.is_some() {
+            let _ = Command::new("stty").args(["-icanon", "-echo", "min", "1"]).stdin(Stdio::inherit()).status();
+        }
+        print!("\x1b[?1049h\x1b[?25l");
+        RawMode { saved }
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        if let Some(saved) = &self.saved {
+            let _ = Command::new("stty").arg(saved).stdin(Stdio::inherit()).status();
+        }
+    }
+}
+
+fn read_key(stdin: &mut io::Stdin) -> Key {
+    let mut next = || {
+        let mut byte = [0u8; 1];
+        match stdin.read(&mut byte) {
+            Ok(1) => Some(byte[0]),
+            _ => None
+        }
+    };
+    match next() {
+        None => Key::Eof,
+        Some(27) => match (next(), next()) {
+            (Some(b'['), Some(b'A')) => Key::Up,
+            (Some(b'['), Some(b'B')) => Key::Down,
+            (Some(b'['), Some(b'C')) => Key::Right,
+            (Some(b'['), Some(b'D')) => Key::Left,
+            _ => Key::Char('\x1b')
+        },
+        Some(b'\n') | Some(b'\r') | Some(b' ') => Key::Enter,
+        Some(c) => Key::Char(c as char)
+    }
+}
+
+fn print_seconds(us: u128) -> String {
+    format!("{:.1} s", us as f64 / 1_000_000.0)
+}
+
+struct Tui {
+    cursor: Input,
+    message: String,
+    engine_output: Vec<String>,
+    input_suggestion: Option<Input>,
+    turn_start: Instant,
+    agents: Agents,
+    limits: Limits,
+    failed: bool
+}
+
+impl Tui {
+    fn new(board: &Board, limits: &Limits, agents: Agents) -> Tui {
+        Tui {
+            cursor: (board.get_width() / 2, board.get_height() / 2),
+            message: String::new(),
+            engine_output: vec![],
+            input_suggestion: None,
+            turn_start: Instant::now(),
+            agents,
+            limits: *limits,
+            failed: false
+        }
+    }
+
+    fn move_cursor(&mut self, x: i32, y: i32, board: &Board) {
+        let new_x = (self.cursor.0 as i32 + x).clamp(0, board.get_width() as i32 - 1);
+        let new_y = (self.cursor.1 as i32 + y).clamp(0, board.get_height() as i32 - 1);
+        self.cursor = (new_x as usize, new_y as usize);
+    }
+
+    fn add_engine_output(&mut self, line: String) {
+        self.engine_output.push(line);
+        if self.engine_output.len() > ENGINE_OUTPUT_LEN {
+            self.engine_output.remove(0);
+        }
+    }
+
+    fn new_turn(&mut self) {
+        self.input_suggestion = None;
+        self.turn_start = Instant::now();
+    }
+
+    fn play(&mut self, record: &mut GameRecord, input: Input, time: u128, trees: Trees) -> bool {
+        let color = record.get_players().get_current_player().get_player_color();
+        match record.play(input, time, trees) {
+            Ok(_) => {
+                self.message = format!("{} played {}", color, format_input(input, record.get_board()));
+                self.new_turn();
+                self.agents.notify_move(&GameState::new(record), color, input, time);
+                true
+            },
+            Err(e) => {
+                self.message = e.to_string();
+                false
+            }
+        }
+    }
+
+    /* Move of a player that is not a human at this keyboard, nothing can be played anymore if its agent failed */
+    fn play_agent(&mut self, record: &mut GameRecord) {
+        let color = record.get_players().get_current_player().get_player_color();
+        let now = Instant::now();
+        match self.agents.choose_move(&GameState::new(record), &self.limits) {
+            Decision::Play(input, trees, time) => {
+                let elapsed = now.elapsed();
+                let time = time.unwrap_or(self.turn_start.elapsed().as_micros());
+                if self.play(record, input, time, *trees) {
+                    let eval = heuristic(record.get_board(), record.get_players(), color);
+                    self.add_engine_output(format!("{} {} in {:?}, eval {}", color, format_input(input, record.get_board()), elapsed, eval));
+                }
+            },
+            Decision::Failed(reason) => {
+                self.message = reason;
+                self.failed = true;
+            },
+            Decision::Pending | Decision::Command(_) => ()
+        }
+    }
+
+    fn undo(&mut self, record: &mut GameRecord) {
+        if record.get_players().has_remote_player() {
+            self.message = PlacementError::NetworkGame.to_string();
+            return
+        }
+        if !record.undo() {
+            self.message = PlacementError::NothingToUndo.to_string();
+            return
+        }
+        if record.get_players().get_current_player().get_player_type() != PlayerType::Human {
+            record.undo();
+        }
+        self.message = String::new();
+        self.new_turn();
+    }
+
+    fn redo(&mut self, record: &mut GameRecord) {
+        if record.get_players().has_remote_player() {
+            self.message = PlacementError::NetworkGame.to_string();
+        } else if record.redo() {
+            self.new_turn();
+        }
+    }
+
+    fn hint(&mut self, record: &GameRecord) {
+        let input = record.get_bot_move(DEPTH_SUGGESTION).0;
+        self.input_suggestion = Some(input);
+        self.add_engine_output(format!("hint {}", format_input(input, record.get_board())));
+    }
+
+    /* Remaining time with a time control, time spent otherwise */
+    fn clock_text(&self, record: &GameRecord, color: Color, spent: u128) -> String {
+        let clock = record.get_clock();
+        if clock.is_unlimited() {
+            return print_seconds(spent)
+        }
+        let players = record.get_players();
+        let thinking = !matches!(players.get_game_status(), GameStatus::Finished(_))
+            && players.get_current_player().get_player_color() == color;
+        clock.display(color, if thinking { self.turn_start.elapsed().as_micros() } else { 0 })
+    }
+
+    fn panel(&self, record: &GameRecord) -> Vec<String> {
+        let board = record.get_board();
+        let players = record.get_players();
+        let status = match players.get_game_status() {
+            GameStatus::Finished(Some(color)) => format!("BRAVO {:?} \"{}\"", color, color),
+            GameStatus::Finished(None) => "DRAW !".to_string(),
+            GameStatus::PendingFive(color) => format!("{} aligned, last chance to break it", color),
+            GameStatus::Playing => format!("{} to play", players.get_current_player().get_player_color())
+        };
+        let moves = record.get_moves();
+        let mut clocks = [0, 0];
+        for (color, _, time) in moves.iter() {
+            clocks[*color as usize] += time;
+        }
+        if !matches!(players.get_game_status(), GameStatus::Finished(_)) {
+            clocks[players.get_current_player().get_player_color() as usize] += self.turn_start.elapsed().as_micros();
+        }
+        let mut lines = vec![
+            status.bold().to_string(),
+            String::new(),
+            "Clocks".bold().to_string(),
+            format!("  {}  {}", Color::Black, self.clock_text(record, Color::Black, clocks[Color::Black as usize])),
+            format!("  {}  {}", Color::White, self.clock_text(record, Color::White, clocks[Color::White as usize])),
+            String::new(),
+            "Moves".bold().to_string()
+        ];
+        let first = moves.len().saturating_sub(MOVE_LIST_LEN);
+        for (i, (color, input, time)) in moves.iter().enumerate().skip(first) {
+            lines.push(format!("  {:>3}. {} {:<4} {}", i + 1, color, format_input(*input, board), print_seconds(*time)));
+        }
+        lines.push(String::new());
+        lines.push("Engine".bold().to_string());
+        for line in self.engine_output.iter() {
+            lines.push(format!("  {}", line));
+        }
+        lines.push(String::new());
+        lines.push("arrows move, enter play, u undo, r redo, h hint, q quit".dimmed().to_string());
+        lines.push(self.message.yellow().to_string());
+        lines
+    }
+
+    fn draw(&self, record: &GameRecord) {
+        let board = record.get_board();
+        let players = record.get_players();
+        let finished = matches!(players.get_game_status(), GameStatus::Finished(_));
+        let mut screen = String::from("\x1b[H\x1b[2J");
+        screen += &render(board, players, record.get_last_input(), self.input_suggestion, Some(self.cursor), finished);
+        let column = board.get_width() * 3 + 10;
+        for (i, line) in self.panel(record).iter().enumerate() {
+            screen += &format!("\x1b[{};{}H{}", i + 1, column, line);
+        }
+        print!("{}", screen);
+        let _ = io::stdout().flush();
+    }
+}
+
+pub fn run(record: &mut GameRecord, limits: &Limits, agents: Agents) {
+    let _raw_mode = RawMode::enable();
+    let mut tui = Tui::new(record.get_board(), limits, agents);
+    let mut stdin = io::stdin();
+    loop {
+        let player_type = record.get_players().get_current_player().get_player_type();
+        let finished = matches!(record.get_players().get_game_status(), GameStatus::Finished(_)) || tui.failed;
+        if !finished && player_type == PlayerType::Remote {
+            tui.message = "waiting for the opponent".to_string();
+        }
+        tui.draw(record);
+        if !finished && player_type != PlayerType::Human {
+            tui.play_agent(record);
+            continue;
+        }
+        if !finished {
+            tui.agents.opponent_thinking(&GameState::new(record), &tui.limits);
+        }
+        match read_key(&mut stdin) {
+            Key::Up => tui.move_cursor(0, -1, record.get_board()),
+            Key::Down => tui.move_cursor(0, 1, record.get_board()),
+            Key::Left => tui.move_cursor(-1, 0, record.get_board()),
+            Key::Right => tui.move_cursor(1, 0, record.get_board()),
+            Key::Enter if !finished => {
+                let cursor = tui.cursor;
+                let time = tui.turn_start.elapsed().as_micros();
+                tui.play(record, cursor, time, (None, None));
+            },
+            Key::Char('u') => tui.undo(record),
+            Key::Char('r') => tui.redo(record),
+            Key::Char('h') if !finished => tui.hint(record),
+            Key::Char('q') | Key::Eof => break,
+            _ => ()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use crate::players::{Algorithm, Player, Players};
+    use crate::capture_rules::CaptureRules;
+    use crate::clock::TimeControl;
+    use crate::agent::Interface;
+
+    fn new_tui(width: usize, height: usize, white: PlayerType) -> (Tui, GameRecord) {
+        let capture_rules = CaptureRules::new(2, true, true, true, true);
+        let players = Players::new(Player::new(Color::Black, PlayerType::Human), Player::new(Color::White, white), 10, capture_rules, 1);
+        let record = GameRecord::new(Board::new(width, height, 5, capture_rules), players, TimeControl::Unlimited);
+        let agents = Agents::new(Interface::FullScreen, None, None, (None, None));
+        (Tui::new(record.get_board(), &Limits::new(2, Duration::from_secs(1)), agents), record)
+    }
+
+    #[test]
+    fn cursor_starts_in_the_middle_and_stays_on_the_board() {
+        let (mut tui, record) = new_tui(9, 5, PlayerType::Human);
+        assert_eq!(tui.cursor, (4, 2));
+        tui.move_cursor(0, -3, record.get_board());
+        tui.move_cursor(7, 0, record.get_board());
+        assert_eq!(tui.cursor, (8, 0));
+        tui.move_cursor(-10, 10, record.get_board());
+        assert_eq!(tui.cursor, (0, 4));
+    }
+
+    #[test]
+    fn illegal_move_is_reported_and_not_played() {
+        let (mut tui, mut record) = new_tui(9, 9, PlayerType::Human);
+        assert!(tui.play(&mut record, (4, 4), 0, (None, None)));
+        assert_eq!(tui.message, "X played e5");
+        assert!(!tui.play(&mut record, (4, 4), 0, (None, None)));
+        assert_eq!(tui.message, PlacementError::NotEmpty.to_string());
+        assert_eq!(record.get_move_number(), 1);
+    }
+
+    #[test]
+    fn undo_against_a_bot_goes_back_to_the_human() {
+        let (mut tui, mut record) = new_tui(9, 9, PlayerType::Bot(Algorithm::Minimax));
+        tui.undo(&mut record);
+        assert_eq!(tui.message, PlacementError::NothingToUndo.to_string());
+        tui.play(&mut record, (4, 4), 0, (None, None));
+        tui.play_agent(&mut record);
+        assert_eq!(record.get_move_number(), 2);
+        assert_eq!(tui.engine_output.len(), 1);
+        tui.undo(&mut record);
+        assert_eq!(record.get_move_number(), 0);
+        tui.redo(&mut record);
+        assert_eq!(record.get_move_number(), 1);
+    }
+
+    #[test]
+    fn panel_lists_the_last_moves() {
+        colored::control::set_override(false);
+        let (mut tui, mut record) = new_tui(19, 19, PlayerType::Human);
+        for i in 0..14 {
+            tui.play(&mut record, (i, i % 2), 0, (None, None));
+        }
+        let panel = tui.panel(&record);
+        assert_eq!(panel[0], "X to play");
+        assert_eq!(panel[7], "    3. X c19  0.0 s");
+        assert_eq!(panel[7 + MOVE_LIST_LEN - 1], "   14. O n18  0.0 s");
+        assert_eq!(panel[7 + MOVE_LIST_LEN], "");
+    }
+}
@@ -1,13 +1,28 @@
 use crate::board::*;
 use crate::Players;
 use crate::color::{Color};
+use crate::notation::{column_name, format_input, row_name};
+use crate::game_record::{GameRecord, LineMove};
 use graphics::*;//::{Context, Graphics, CircleArc, Line};
 
+/* Sizes of the original fixed window, the header items are laid out in these units then scaled */
+const BASE_WINDOW_SIZE: f64 = 1000.0;
+const MARGIN: f64 = 100.0;
+const MIN_CELL_SIZE: f64 = 20.0;
+/* The move list on the right of the grid */
+const PANEL_WIDTH: f64 = 220.0;
+const ROW_HEIGHT: f64 = 24.0;
+const PANEL_COLOR: [f32; 4] = [0.97, 0.89, 0.71, 0.35];
+const SELECTED_COLOR: [f32; 4] = [0.97, 0.89, 0.71, 0.75];
+
 pub struct View {
     background_color: [f32; 4],
-    window_size: f64,
-    grid_start: f64,
-    grid_end: f64,
+    window_size: [f64; 2],
+    scale: f64,
+    grid_start_x: f64,
+    grid_start_y: f64,
+    grid_end_x: f64,
+    grid_end_y: f64,
     grid_thickness: f64,
     cell_size: f64,
     stone_size: f64,
@@ -17,39 +32,75 @@ pub struct View {
 }
 
 impl View {
+    /* The window starts big enough for the cells of large boards to stay readable, the margins growing with it */
     pub fn new(board: &Board) -> View {
-        let grid_start: f64 = 100.0;
-        let grid_end: f64 = 900.0;
-        let cell: f64 = (grid_end - grid_start) / board.get_size() as f64;
+        let longest = board.get_width().max(board.get_height()) as f64;
+        let size = (longest * MIN_CELL_SIZE / (1.0 - 2.0 * MARGIN / BASE_WINDOW_SIZE)).max(BASE_WINDOW_SIZE);
+        View::with_size(board, [size * (1.0 + PANEL_WIDTH / BASE_WINDOW_SIZE), size])
+    }
+
+    /* The grid is centered in what is left of the window once the header, the move list and a margin are removed */
+    pub fn with_size(board: &Board, window_size: [f64; 2]) -> View {
+        let scale = window_size[0].min(window_size[1]) / BASE_WINDOW_SIZE;
+        let margin = MARGIN * scale;
+        let grid_width = window_size[0] - PANEL_WIDTH * scale;
+        let cell: f64 = ((grid_width - 2.0 * margin) / board.get_width() as f64)
+            .min((window_size[1] - 2.0 * margin) / board.get_height() as f64)
+            .max(1.0);
         let stone: f64 = ((cell - 2.0) * 2.0) / 3.0;
+        let grid_start_x = (grid_width - cell * board.get_width() as f64) / 2.0;
+        let grid_start_y = margin + (window_size[1] - 2.0 * margin - cell * board.get_height() as f64) / 2.0;
         View {
             background_color: [0.35, 0.18, 0.0, 1.0], // Brown
-            window_size: 1000.0,
-            grid_start: grid_start,
-            grid_end: grid_end,
-            grid_thickness: 2.0,
+            window_size,
+            scale,
+            grid_start_x,
+            grid_start_y,
+            grid_end_x: grid_start_x + cell * board.get_width() as f64,
+            grid_end_y: grid_start_y + cell * board.get_height() as f64,
+            grid_thickness: (2.0 * scale).max(1.0),
             cell_size: cell,
-            stone_size: stone,
+            stone_size: stone.max(1.0),
             circle_start: 0.0,
             circle_end: 6.3,
-            imprecision: 2.0
+            imprecision: (2.0 * scale).max(1.0)
         }
     }
 
+    pub fn resize(&mut self, board: &Board, window_size: [f64; 2]) {
+        *self = View::with_size(board, window_size);
+    }
+
     pub fn get_background_color(&self) -> [f32; 4] {
         self.background_color
     }
 
-    pub fn get_window_size(&self) -> f64 {
+    pub fn get_window_size(&self) -> [f64; 2] {
         self.window_size
     }
 
-    pub fn get_grid_start(&self) -> f64 {
-        self.grid_start
+    pub fn get_scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn get_margin(&self) -> f64 {
+        MARGIN * self.scale
+    }
+
+    pub fn get_grid_start_x(&self) -> f64 {
+        self.grid_start_x
+    }
+
+    pub fn get_grid_start_y(&self) -> f64 {
+        self.grid_start_y
     }
 
-    pub fn get_grid_end(&self) -> f64 {
-        self.grid_end
+    pub fn get_grid_end_x(&self) -> f64 {
+        self.grid_end_x
+    }
+
+    pub fn get_grid_end_y(&self) -> f64 {
+        self.grid_end_y
     }
 
     pub fn get_cell_size(&self) -> f64 {
@@ -68,10 +119,80 @@ impl View {
         self.circle_end
     }
 
-    fn get_imprecision(&self) -> f64 {
+    pub fn get_grid_thickness(&self) -> f64 {
+        self.grid_thickness
+    }
+
+    pub fn get_imprecision(&self) -> f64 {
         self.imprecision
     }
 
+    /* A rectangle of the 1000px layout, in the coordinates of the current window */
+    pub fn scaled(&self, rect: [f64; 4]) -> [f64; 4] {
+        [rect[0] * self.scale, rect[1] * self.scale, rect[2] * self.scale, rect[3] * self.scale]
+    }
+
+    pub fn font_size(&self, size: f64) -> u32 {
+        (size * self.scale).round().max(1.0) as u32
+    }
+
+    pub fn get_reset_button(&self) -> [f64; 4] {
+        self.scaled([50.0, 20.0, 100.0, 50.0])
+    }
+
+    pub fn get_undo_button(&self) -> [f64; 4] {
+        self.scaled([200.0, 20.0, 100.0, 50.0])
+    }
+
+    pub fn get_player_toggle(&self, color: Color) -> [f64; 4] {
+        match color {
+            Color::Black => self.scaled([335.0, 40.0, 40.0, 50.0]),
+            Color::White => self.scaled([435.0, 40.0, 40.0, 50.0])
+        }
+    }
+
+    pub fn is_inside(rect: [f64; 4], mpos: [f64; 2]) -> bool {
+        mpos[0] >= rect[0] && mpos[0] <= rect[0] + rect[2] && mpos[1] >= rect[1] && mpos[1] <= rect[1] + rect[3]
+    }
+
+    fn get_panel(&self) -> [f64; 4] {
+        let width = PANEL_WIDTH * self.scale;
+        let margin = self.get_margin();
+        [self.window_size[0] - width, margin, width - margin * 0.2, self.window_size[1] - 2.0 * margin]
+    }
+
+    /* First row shown and number of rows, the list scrolls to keep the current move in the middle */
+    fn get_panel_rows(&self, current: usize, total: usize) -> (usize, usize) {
+        let count = ((self.get_panel()[3] / (ROW_HEIGHT * self.scale)) as usize).max(1);
+        let first = current.saturating_sub(count / 2).min(total.saturating_sub(count));
+        (first, count.min(total))
+    }
+
+    /* The move number of the row under the cursor, `total` counting the starting position */
+    pub fn move_list_at(&self, mpos: [f64; 2], current: usize, total: usize) -> Option<usize> {
+        let panel = self.get_panel();
+        let (first, count) = self.get_panel_rows(current, total);
+        if !View::is_inside(panel, mpos) {
+            return None
+        }
+        let row = ((mpos[1] - panel[1]) / (ROW_HEIGHT * self.scale)) as usize;
+        if row < count {
+            Some(first + row)
+        } else {
+            None
+        }
+    }
+
+    /* The intersection under the cursor, if it is on the grid */
+    pub fn input_at(&self, mpos: [f64; 2]) -> Option<Input> {
+        if mpos[0] > self.grid_start_x && mpos[0] < self.grid_end_x - self.imprecision
+            && mpos[1] > self.grid_start_y && mpos[1] < self.grid_end_y - self.imprecision {
+            Some((((mpos[0] - self.grid_start_x) / self.cell_size) as usize, ((mpos[1] - self.grid_start_y) / self.cell_size) as usize))
+        } else {
+            None
+        }
+    }
+
     pub fn white_color(&self, transparency: bool) -> [f32; 4] {
         if transparency {
             [0.75, 0.75, 0.75, 1.0]
@@ -90,13 +211,19 @@ impl View {
 
     fn circle_at_center(&self, input: Input) -> [f64; 4] {
         [
-            input.0 as f64 * self.get_cell_size() + self.get_stone_size() / 2.0 + self.get_grid_start(),
-            input.1 as f64 * self.get_cell_size() + self.get_stone_size() / 2.0 + self.get_grid_start(),
+            input.0 as f64 * self.get_cell_size() + self.get_stone_size() / 2.0 + self.get_grid_start_x(),
+            input.1 as f64 * self.get_cell_size() + self.get_stone_size() / 2.0 + self.get_grid_start_y(),
             self.get_stone_size() / 2.0,
             self.get_stone_size() / 2.0,
         ]
     }
 
+    /* Center and radius of the disc `draw_stone` paints at `input`, for the renderers working without a window */
+    pub fn stone_disc(&self, input: Input) -> [f64; 3] {
+        let rec = self.circle_at_center(input);
+        [rec[0] + rec[2] / 2.0, rec[1] + rec[3] / 2.0, (rec[2] + self.get_stone_size()) / 2.0]
+    }
+
     fn draw_circle<G: Graphics>(
         color: [f32; 4],
         radius: f64,
@@ -152,17 +279,16 @@ impl View {
             Color::Black => self.black_color(false),
             _ => self.white_color(false)
         };
-        let player_input = ((mpos[0] as usize - self.get_grid_start() as usize) / self.get_cell_size() as usize, (mpos[1] as usize - self.get_grid_start() as usize) / self.get_cell_size() as usize);
-        let player_pos = board.from_input(player_input);
-        if mpos[0] > self.get_grid_start() && mpos[0] < self.get_grid_end() - self.get_imprecision()
-            && mpos[1] > self.get_grid_start() && mpos[1] < self.get_grid_end() - self.get_imprecision() && !finished {
-                self.draw_stone(
-                    context,
-                    graphics,
-                    color,
-                    self.circle_at_center(player_input),
-                    self.get_stone_size()
-                );
+        let hovered = self.input_at(mpos);
+        let player_pos = hovered.map_or(usize::MAX, |input| board.from_input(input));
+        if let (Some(player_input), false) = (hovered, finished) {
+            self.draw_stone(
+                context,
+                graphics,
+                color,
+                self.circle_at_center(player_input),
+                self.get_stone_size()
+            );
         }
         for (i, stone) in board.get_board().iter().enumerate() {
             if input_suggestion != None && board.get_input(i) == input_suggestion.unwrap() && i != player_pos && !finished {
@@ -179,40 +305,120 @@ impl View {
     }
 
     fn draw_buttons<G: Graphics>(&self, context: &Context, graphics: &mut G) {
-        Rectangle::new_round([0.97, 0.89, 0.71, 0.75], 15.0)
-            .draw([50.0, 20.0, 100.0, 50.0], &context.draw_state, context.transform, graphics);
-        Rectangle::new_round([0.97, 0.89, 0.71, 0.75], 15.0)
-            .draw([200.0, 20.0, 100.0, 50.0], &context.draw_state, context.transform, graphics);
+        Rectangle::new_round([0.97, 0.89, 0.71, 0.75], 15.0 * self.scale)
+            .draw(self.get_reset_button(), &context.draw_state, context.transform, graphics);
+        Rectangle::new_round([0.97, 0.89, 0.71, 0.75], 15.0 * self.scale)
+            .draw(self.get_undo_button(), &context.draw_state, context.transform, graphics);
+    }
+
+    /* Centered on [x, y], clipped if the font is missing a glyph */
+    fn draw_text<G: Graphics, C: CharacterCache<Texture = G::Texture>>(text: &str, color: [f32; 4], size: u32, center: [f64; 2], glyphs: &mut C, context: &Context, graphics: &mut G) {
+        let width = glyphs.width(size, text).unwrap_or(0.0);
+        let _ = Text::new_color(color, size).draw(
+            text,
+            glyphs,
+            &context.draw_state,
+            context.transform.trans(center[0] - width / 2.0, center[1] + size as f64 * 0.35),
+            graphics
+        );
+    }
+
+    /* Columns are named under the grid and rows on both of its sides, as in the terminal */
+    fn draw_labels<G: Graphics, C: CharacterCache<Texture = G::Texture>>(&self, board: &Board, glyphs: &mut C, context: &Context, graphics: &mut G) {
+        let size = (self.get_cell_size() * 0.4).min(self.get_margin() * 0.3).max(1.0);
+        let gap = size * 1.2;
+        for x in 0..board.get_width() {
+            let x_axe = x as f64 * self.get_cell_size() + self.get_cell_size() / 2.0 + self.get_grid_start_x();
+            View::draw_text(&column_name(x), self.black_color(false), size as u32, [x_axe, self.get_grid_end_y() + gap / 2.0], glyphs, context, graphics);
+        }
+        for y in 0..board.get_height() {
+            let y_axe = y as f64 * self.get_cell_size() + self.get_cell_size() / 2.0 + self.get_grid_start_y();
+            let name = row_name(y, board.get_height());
+            View::draw_text(&name, self.black_color(false), size as u32, [self.get_grid_start_x() - gap, y_axe], glyphs, context, graphics);
+            View::draw_text(&name, self.black_color(false), size as u32, [self.get_grid_end_x() + gap, y_axe], glyphs, context, graphics);
+        }
+    }
+
+    fn draw_move_numbers<G: Graphics, C: CharacterCache<Texture = G::Texture>>(&self, board: &Board, numbers: &[Option<usize>], glyphs: &mut C, context: &Context, graphics: &mut G) {
+        for (i, number) in numbers.iter().enumerate() {
+            if let (Some(number), Tile::Color(color)) = (number, board.get_ref(board.get_input(i))) {
+                let ink = match color {
+                    Color::Black => self.white_color(false),
+                    Color::White => self.black_color(false)
+                };
+                let [x, y, radius] = self.stone_disc(board.get_input(i));
+                let size = if *number < 100 { radius * 1.1 } else { radius * 0.8 };
+                View::draw_text(&number.to_string(), ink, size.max(1.0) as u32, [x, y], glyphs, context, graphics);
+            }
+        }
+    }
+
+    /* One row per move of `line`, captures followed by their count and moves with alternatives by their number */
+    pub fn draw_move_list<G: Graphics, C: CharacterCache<Texture = G::Texture>>(&self, board: &Board, line: &[LineMove], current: usize, glyphs: &mut C, context: &Context, graphics: &mut G) {
+        let panel = self.get_panel();
+        let row_height = ROW_HEIGHT * self.scale;
+        let (first, count) = self.get_panel_rows(current, line.len() + 1);
+        Rectangle::new_round(PANEL_COLOR, 15.0 * self.scale)
+            .draw(panel, &context.draw_state, context.transform, graphics);
+        for number in first..first + count {
+            let y = panel[1] + (number - first) as f64 * row_height;
+            if number == current {
+                Rectangle::new_round(SELECTED_COLOR, 8.0 * self.scale)
+                    .draw([panel[0], y, panel[2], row_height], &context.draw_state, context.transform, graphics);
+            }
+            let text = match number.checked_sub(1).map(|index| line[index]) {
+                None => "start".to_string(),
+                Some((color, input, captured, variations)) => {
+                    let mut text = format!("{}. {} {}", number, color, format_input(input, board));
+                    if captured > 0 {
+                        text += &format!("  x{}", captured);
+                    }
+                    if variations > 1 {
+                        text += &format!("  ({})", variations);
+                    }
+                    text
+                }
+            };
+            let size = self.font_size(16.0);
+            let _ = Text::new_color(self.black_color(false), size).draw(
+                &text,
+                glyphs,
+                &context.draw_state,
+                context.transform.trans(panel[0] + 12.0 * self.scale, y + (row_height + size as f64 * 0.7) / 2.0),
+                graphics
+            );
+        }
     }
 
-    fn draw_grid<G: Graphics>(&self, board: &Board, context: &Context, graphics: &mut G) {
-        for i in 0..board.get_size() {
-            let x_axe: f64 = i as f64 * self.get_cell_size() + self.get_cell_size() / 2.0 + self.get_grid_start();
-            if x_axe < self.get_grid_end() {
+    fn draw_grid<G: Graphics, C: CharacterCache<Texture = G::Texture>>(&self, board: &Board, glyphs: &mut C, context: &Context, graphics: &mut G) {
+        self.draw_labels(board, glyphs, context, graphics);
+        for i in 0..board.get_width() {
+            let x_axe: f64 = i as f64 * self.get_cell_size() + self.get_cell_size() / 2.0 + self.get_grid_start_x();
+            if x_axe < self.get_grid_end_x() {
                 View::draw_line(
                     self.black_color(false),
                     self.grid_thickness,
                     [
                         x_axe,
-                        self.get_grid_start(),
+                        self.get_grid_start_y(),
                         x_axe,
-                        self.get_grid_end() - self.get_imprecision()
+                        self.get_grid_end_y() - self.get_imprecision()
                     ],
                     context,
                     graphics
                 );
             }
         }
-        for i in 0..board.get_size() {
-            let y_axe: f64 = i as f64 * self.get_cell_size() + self.get_cell_size() / 2.0 + self.get_grid_start();
-            if y_axe < self.get_grid_end() {
+        for i in 0..board.get_height() {
+            let y_axe: f64 = i as f64 * self.get_cell_size() + self.get_cell_size() / 2.0 + self.get_grid_start_y();
+            if y_axe < self.get_grid_end_y() {
                 View::draw_line(
                     self.black_color(false),
                     self.grid_thickness,
                     [
-                        self.get_grid_start(),
+                        self.get_grid_start_x(),
                         y_axe,
-                        self.get_grid_end() - self.get_imprecision(),
+                        self.get_grid_end_x() - self.get_imprecision(),
                         y_axe,
                     ],
                     context,
@@ -222,9 +428,113 @@ impl View {
         }
     }
 
-    pub fn draw<G: Graphics>(&self, board: &Board, players: &Players, context: &Context, graphics: &mut G, mpos: [f64; 2], finished: bool, last_input: Option<&Input>, input_suggestion: Option<Input>) {
-        self.draw_grid(board, context, graphics);
-        self.draw_stones(board, context, graphics, players, last_input, finished, input_suggestion, mpos);
+    pub fn draw<G: Graphics, C: CharacterCache<Texture = G::Texture>>(&self, record: &GameRecord, context: &Context, graphics: &mut G, glyphs: &mut C, mpos: [f64; 2], finished: bool, input_suggestion: Option<Input>, show_numbers: bool) {
+        let board = record.get_board();
+        self.draw_grid(board, glyphs, context, graphics);
+        self.draw_stones(board, context, graphics, record.get_players(), record.get_last_input(), finished, input_suggestion, mpos);
+        if show_numbers {
+            self.draw_move_numbers(board, &record.get_move_numbers(), glyphs, context, graphics);
+        }
         self.draw_buttons(context, graphics);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture_rules::CaptureRules;
+
+    fn new_board(width: usize, height: usize) -> Board {
+        Board::new(width, height, 5, CaptureRules::new(2, true, true, true, true))
+    }
+
+    /* The grid is square celled, left of the move list and between the margins */
+    fn assert_grid_fits(view: &View, board: &Board) {
+        let [width, height] = view.get_window_size();
+        let cell = view.get_cell_size();
+        assert!((view.get_grid_end_x() - view.get_grid_start_x() - cell * board.get_width() as f64).abs() < 1e-6);
+        assert!((view.get_grid_end_y() - view.get_grid_start_y() - cell * board.get_height() as f64).abs() < 1e-6);
+        assert!(view.get_grid_start_x() >= view.get_margin() - 1e-6);
+        assert!(view.get_grid_end_x() <= width - PANEL_WIDTH * view.get_scale() - view.get_margin() + 1e-6);
+        assert!(view.get_grid_start_y() >= view.get_margin() - 1e-6);
+        assert!(view.get_grid_end_y() <= height - view.get_margin() + 1e-6);
+    }
+
+    #[test]
+    fn rectangular_boards_fit_the_window() {
+        for (width, height) in [(19, 19), (30, 8), (8, 30), (60, 60)] {
+            let board = new_board(width, height);
+            let view = View::new(&board);
+            assert_grid_fits(&view, &board);
+            assert!(view.get_cell_size() >= MIN_CELL_SIZE - 1e-6);
+        }
+        let view = View::new(&new_board(19, 19));
+        assert_eq!(view.get_window_size(), [BASE_WINDOW_SIZE + PANEL_WIDTH, BASE_WINDOW_SIZE]);
+        assert_eq!(view.get_scale(), 1.0);
+    }
+
+    #[test]
+    fn layout_follows_the_window_size() {
+        let board = new_board(15, 10);
+        let mut view = View::new(&board);
+        for window_size in [[2000.0, 600.0], [600.0, 2000.0], [400.0, 300.0]] {
+            view.resize(&board, window_size);
+            assert_eq!(view.get_window_size(), window_size);
+            assert_eq!(view.get_scale(), window_size[0].min(window_size[1]) / BASE_WINDOW_SIZE);
+            assert_grid_fits(&view, &board);
+            assert_eq!(view.get_reset_button(), view.scaled([50.0, 20.0, 100.0, 50.0]));
+        }
+        let small = View::with_size(&board, [400.0, 300.0]);
+        let big = View::with_size(&board, [800.0, 600.0]);
+        assert!((big.get_cell_size() / small.get_cell_size() - 2.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn cursor_is_turned_into_the_intersection_under_it() {
+        let board = new_board(12, 7);
+        for window_size in [[1220.0, 1000.0], [700.0, 900.0], [1600.0, 500.0]] {
+            let view = View::with_size(&board, window_size);
+            let cell = view.get_cell_size();
+            for input in [(0, 0), (11, 0), (0, 6), (11, 6), (5, 3)] {
+                let center = [view.get_grid_start_x() + (input.0 as f64 + 0.5) * cell, view.get_grid_start_y() + (input.1 as f64 + 0.5) * cell];
+                assert_eq!(view.input_at(center), Some(input));
+            }
+            assert_eq!(view.input_at([view.get_grid_start_x() - 1.0, view.get_grid_start_y() + cell]), None);
+            assert_eq!(view.input_at([view.get_grid_end_x() + 1.0, view.get_grid_start_y() + cell]), None);
+            assert_eq!(view.input_at([view.get_grid_start_x() + cell, view.get_grid_end_y() + 1.0]), None);
+        }
+    }
+
+    #[test]
+    fn move_list_scrolls_to_keep_the_current_move_in_the_middle() {
+        let view = View::with_size(&new_board(19, 19), [1220.0, 1000.0]);
+        let count = ((view.get_panel()[3] / ROW_HEIGHT) as usize).max(1);
+        assert_eq!(view.get_panel_rows(0, 5), (0, 5));
+        assert_eq!(view.get_panel_rows(4, 5), (0, 5));
+        assert_eq!(view.get_panel_rows(0, 100), (0, count));
+        assert_eq!(view.get_panel_rows(50, 100), (50 - count / 2, count));
+        assert_eq!(view.get_panel_rows(99, 100), (100 - count, count));
+        let small = View::with_size(&new_board(19, 19), [610.0, 500.0]);
+        assert_eq!(small.get_panel_rows(99, 100).1, count);
+        let tall = View::with_size(&new_board(19, 19), [700.0, 1400.0]);
+        assert!(tall.get_panel_rows(0, 100).1 > count);
+        assert_eq!(tall.get_panel_rows(0, 3), (0, 3));
+    }
+
+    #[test]
+    fn click_on_the_move_list_selects_the_row_under_it() {
+        for window_size in [[1220.0, 1000.0], [700.0, 1400.0], [2400.0, 600.0]] {
+            let view = View::with_size(&new_board(19, 19), window_size);
+            let panel = view.get_panel();
+            let row = ROW_HEIGHT * view.get_scale();
+            let x = panel[0] + panel[2] / 2.0;
+            assert_eq!(view.move_list_at([x, panel[1] + row / 2.0], 0, 5), Some(0));
+            assert_eq!(view.move_list_at([x, panel[1] + row * 4.5], 0, 5), Some(4));
+            assert_eq!(view.move_list_at([x, panel[1] + row * 5.5], 0, 5), None);
+            let (first, _) = view.get_panel_rows(150, 200);
+            assert_eq!(view.move_list_at([x, panel[1] + row * 2.5], 150, 200), Some(first + 2));
+            assert_eq!(view.move_list_at([panel[0] - 1.0, panel[1] + row / 2.0], 0, 5), None);
+            assert_eq!(view.move_list_at([x, panel[1] - 1.0], 0, 5), None);
+        }
+    }
+}